@@ -1,4 +1,7 @@
-use crate::fs::{as_sync, into_sync, DirBuilder, File, Metadata, OpenOptions, ReadDir};
+use crate::fs::{
+    as_sync, into_sync, CopyDirOptions, DirBuilder, File, FileLock, Glob, Metadata, OpenOptions,
+    ReadDir, Walk,
+};
 #[cfg(target_os = "wasi")]
 use async_std::os::wasi::{
     fs::OpenOptionsExt,
@@ -9,9 +12,12 @@ use async_std::{
     path::{Component, Path, PathBuf},
 };
 use cap_primitives::fs::{
-    canonicalize, copy, create_dir, hard_link, open, open_ambient_dir, open_dir, read_dir,
-    read_link, remove_dir, remove_dir_all, remove_file, remove_open_dir, remove_open_dir_all,
-    rename, set_permissions, stat, DirOptions, FollowSymlinks, Permissions,
+    canonicalize, copy, copy_dir_all, copy_dir_all_no_xdev, create_anonymous_file, create_dir,
+    follow_no_xdev, hard_link, link_file_at, open, open_ambient_dir, open_dir, open_dir_no_xdev,
+    open_no_xdev, read_dir, read_dir_no_xdev, read_link, remove_dir, remove_dir_all,
+    remove_dir_all_no_xdev, remove_file, remove_open_dir, remove_open_dir_all,
+    remove_open_dir_all_no_xdev, rename, set_permissions, stat, stat_no_xdev, via_parent_no_xdev,
+    DirAccess, DirOptions, FollowSymlinks, Permissions,
 };
 use std::fmt;
 #[cfg(unix)]
@@ -35,10 +41,21 @@ use {
 /// Unlike `async_std::fs`, this API's `canonicalize` returns a relative path since
 /// absolute paths don't interoperate well with the capability model.
 ///
+/// A `Dir` may be restricted to a [`DirAccess`] level with [`read_only`],
+/// [`append_only`], or [`create_only`], and pinned to the filesystem it's on
+/// with [`no_xdev`].
+///
 /// [functions in `async_std::fs`]: https://docs.rs/async-std/latest/async_std/fs/index.html#functions
 /// [`async_std::fs::File`]: https://docs.rs/async-std/latest/async_std/fs/struct.File.html
+/// [`DirAccess`]: enum.DirAccess.html
+/// [`read_only`]: #method.read_only
+/// [`append_only`]: #method.append_only
+/// [`create_only`]: #method.create_only
+/// [`no_xdev`]: #method.no_xdev
 pub struct Dir {
     std_file: fs::File,
+    access: DirAccess,
+    no_xdev: bool,
 }
 
 impl Dir {
@@ -53,15 +70,156 @@ impl Dir {
     /// process has access to.
     #[inline]
     pub unsafe fn from_std_file(std_file: fs::File) -> Self {
-        Self { std_file }
+        Self::from_std_file_with_policy(std_file, DirAccess::ReadWrite, false)
+    }
+
+    #[inline]
+    pub(crate) unsafe fn from_std_file_with_policy(
+        std_file: fs::File,
+        access: DirAccess,
+        no_xdev: bool,
+    ) -> Self {
+        Self {
+            std_file,
+            access,
+            no_xdev,
+        }
     }
 
     /// Consumes `self` and returns an `async_std::fs::File`.
+    ///
+    /// The returned `async_std::fs::File` isn't subject to the [`DirAccess`]
+    /// level of `self`.
+    ///
+    /// [`DirAccess`]: enum.DirAccess.html
     #[inline]
     pub fn into_std_file(self) -> fs::File {
         self.std_file
     }
 
+    /// Returns the [`DirAccess`] level that this `Dir` is restricted to.
+    ///
+    /// [`DirAccess`]: enum.DirAccess.html
+    #[inline]
+    pub fn access(&self) -> DirAccess {
+        self.access
+    }
+
+    /// Returns a new `Dir` for the same directory which can't create, write,
+    /// remove, or rename anything, or change permissions.
+    ///
+    /// Methods which would do so fail with `io::ErrorKind::PermissionDenied`,
+    /// including `open_with` with options which write, append, create, or
+    /// truncate. Directories opened from the returned `Dir`, and from its
+    /// `DirEntry`s, are restricted the same way, and `File`s opened from them
+    /// can't change their permissions or owner.
+    ///
+    /// The `Dir` and `File` extension traits in `cap-fs-ext` honor the
+    /// restriction too. It doesn't change the access mode of the underlying
+    /// handle, though, so anything with access to the raw handle isn't
+    /// restricted.
+    #[inline]
+    pub fn read_only(&self) -> io::Result<Self> {
+        self.restricted(DirAccess::ReadOnly)
+    }
+
+    /// Returns a new `Dir` for the same directory which can create new files
+    /// and directories and append to files, but can't otherwise modify
+    /// anything.
+    ///
+    /// See [`read_only`] and [`DirAccess::AppendOnly`] for details.
+    ///
+    /// [`read_only`]: #method.read_only
+    /// [`DirAccess::AppendOnly`]: enum.DirAccess.html#variant.AppendOnly
+    #[inline]
+    pub fn append_only(&self) -> io::Result<Self> {
+        self.restricted(DirAccess::AppendOnly)
+    }
+
+    /// Returns a new `Dir` for the same directory which can create new files
+    /// and directories, but can't otherwise modify anything.
+    ///
+    /// See [`read_only`] and [`DirAccess::CreateOnly`] for details.
+    ///
+    /// [`read_only`]: #method.read_only
+    /// [`DirAccess::CreateOnly`]: enum.DirAccess.html#variant.CreateOnly
+    #[inline]
+    pub fn create_only(&self) -> io::Result<Self> {
+        self.restricted(DirAccess::CreateOnly)
+    }
+
+    pub(crate) fn restricted(&self, access: DirAccess) -> io::Result<Self> {
+        let file = unsafe { as_sync(&self.std_file) };
+        let dir = file.try_clone()?.into();
+        let access = self.access.restrict(access);
+        Ok(unsafe { Self::from_std_file_with_policy(dir, access, self.no_xdev) })
+    }
+
+    /// Returns `true` if this `Dir` is pinned to the filesystem it's on by
+    /// [`no_xdev`].
+    ///
+    /// [`no_xdev`]: #method.no_xdev
+    #[inline]
+    pub fn is_no_xdev(&self) -> bool {
+        self.no_xdev
+    }
+
+    /// Returns a new `Dir` for the same directory which fails rather than
+    /// resolve a path through a mount point, so that everything accessed
+    /// through it is on the same filesystem as the directory itself.
+    ///
+    /// Methods which would cross a mount point fail with
+    /// `io::ErrorKind::PermissionDenied`. This applies to every component of
+    /// a path, including those reached through symlinks, and to the
+    /// `DirEntry`s yielded by `read_dir`, `walk`, and `glob`. Directories
+    /// opened from the returned `Dir`, and from its `DirEntry`s, are pinned
+    /// the same way. The extension traits in `cap-fs-ext` honor this too.
+    ///
+    /// On Linux this uses `openat2` with `RESOLVE_NO_XDEV` where it's
+    /// available; elsewhere, device numbers are compared at each step.
+    #[cfg(unix)]
+    #[inline]
+    pub fn no_xdev(&self) -> io::Result<Self> {
+        let file = unsafe { as_sync(&self.std_file) };
+        let dir = file.try_clone()?.into();
+        Ok(unsafe { Self::from_std_file_with_policy(dir, self.access, true) })
+    }
+
+    /// Calls `f` with the directory to resolve `path` in and what's left of
+    /// `path`. With [`no_xdev`], that's the parent of `path`, opened without
+    /// crossing a mount point, and its last component; otherwise it's `self`
+    /// and `path`.
+    ///
+    /// [`no_xdev`]: #method.no_xdev
+    fn via_parent<T, F>(&self, path: &std::path::Path, f: F) -> io::Result<T>
+    where
+        F: FnOnce(&std::fs::File, &std::path::Path) -> io::Result<T>,
+    {
+        let file = unsafe { as_sync(&self.std_file) };
+        if self.no_xdev {
+            via_parent_no_xdev(&file, path, f)
+        } else {
+            f(&file, path)
+        }
+    }
+
+    /// Like [`via_parent`], but first follows any symlinks in the last
+    /// component of `path`, so that `f` isn't given one.
+    ///
+    /// [`via_parent`]: #method.via_parent
+    fn via_parent_follow<T, F>(&self, path: &std::path::Path, f: F) -> io::Result<T>
+    where
+        F: FnOnce(&std::fs::File, &std::path::Path) -> io::Result<T>,
+    {
+        let file = unsafe { as_sync(&self.std_file) };
+        if self.no_xdev {
+            let path = follow_no_xdev(&file, path)?;
+            via_parent_no_xdev(&file, &path, f)
+        } else {
+            f(&file, path)
+        }
+    }
+
     /// Attempts to open a file in read-only mode.
     ///
     /// This corresponds to [`async_std::fs::File::open`], but only accesses paths
@@ -83,28 +241,42 @@ impl Dir {
     /// [`async_std::fs::OpenOptions::open`]: https://docs.rs/async-std/latest/async_std/fs/struct.OpenOptions.html#method.open
     #[inline]
     pub fn open_with<P: AsRef<Path>>(&self, path: P, options: &OpenOptions) -> io::Result<File> {
-        let file = unsafe { as_sync(&self.std_file) };
-        Self::_open_with(&file, path.as_ref(), options)
+        #[cfg(not(target_os = "wasi"))]
+        self.access.check_open(options)?;
+        // `async_std`'s `OpenOptions` can't be inspected, so only
+        // unrestricted `Dir`s can open files.
+        #[cfg(target_os = "wasi")]
+        self.access.check_modify()?;
+        let file = self._open_with(path.as_ref(), options)?;
+        Ok(unsafe { File::from_std_with_access(file.into(), self.access) })
     }
 
     #[cfg(not(target_os = "wasi"))]
-    fn _open_with(file: &std::fs::File, path: &Path, options: &OpenOptions) -> io::Result<File> {
-        let file = open(file, path.as_ref(), options)?.into();
-        Ok(unsafe { File::from_std(file) })
+    fn _open_with(&self, path: &Path, options: &OpenOptions) -> io::Result<std::fs::File> {
+        let file = unsafe { as_sync(&self.std_file) };
+        if self.no_xdev {
+            open_no_xdev(&file, path.as_ref(), options)
+        } else {
+            open(&file, path.as_ref(), options)
+        }
     }
 
     #[cfg(target_os = "wasi")]
-    fn _open_with(file: &std::fs::File, path: &Path, options: &OpenOptions) -> io::Result<File> {
-        let file = options.open_at(&self.std_file, path)?.into();
-        Ok(unsafe { File::from_std(file) })
+    fn _open_with(&self, path: &Path, options: &OpenOptions) -> io::Result<std::fs::File> {
+        let file = unsafe { as_sync(&self.std_file) };
+        options.open_at(&file, path)
     }
 
     /// Attempts to open a directory.
     #[inline]
     pub fn open_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<Self> {
         let file = unsafe { as_sync(&self.std_file) };
-        let dir = open_dir(&file, path.as_ref().as_ref())?.into();
-        Ok(unsafe { Self::from_std_file(dir) })
+        let dir = if self.no_xdev {
+            open_dir_no_xdev(&file, path.as_ref().as_ref())?
+        } else {
+            open_dir(&file, path.as_ref().as_ref())?
+        };
+        Ok(unsafe { Self::from_std_file_with_policy(dir.into(), self.access, self.no_xdev) })
     }
 
     /// Creates a new, empty directory at the provided path.
@@ -149,8 +321,10 @@ impl Dir {
     }
 
     fn _create_dir_one(&self, path: &Path, dir_options: &DirOptions) -> io::Result<()> {
-        let file = unsafe { as_sync(&self.std_file) };
-        create_dir(&file, path.as_ref(), dir_options)
+        self.access.check_create()?;
+        self.via_parent(path.as_ref(), |dir, path| {
+            create_dir(dir, path, dir_options)
+        })
     }
 
     fn _create_dir_all(&self, path: &Path, dir_options: &DirOptions) -> io::Result<()> {
@@ -194,6 +368,40 @@ impl Dir {
         )
     }
 
+    /// Creates a new file in `self` which has no name, opened for reading and
+    /// writing.
+    ///
+    /// The file is deleted when it's closed, unless it's first given a name
+    /// with [`link_file_at`]. On Linux this uses `O_TMPFILE`; elsewhere, or
+    /// on filesystems which don't support it, the file is created with a
+    /// temporary name which is removed immediately, and it can't be linked.
+    ///
+    /// [`link_file_at`]: #method.link_file_at
+    #[inline]
+    pub fn create_anonymous_file(&self) -> io::Result<File> {
+        self.access.check_create()?;
+        let dir = unsafe { as_sync(&self.std_file) };
+        let file = create_anonymous_file(&dir)?.into();
+        Ok(unsafe { File::from_std_with_access(file, self.access) })
+    }
+
+    /// Gives a file created by [`create_anonymous_file`] the name `path`.
+    ///
+    /// This fails if `path` already exists, if the file can't be linked
+    /// because it was created without `O_TMPFILE`, or if `file` already has a
+    /// name, either because it wasn't created by [`create_anonymous_file`] or
+    /// because it's already been linked.
+    ///
+    /// [`create_anonymous_file`]: #method.create_anonymous_file
+    #[inline]
+    pub fn link_file_at<P: AsRef<Path>>(&self, file: &File, path: P) -> io::Result<()> {
+        self.access.check_create()?;
+        let file = unsafe { as_sync(&file.std) };
+        self.via_parent(path.as_ref().as_ref(), |dir, path| {
+            link_file_at(&file, dir, path)
+        })
+    }
+
     /// Returns the canonical form of a path with all intermediate components normalized
     /// and symbolic links resolved.
     ///
@@ -204,7 +412,11 @@ impl Dir {
     #[inline]
     pub fn canonicalize<P: AsRef<Path>>(&self, path: P) -> io::Result<PathBuf> {
         let file = unsafe { as_sync(&self.std_file) };
-        canonicalize(&file, path.as_ref().as_ref()).map(PathBuf::from)
+        let path = canonicalize(&file, path.as_ref().as_ref())?;
+        if self.no_xdev && !path.as_os_str().is_empty() {
+            stat_no_xdev(&file, &path, FollowSymlinks::No)?;
+        }
+        Ok(path.into())
     }
 
     /// Copies the contents of one file to another. This function will also copy the permission
@@ -221,14 +433,60 @@ impl Dir {
         to_dir: &Self,
         to: Q,
     ) -> io::Result<u64> {
-        let from_file = unsafe { as_sync(&self.std_file) };
-        let to_file = unsafe { as_sync(&to_dir.std_file) };
-        copy(
-            &from_file,
-            from.as_ref().as_ref(),
-            &to_file,
-            to.as_ref().as_ref(),
-        )
+        to_dir.access.check_modify()?;
+        self.via_parent_follow(from.as_ref().as_ref(), |from_dir, from| {
+            to_dir.via_parent(to.as_ref().as_ref(), |to_dir, to| {
+                copy(from_dir, from, to_dir, to)
+            })
+        })
+    }
+
+    /// Recursively copies a directory and all of its contents to a new
+    /// directory, which may be in a different `Dir`.
+    ///
+    /// Regular files are copied as with [`copy`], and symlinks are recreated
+    /// without being followed. `to` must not already exist. Permissions are
+    /// preserved, and timestamps aren't; use [`copy_dir_all_with`] to
+    /// configure this.
+    ///
+    /// [`copy`]: #method.copy
+    /// [`copy_dir_all_with`]: #method.copy_dir_all_with
+    #[inline]
+    pub async fn copy_dir_all<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<u64> {
+        self.copy_dir_all_with(from, to_dir, to, &CopyDirOptions::new())
+            .await
+    }
+
+    /// Recursively copies a directory and all of its contents to a new
+    /// directory, with the options specified by `options`.
+    ///
+    /// See [`copy_dir_all`] for details.
+    ///
+    /// [`copy_dir_all`]: #method.copy_dir_all
+    #[inline]
+    pub async fn copy_dir_all_with<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        options: &CopyDirOptions,
+    ) -> io::Result<u64> {
+        to_dir.access.check_modify()?;
+        let copy_dir_all = if self.no_xdev {
+            copy_dir_all_no_xdev
+        } else {
+            copy_dir_all
+        };
+        self.via_parent_follow(from.as_ref().as_ref(), |from_dir, from| {
+            to_dir.via_parent(to.as_ref().as_ref(), |to_dir, to| {
+                copy_dir_all(from_dir, from, to_dir, to, options)
+            })
+        })
     }
 
     /// Creates a new hard link on a filesystem.
@@ -244,14 +502,12 @@ impl Dir {
         dst_dir: &Self,
         dst: Q,
     ) -> io::Result<()> {
-        let src_file = unsafe { as_sync(&self.std_file) };
-        let dst_file = unsafe { as_sync(&dst_dir.std_file) };
-        hard_link(
-            &src_file,
-            src.as_ref().as_ref(),
-            &dst_file,
-            dst.as_ref().as_ref(),
-        )
+        dst_dir.access.check_modify()?;
+        self.via_parent(src.as_ref().as_ref(), |src_dir, src| {
+            dst_dir.via_parent(dst.as_ref().as_ref(), |dst_dir, dst| {
+                hard_link(src_dir, src, dst_dir, dst)
+            })
+        })
     }
 
     /// Given a path, query the file system to get information about a file, directory, etc.
@@ -262,8 +518,7 @@ impl Dir {
     /// [`async_std::fs::metadata`]: https://docs.rs/async-std/latest/async_std/fs/fn.metadata.html
     #[inline]
     pub fn metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
-        let file = unsafe { as_sync(&self.std_file) };
-        stat(&file, path.as_ref().as_ref(), FollowSymlinks::Yes)
+        self.stat(path.as_ref(), FollowSymlinks::Yes)
     }
 
     /// Returns an iterator over the entries within `self`.
@@ -281,7 +536,47 @@ impl Dir {
     #[inline]
     pub fn read_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<ReadDir> {
         let file = unsafe { as_sync(&self.std_file) };
-        read_dir(&file, path.as_ref().as_ref()).map(|inner| ReadDir { inner })
+        let inner = if self.no_xdev {
+            read_dir_no_xdev(&file, path.as_ref().as_ref())?
+        } else {
+            read_dir(&file, path.as_ref().as_ref())?
+        };
+        Ok(ReadDir {
+            inner,
+            access: self.access,
+            no_xdev: self.no_xdev,
+        })
+    }
+
+    /// Returns an iterator over the entries within `self` and, recursively,
+    /// its subdirectories.
+    ///
+    /// This is built on [`entries`] and [`DirEntry::open_dir`], so it never
+    /// accesses anything outside of `self`. See [`Walk`] for the options it
+    /// supports.
+    ///
+    /// [`entries`]: #method.entries
+    /// [`DirEntry::open_dir`]: struct.DirEntry.html#method.open_dir
+    /// [`Walk`]: struct.Walk.html
+    #[inline]
+    pub fn walk(&self) -> io::Result<Walk> {
+        let file = unsafe { as_sync(&self.std_file) }.try_clone()?;
+        Ok(Walk::new(file, self.access, self.no_xdev))
+    }
+
+    /// Returns an iterator over the paths within `self` which match the glob
+    /// `pattern`, relative to `self`.
+    ///
+    /// This fails if `pattern` is absolute or malformed. See [`Glob`] for the
+    /// pattern syntax and the options it supports.
+    ///
+    /// [`Glob`]: struct.Glob.html
+    #[inline]
+    pub fn glob(&self, pattern: &str) -> io::Result<Glob> {
+        let file = unsafe { as_sync(&self.std_file) }.try_clone()?;
+        let mut inner = cap_primitives::fs::Glob::new(file, pattern)?;
+        inner.no_xdev(self.no_xdev);
+        Ok(Glob { inner })
     }
 
     /// Read the entire contents of a file into a bytes vector.
@@ -307,8 +602,8 @@ impl Dir {
     /// [`async_std::fs::read_link`]: https://docs.rs/async-std/latest/async_std/fs/fn.read_link.html
     #[inline]
     pub fn read_link<P: AsRef<Path>>(&self, path: P) -> io::Result<PathBuf> {
-        let file = unsafe { as_sync(&self.std_file) };
-        read_link(&file, path.as_ref().as_ref()).map(PathBuf::from)
+        self.via_parent(path.as_ref().as_ref(), read_link)
+            .map(PathBuf::from)
     }
 
     /// Read the entire contents of a file into a string.
@@ -333,8 +628,8 @@ impl Dir {
     /// [`async_std::fs::remove_dir`]: https://docs.rs/async-std/latest/async_std/fs/fn.remove_dir.html
     #[inline]
     pub fn remove_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
-        let file = unsafe { as_sync(&self.std_file) };
-        remove_dir(&file, path.as_ref().as_ref())
+        self.access.check_modify()?;
+        self.via_parent(path.as_ref().as_ref(), remove_dir)
     }
 
     /// Removes a directory at this path, after removing all its contents. Use carefully!
@@ -345,8 +640,13 @@ impl Dir {
     /// [`async_std::fs::remove_dir_all`]: https://docs.rs/async-std/latest/async_std/fs/fn.remove_dir_all.html
     #[inline]
     pub async fn remove_dir_all<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.access.check_modify()?;
         let file = unsafe { as_sync(&self.std_file) };
-        remove_dir_all(&file, path.as_ref().as_ref())
+        if self.no_xdev {
+            remove_dir_all_no_xdev(&file, path.as_ref().as_ref())
+        } else {
+            remove_dir_all(&file, path.as_ref().as_ref())
+        }
     }
 
     /// Remove the directory referenced by `self` and consume `self`.
@@ -356,6 +656,7 @@ impl Dir {
     /// to a concurrent rename of the directory.
     #[inline]
     pub fn remove_open_dir(self) -> io::Result<()> {
+        self.access.check_modify()?;
         let file = into_sync(self.std_file);
         remove_open_dir(file)
     }
@@ -368,8 +669,13 @@ impl Dir {
     /// to a concurrent rename of the directory.
     #[inline]
     pub fn remove_open_dir_all(self) -> io::Result<()> {
+        self.access.check_modify()?;
         let file = into_sync(self.std_file);
-        remove_open_dir_all(file)
+        if self.no_xdev {
+            remove_open_dir_all_no_xdev(file)
+        } else {
+            remove_open_dir_all(file)
+        }
     }
 
     /// Removes a file from a filesystem.
@@ -380,8 +686,8 @@ impl Dir {
     /// [`async_std::fs::remove_file`]: https://docs.rs/async-std/latest/async_std/fs/fn.remove_file.html
     #[inline]
     pub fn remove_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
-        let file = unsafe { as_sync(&self.std_file) };
-        remove_file(&file, path.as_ref().as_ref())
+        self.access.check_modify()?;
+        self.via_parent(path.as_ref().as_ref(), remove_file)
     }
 
     /// Rename a file or directory to a new name, replacing the original file if to already exists.
@@ -397,14 +703,13 @@ impl Dir {
         to_dir: &Self,
         to: Q,
     ) -> io::Result<()> {
-        let file = unsafe { as_sync(&self.std_file) };
-        let to_file = unsafe { as_sync(&to_dir.std_file) };
-        rename(
-            &file,
-            from.as_ref().as_ref(),
-            &to_file,
-            to.as_ref().as_ref(),
-        )
+        self.access.check_modify()?;
+        to_dir.access.check_modify()?;
+        self.via_parent(from.as_ref().as_ref(), |from_dir, from| {
+            to_dir.via_parent(to.as_ref().as_ref(), |to_dir, to| {
+                rename(from_dir, from, to_dir, to)
+            })
+        })
     }
 
     /// Changes the permissions found on a file or a directory.
@@ -415,8 +720,10 @@ impl Dir {
     ///
     /// [`async_std::fs::set_permissions`]: https://docs.rs/async-std/current/async_std/fs/fn.set_permissions.html
     pub fn set_permissions<P: AsRef<Path>>(&self, path: P, perm: Permissions) -> io::Result<()> {
-        let file = unsafe { as_sync(&self.std_file) };
-        set_permissions(&file, path.as_ref().as_ref(), perm)
+        self.access.check_modify()?;
+        self.via_parent_follow(path.as_ref().as_ref(), |dir, path| {
+            set_permissions(dir, path, perm)
+        })
     }
 
     /// Query the metadata about a file without following symlinks.
@@ -427,8 +734,16 @@ impl Dir {
     /// [`async_std::fs::symlink_metadata`]: https://docs.rs/async-std/latest/async_std/fs/fn.symlink_metadata.html
     #[inline]
     pub fn symlink_metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        self.stat(path.as_ref(), FollowSymlinks::No)
+    }
+
+    fn stat(&self, path: &Path, follow: FollowSymlinks) -> io::Result<Metadata> {
         let file = unsafe { as_sync(&self.std_file) };
-        stat(&file, path.as_ref().as_ref(), FollowSymlinks::No)
+        if self.no_xdev {
+            stat_no_xdev(&file, path.as_ref(), follow)
+        } else {
+            stat(&file, path.as_ref(), follow)
+        }
     }
 
     /// Write a slice as the entire contents of a file.
@@ -448,6 +763,62 @@ impl Dir {
         file.write_all(contents.as_ref()).await
     }
 
+    /// Writes a slice as the entire contents of a file, atomically replacing
+    /// any existing file at `path`.
+    ///
+    /// Unlike [`write`], which truncates the file and writes it in place,
+    /// this writes to a temporary file in the same directory, syncs it,
+    /// renames it over the target, and then syncs the directory, so that
+    /// after a crash the file has either its old or its new contents.
+    ///
+    /// [`write`]: #method.write
+    #[inline]
+    pub async fn atomic_write<P: AsRef<Path>, C: AsRef<[u8]>>(
+        &self,
+        path: P,
+        contents: C,
+    ) -> io::Result<()> {
+        use std::io::Write;
+        self.access.check_modify()?;
+        let mut atomic_file =
+            self.via_parent(path.as_ref().as_ref(), cap_primitives::fs::AtomicFile::new)?;
+        atomic_file.write_all(contents.as_ref())?;
+        atomic_file.commit()
+    }
+
+    /// Opens the lock file at `path`, creating it if it doesn't exist, and
+    /// acquires an exclusive advisory lock on it, waiting until it's
+    /// available.
+    ///
+    /// The lock is released when the returned [`FileLock`] is dropped. The
+    /// lock file's contents are left unchanged, and it isn't removed.
+    ///
+    /// [`FileLock`]: struct.FileLock.html
+    #[inline]
+    pub async fn lock_file<P: AsRef<Path>>(&self, path: P) -> io::Result<FileLock> {
+        let file = self.open_lock_file(path.as_ref())?;
+        file.lock_exclusive().await?;
+        Ok(FileLock::new(file))
+    }
+
+    /// Like [`lock_file`], but returns `Ok(None)` instead of waiting if the
+    /// lock is held by someone else.
+    ///
+    /// [`lock_file`]: #method.lock_file
+    #[inline]
+    pub fn try_lock_file<P: AsRef<Path>>(&self, path: P) -> io::Result<Option<FileLock>> {
+        let file = self.open_lock_file(path.as_ref())?;
+        Ok(if file.try_lock_exclusive()? {
+            Some(FileLock::new(file))
+        } else {
+            None
+        })
+    }
+
+    fn open_lock_file(&self, path: &Path) -> io::Result<File> {
+        self.open_with(path, OpenOptions::new().read(true).write(true).create(true))
+    }
+
     /// Creates a new symbolic link on a filesystem.
     ///
     /// This corresponds to [`async_std::os::unix::fs::symlink`], but only accesses paths
@@ -457,8 +828,10 @@ impl Dir {
     #[cfg(not(windows))]
     #[inline]
     pub fn symlink<P: AsRef<Path>, Q: AsRef<Path>>(&self, src: P, dst: Q) -> io::Result<()> {
-        let file = unsafe { as_sync(&self.std_file) };
-        symlink(src.as_ref().as_ref(), &file, dst.as_ref().as_ref())
+        self.access.check_modify()?;
+        self.via_parent(dst.as_ref().as_ref(), |dir, dst| {
+            symlink(src.as_ref().as_ref(), dir, dst)
+        })
     }
 
     /// Creates a new file symbolic link on a filesystem.
@@ -470,8 +843,10 @@ impl Dir {
     #[cfg(windows)]
     #[inline]
     pub fn symlink_file<P: AsRef<Path>, Q: AsRef<Path>>(&self, src: P, dst: Q) -> io::Result<()> {
-        let file = unsafe { as_sync(&self.std_file) };
-        symlink_file(src.as_ref().as_ref(), &file, dst.as_ref().as_ref())
+        self.access.check_modify()?;
+        self.via_parent(dst.as_ref().as_ref(), |dir, dst| {
+            symlink_file(src.as_ref().as_ref(), dir, dst)
+        })
     }
 
     /// Creates a new directory symlink on a filesystem.
@@ -483,8 +858,10 @@ impl Dir {
     #[cfg(windows)]
     #[inline]
     pub fn symlink_dir<P: AsRef<Path>, Q: AsRef<Path>>(&self, src: P, dst: Q) -> io::Result<()> {
-        let file = unsafe { as_sync(&self.std_file) };
-        symlink_dir(src.as_ref().as_ref(), &file, dst.as_ref().as_ref())
+        self.access.check_modify()?;
+        self.via_parent(dst.as_ref().as_ref(), |dir, dst| {
+            symlink_dir(src.as_ref().as_ref(), dir, dst)
+        })
     }
 
     /// Creates a new `UnixListener` bound to the specified socket.
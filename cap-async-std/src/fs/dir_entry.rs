@@ -4,6 +4,7 @@ use async_std::io;
 use async_std::os::unix::fs::DirEntryExt;
 #[cfg(target_os = "wasi")]
 use async_std::os::wasi::fs::DirEntryExt;
+use cap_primitives::fs::DirAccess;
 use std::{ffi::OsString, fmt};
 
 /// Entries returned by the `ReadDir` iterator.
@@ -25,6 +26,8 @@ use std::{ffi::OsString, fmt};
 /// [`async_std::fs::DirEntry`]: https://docs.rs/async-std/latest/async_std/fs/struct.DirEntry.html
 pub struct DirEntry {
     pub(crate) inner: cap_primitives::fs::DirEntry,
+    pub(crate) access: DirAccess,
+    pub(crate) no_xdev: bool,
 }
 
 impl DirEntry {
@@ -32,32 +35,35 @@ impl DirEntry {
     #[inline]
     pub fn open(&self) -> io::Result<File> {
         let file = self.inner.open()?.into();
-        Ok(unsafe { File::from_std(file) })
+        Ok(unsafe { File::from_std_with_access(file, self.access) })
     }
 
     /// Open the file with the given options.
     #[inline]
     pub fn open_with(&self, options: &OpenOptions) -> io::Result<File> {
+        self.access.check_open(options)?;
         let file = self.inner.open_with(options)?.into();
-        Ok(unsafe { File::from_std(file) })
+        Ok(unsafe { File::from_std_with_access(file, self.access) })
     }
 
     /// Open the entry as a directory.
     #[inline]
     pub fn open_dir(&self) -> io::Result<Dir> {
         let file = self.inner.open_dir()?.into();
-        Ok(unsafe { Dir::from_std_file(file) })
+        Ok(unsafe { Dir::from_std_file_with_policy(file, self.access, self.no_xdev) })
     }
 
     /// Removes the file from its filesystem.
     #[inline]
     pub fn remove_file(&self) -> io::Result<()> {
+        self.access.check_modify()?;
         self.inner.remove_file()
     }
 
     /// Removes the directory from its filesystem.
     #[inline]
     pub fn remove_dir(&self) -> io::Result<()> {
+        self.access.check_modify()?;
         self.inner.remove_dir()
     }
 
@@ -1,4 +1,4 @@
-use crate::fs::{as_sync, Metadata, Permissions};
+use crate::fs::{as_sync, DirAccess, Metadata, Permissions};
 #[cfg(unix)]
 use async_std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 #[cfg(target_os = "wasi")]
@@ -8,9 +8,9 @@ use async_std::os::windows::io::{AsRawHandle, FromRawHandle, IntoRawHandle, RawH
 use async_std::{
     fs,
     io::{self, IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write},
-    task::{Context, Poll},
+    task::{self, Context, Poll},
 };
-use cap_primitives::fs::is_read_write;
+use cap_primitives::fs::{is_read_write, lock, try_lock, unlock, FileLockKind};
 use std::{fmt, pin::Pin};
 
 /// A reference to an open file on a filesystem.
@@ -27,6 +27,7 @@ use std::{fmt, pin::Pin};
 /// [`Dir::create`]: struct.Dir.html#method.create
 pub struct File {
     pub(crate) std: fs::File,
+    pub(crate) access: DirAccess,
 }
 
 impl File {
@@ -38,7 +39,11 @@ impl File {
     /// process has access to.
     #[inline]
     pub unsafe fn from_std(std: fs::File) -> Self {
-        Self { std }
+        Self::from_std_with_access(std, DirAccess::ReadWrite)
+    }
+
+    pub(crate) unsafe fn from_std_with_access(std: fs::File, access: DirAccess) -> Self {
+        Self { std, access }
     }
 
     /// Consumes `self` and returns an `async_std::fs::File`.
@@ -47,6 +52,17 @@ impl File {
         self.std
     }
 
+    /// Returns the [`DirAccess`] level of the `Dir` this file was opened
+    /// from. Methods which change the file's metadata, such as
+    /// `set_permissions`, fail with `io::ErrorKind::PermissionDenied` unless
+    /// it's `DirAccess::ReadWrite`.
+    ///
+    /// [`DirAccess`]: enum.DirAccess.html
+    #[inline]
+    pub fn access(&self) -> DirAccess {
+        self.access
+    }
+
     // async_std doesn't have `with_options`.
 
     /// Attempts to sync all OS-internal metadata to disk.
@@ -100,11 +116,81 @@ impl File {
     /// [`async_std::fs::File::set_permissions`]: https://docs.rs/async-std/latest/async_std/fs/struct.File.html#method.set_permissions
     #[inline]
     pub async fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
+        self.access.check_modify()?;
         let sync = unsafe { as_sync(&self.std) };
         self.std
             .set_permissions(permissions_into_std(&sync, perm)?)
             .await
     }
+
+    /// Changes the owner and group of the underlying file. A `None` `uid` or
+    /// `gid` leaves the corresponding owner unchanged.
+    ///
+    /// This corresponds to [`std::os::unix::fs::fchown`].
+    ///
+    /// [`std::os::unix::fs::fchown`]: https://doc.rust-lang.org/std/os/unix/fs/fn.fchown.html
+    #[cfg(unix)]
+    #[inline]
+    pub fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+        self.access.check_modify()?;
+        let sync = unsafe { as_sync(&self.std) };
+        cap_primitives::fs::set_file_owner(&sync, uid, gid)
+    }
+
+    /// Acquires a shared advisory lock on the whole file, waiting until it's
+    /// available.
+    ///
+    /// Any number of shared locks may be held at once, as long as nobody
+    /// holds an exclusive lock. If this file already holds a lock, it's
+    /// converted to a shared lock. On Unix, this uses `flock`, so the lock
+    /// is shared by clones of this `File` and is released when the last of
+    /// them is closed.
+    #[inline]
+    pub async fn lock_shared(&self) -> io::Result<()> {
+        self.lock_blocking(FileLockKind::Shared).await
+    }
+
+    /// Acquires an exclusive advisory lock on the whole file, waiting until
+    /// it's available.
+    ///
+    /// See [`lock_shared`] for details.
+    ///
+    /// [`lock_shared`]: #method.lock_shared
+    #[inline]
+    pub async fn lock_exclusive(&self) -> io::Result<()> {
+        self.lock_blocking(FileLockKind::Exclusive).await
+    }
+
+    /// Acquires a shared advisory lock on the whole file if it's available,
+    /// returning `Ok(false)` instead of waiting if it isn't.
+    #[inline]
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        let sync = unsafe { as_sync(&self.std) };
+        try_lock(&sync, FileLockKind::Shared)
+    }
+
+    /// Acquires an exclusive advisory lock on the whole file if it's
+    /// available, returning `Ok(false)` instead of waiting if it isn't.
+    #[inline]
+    pub fn try_lock_exclusive(&self) -> io::Result<bool> {
+        let sync = unsafe { as_sync(&self.std) };
+        try_lock(&sync, FileLockKind::Exclusive)
+    }
+
+    /// Releases an advisory lock acquired by one of the `lock_*` or
+    /// `try_lock_*` methods.
+    #[inline]
+    pub fn unlock(&self) -> io::Result<()> {
+        let sync = unsafe { as_sync(&self.std) };
+        unlock(&sync)
+    }
+
+    async fn lock_blocking(&self, kind: FileLockKind) -> io::Result<()> {
+        // Lock through a clone of the handle, which refers to the same open
+        // file, so that the wait doesn't block the executor.
+        let clone = unsafe { as_sync(&self.std) }.try_clone()?;
+        task::spawn_blocking(move || lock(&clone, kind)).await
+    }
 }
 
 #[cfg(not(target_os = "wasi"))]
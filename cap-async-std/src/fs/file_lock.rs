@@ -0,0 +1,48 @@
+use crate::fs::File;
+use async_std::io;
+use std::fmt;
+
+/// An exclusive advisory lock on a lock file, returned by [`Dir::lock_file`].
+///
+/// The lock is released when the `FileLock` is dropped, or explicitly with
+/// [`unlock`]. The lock file itself is left in place, so that it can be
+/// locked again later.
+///
+/// [`Dir::lock_file`]: struct.Dir.html#method.lock_file
+/// [`unlock`]: #method.unlock
+pub struct FileLock {
+    file: File,
+}
+
+impl FileLock {
+    pub(crate) fn new(file: File) -> Self {
+        Self { file }
+    }
+
+    /// Returns the locked file.
+    #[inline]
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    /// Releases the lock, reporting any error which occurs.
+    #[inline]
+    pub fn unlock(self) -> io::Result<()> {
+        self.file.unlock()
+    }
+}
+
+impl Drop for FileLock {
+    #[inline]
+    fn drop(&mut self) {
+        self.file.unlock().ok();
+    }
+}
+
+impl fmt::Debug for FileLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("FileLock");
+        b.field("file", &self.file);
+        b.finish()
+    }
+}
@@ -29,17 +29,25 @@
 mod dir;
 mod dir_entry;
 mod file;
+mod file_lock;
+mod glob;
 mod read_dir;
+mod walk;
 
 pub use dir::*;
 pub use dir_entry::*;
 pub use file::*;
+pub use file_lock::*;
+pub use glob::*;
 pub use read_dir::*;
+pub use walk::*;
 
 // Re-export things from `cap_primitives` that we can use as-is.
 #[cfg(not(target_os = "wasi"))]
 pub use cap_primitives::fs::{DirBuilder, FileType, Metadata, OpenOptions, Permissions};
 
+pub use cap_primitives::fs::{CopyDirOptions, CopyProgress, DirAccess};
+
 // Re-export things from `async_std` that we can use as-is.
 #[cfg(target_os = "wasi")]
 pub use async_std::fs::{DirBuilder, FileType, Metadata, OpenOptions, Permissions};
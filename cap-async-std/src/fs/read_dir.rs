@@ -1,5 +1,6 @@
 use crate::fs::DirEntry;
 use async_std::io;
+use cap_primitives::fs::DirAccess;
 use std::fmt;
 
 /// Iterator over the entries in a directory.
@@ -13,6 +14,8 @@ use std::fmt;
 /// [`async_std::fs::ReadDir`]: https://docs.rs/async-std/latest/async_std/fs/struct.ReadDir.html
 pub struct ReadDir {
     pub(crate) inner: cap_primitives::fs::ReadDir,
+    pub(crate) access: DirAccess,
+    pub(crate) no_xdev: bool,
 }
 
 impl Iterator for ReadDir {
@@ -20,9 +23,14 @@ impl Iterator for ReadDir {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
+        let (access, no_xdev) = (self.access, self.no_xdev);
         self.inner
             .next()
-            .map(|inner| inner.map(|inner| DirEntry { inner }))
+            .map(|inner| inner.map(|inner| DirEntry {
+                    inner,
+                    access,
+                    no_xdev,
+                }))
     }
 }
 
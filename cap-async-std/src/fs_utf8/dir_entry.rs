@@ -26,7 +26,7 @@ use std::{fmt, io};
 ///
 /// [`async_std::fs::DirEntry`]: https://docs.rs/async-std/latest/async_std/fs/struct.DirEntry.html
 pub struct DirEntry {
-    cap_std: crate::fs::DirEntry,
+    pub(crate) cap_std: crate::fs::DirEntry,
 }
 
 impl DirEntry {
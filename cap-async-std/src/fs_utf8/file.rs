@@ -1,4 +1,4 @@
-use crate::fs::{Metadata, Permissions};
+use crate::fs::{DirAccess, Metadata, Permissions};
 #[cfg(unix)]
 use async_std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 #[cfg(target_os = "wasi")]
@@ -25,7 +25,7 @@ use std::{fmt, pin::Pin};
 /// [`Dir::open`]: struct.Dir.html#method.open
 /// [`Dir::create`]: struct.Dir.html#method.create
 pub struct File {
-    cap_std: crate::fs::File,
+    pub(crate) cap_std: crate::fs::File,
 }
 
 impl File {
@@ -54,6 +54,18 @@ impl File {
 
     // async_std doesn't have `with_options`.
 
+    /// Returns the [`DirAccess`] level of the `Dir` this file was opened
+    /// from.
+    ///
+    /// This corresponds to [`cap_async_std::fs::File::access`].
+    ///
+    /// [`DirAccess`]: ../fs/enum.DirAccess.html
+    /// [`cap_async_std::fs::File::access`]: ../fs/struct.File.html#method.access
+    #[inline]
+    pub fn access(&self) -> DirAccess {
+        self.cap_std.access()
+    }
+
     /// Attempts to sync all OS-internal metadata to disk.
     ///
     /// This corresponds to [`async_std::fs::File::sync_all`].
@@ -107,6 +119,61 @@ impl File {
     pub async fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
         self.cap_std.set_permissions(perm).await
     }
+
+    /// Changes the owner and group of the underlying file. A `None` `uid` or
+    /// `gid` leaves the corresponding owner unchanged.
+    ///
+    /// This corresponds to [`std::os::unix::fs::fchown`].
+    ///
+    /// [`std::os::unix::fs::fchown`]: https://doc.rust-lang.org/std/os/unix/fs/fn.fchown.html
+    #[cfg(unix)]
+    #[inline]
+    pub fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+        self.cap_std.set_owner(uid, gid)
+    }
+
+    /// Acquires a shared advisory lock on the whole file, waiting until it's
+    /// available.
+    ///
+    /// This corresponds to [`cap_async_std::fs::File::lock_shared`].
+    ///
+    /// [`cap_async_std::fs::File::lock_shared`]: ../fs/struct.File.html#method.lock_shared
+    #[inline]
+    pub async fn lock_shared(&self) -> io::Result<()> {
+        self.cap_std.lock_shared().await
+    }
+
+    /// Acquires an exclusive advisory lock on the whole file, waiting until
+    /// it's available.
+    ///
+    /// This corresponds to [`cap_async_std::fs::File::lock_exclusive`].
+    ///
+    /// [`cap_async_std::fs::File::lock_exclusive`]: ../fs/struct.File.html#method.lock_exclusive
+    #[inline]
+    pub async fn lock_exclusive(&self) -> io::Result<()> {
+        self.cap_std.lock_exclusive().await
+    }
+
+    /// Acquires a shared advisory lock on the whole file if it's available,
+    /// returning `Ok(false)` instead of waiting if it isn't.
+    #[inline]
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.cap_std.try_lock_shared()
+    }
+
+    /// Acquires an exclusive advisory lock on the whole file if it's
+    /// available, returning `Ok(false)` instead of waiting if it isn't.
+    #[inline]
+    pub fn try_lock_exclusive(&self) -> io::Result<bool> {
+        self.cap_std.try_lock_exclusive()
+    }
+
+    /// Releases an advisory lock acquired by one of the `lock_*` or
+    /// `try_lock_*` methods.
+    #[inline]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.cap_std.unlock()
+    }
 }
 
 #[cfg(not(windows))]
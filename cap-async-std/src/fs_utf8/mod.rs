@@ -22,15 +22,23 @@
 mod dir;
 mod dir_entry;
 mod file;
+mod file_lock;
+mod glob;
 mod read_dir;
+mod walk;
 
 pub use dir::*;
 pub use dir_entry::*;
 pub use file::*;
+pub use file_lock::*;
+pub use glob::*;
 pub use read_dir::*;
+pub use walk::*;
 
 // Re-export things from `cap_std::fs` that we can use as-is.
-pub use crate::fs::{DirBuilder, FileType, Metadata, OpenOptions, Permissions};
+pub use crate::fs::{
+    CopyDirOptions, CopyProgress, DirBuilder, FileType, Metadata, OpenOptions, Permissions,
+};
 
 fn from_utf8<P: AsRef<str>>(path: P) -> std::io::Result<async_std::path::PathBuf> {
     #[cfg(not(windows))]
@@ -0,0 +1,180 @@
+use crate::fs::FileType;
+use crate::fs_utf8::{to_utf8, DirEntry};
+use cap_primitives::fs::DirAccess;
+use std::{cmp::Ordering, fmt, fs, io};
+
+/// An iterator over the entries of a directory tree, returned by
+/// [`Dir::walk`].
+///
+/// This corresponds to [`cap_async_std::fs::Walk`], but yields paths as
+/// `String`s. Like it, this is a synchronous iterator.
+///
+/// [`Dir::walk`]: struct.Dir.html#method.walk
+/// [`cap_async_std::fs::Walk`]: ../fs/struct.Walk.html
+pub struct Walk {
+    inner: cap_primitives::fs::Walk<DirEntry>,
+}
+
+impl Walk {
+    pub(crate) fn new(std_file: fs::File, access: DirAccess, no_xdev: bool) -> Self {
+        let mut inner = cap_primitives::fs::Walk::with_entry_type(
+            std_file,
+            move |inner| {
+                DirEntry::from_cap_std(crate::fs::DirEntry {
+                    inner,
+                    access,
+                    no_xdev,
+                })
+            },
+            inner,
+        );
+        inner.no_xdev(no_xdev);
+        Self { inner }
+    }
+
+    /// Only yield entries at least `depth` levels below the directory being
+    /// walked. Shallower directories are still descended into.
+    #[inline]
+    pub fn min_depth(&mut self, depth: usize) -> &mut Self {
+        self.inner.min_depth(depth);
+        self
+    }
+
+    /// Don't yield or descend into entries more than `depth` levels below the
+    /// directory being walked.
+    #[inline]
+    pub fn max_depth(&mut self, depth: usize) -> &mut Self {
+        self.inner.max_depth(depth);
+        self
+    }
+
+    /// Sets the option to follow symlinks to directories and descend into
+    /// them. By default, symlinks are yielded but not followed.
+    #[inline]
+    pub fn follow_symlinks(&mut self, follow_symlinks: bool) -> &mut Self {
+        self.inner.follow_symlinks(follow_symlinks);
+        self
+    }
+
+    /// Yield the entries of each directory in the order given by `cmp`.
+    #[inline]
+    pub fn sort_by<F>(&mut self, cmp: F) -> &mut Self
+    where
+        F: FnMut(&DirEntry, &DirEntry) -> Ordering + Send + Sync + 'static,
+    {
+        self.inner.sort_by(cmp);
+        self
+    }
+
+    /// Yield the entries of each directory in order of their file names.
+    #[inline]
+    pub fn sort_by_file_name(&mut self) -> &mut Self {
+        self.inner.sort_by_file_name();
+        self
+    }
+
+    /// Don't descend into the most recently yielded directory. If the most
+    /// recently yielded entry isn't a directory, skip the remaining entries of
+    /// the directory containing it.
+    #[inline]
+    pub fn skip_current_dir(&mut self) {
+        self.inner.skip_current_dir()
+    }
+}
+
+impl Iterator for Walk {
+    type Item = io::Result<WalkEntry>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|inner| {
+            let inner = inner?;
+            let path = to_utf8(async_std::path::Path::new(inner.path()))?;
+            Ok(WalkEntry { inner, path })
+        })
+    }
+}
+
+impl fmt::Debug for Walk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+fn inner(entry: &DirEntry) -> &cap_primitives::fs::DirEntry {
+    &entry.cap_std.inner
+}
+
+/// An entry yielded by the [`Walk`] iterator.
+///
+/// This corresponds to [`cap_async_std::fs::WalkEntry`].
+///
+/// [`Walk`]: struct.Walk.html
+/// [`cap_async_std::fs::WalkEntry`]: ../fs/struct.WalkEntry.html
+pub struct WalkEntry {
+    inner: cap_primitives::fs::WalkEntry<DirEntry>,
+    path: String,
+}
+
+impl WalkEntry {
+    /// Returns the path of this entry, relative to the directory being
+    /// walked.
+    #[inline]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Consumes `self` and returns the path of this entry, relative to the
+    /// directory being walked.
+    #[inline]
+    pub fn into_path(self) -> String {
+        self.path
+    }
+
+    /// Returns the number of levels below the directory being walked that
+    /// this entry is at, where its immediate entries are at depth 1.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.inner.depth()
+    }
+
+    /// Returns the file type of this entry. If symlinks are being followed,
+    /// this is the type of the symlink's target.
+    #[inline]
+    pub fn file_type(&self) -> FileType {
+        self.inner.file_type()
+    }
+
+    /// Returns `true` if this entry is a symlink, whether or not it was
+    /// followed.
+    #[inline]
+    pub fn path_is_symlink(&self) -> bool {
+        self.inner.path_is_symlink()
+    }
+
+    /// Returns the bare file name of this entry without any other leading
+    /// path component.
+    #[inline]
+    pub fn file_name(&self) -> String {
+        self.inner.dir_entry().file_name()
+    }
+
+    /// Returns the underlying `DirEntry`, which may be used to open the
+    /// entry.
+    #[inline]
+    pub fn dir_entry(&self) -> &DirEntry {
+        self.inner.dir_entry()
+    }
+
+    /// Consumes `self` and returns the underlying `DirEntry`.
+    #[inline]
+    pub fn into_dir_entry(self) -> DirEntry {
+        self.inner.into_dir_entry()
+    }
+}
+
+impl fmt::Debug for WalkEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
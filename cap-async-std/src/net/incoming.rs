@@ -1,4 +1,4 @@
-use crate::net::TcpStream;
+use crate::net::{PeerFilter, TcpStream};
 use async_std::{
     io, net,
     stream::Stream,
@@ -14,6 +14,7 @@ use std::pin::Pin;
 /// [`TcpListener`]: struct.TcpListener.html
 pub struct Incoming<'a> {
     std: net::Incoming<'a>,
+    peer_filter: Option<&'a PeerFilter>,
 }
 
 impl<'a> Incoming<'a> {
@@ -25,7 +26,17 @@ impl<'a> Incoming<'a> {
     /// process has access to.
     #[inline]
     pub unsafe fn from_std(std: net::Incoming<'a>) -> Self {
-        Self { std }
+        Self::from_std_filtered(std, None)
+    }
+
+    /// Like `from_std`, but skipping connections from peers rejected by
+    /// `peer_filter`.
+    #[inline]
+    pub(crate) unsafe fn from_std_filtered(
+        std: net::Incoming<'a>,
+        peer_filter: Option<&'a PeerFilter>,
+    ) -> Self {
+        Self { std, peer_filter }
     }
 }
 
@@ -34,12 +45,23 @@ impl<'a> Stream for Incoming<'a> {
 
     #[inline]
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
-        Stream::poll_next(Pin::new(&mut self.std), cx).map(|poll| {
-            poll.map(|result| {
-                let tcp_stream = result?;
-                Ok(unsafe { TcpStream::from_std(tcp_stream) })
-            })
-        })
+        loop {
+            let tcp_stream = match Stream::poll_next(Pin::new(&mut self.std), cx) {
+                Poll::Ready(Some(Ok(tcp_stream))) => tcp_stream,
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+            if let Some(peer_filter) = self.peer_filter {
+                // If the peer has already gone away, its address can't be
+                // checked, so just drop the connection.
+                match tcp_stream.peer_addr() {
+                    Ok(addr) if peer_filter.check_peer(&addr) => {}
+                    _ => continue,
+                }
+            }
+            return Poll::Ready(Some(Ok(unsafe { TcpStream::from_std(tcp_stream) })));
+        }
     }
 
     #[inline]
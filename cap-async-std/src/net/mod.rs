@@ -1,7 +1,5 @@
 //! A capability-oriented network API modeled after `async_std::net`.
 //!
-//! XXX: The interfaces in this module are not yet implemented.
-//!
 //! This corresponds to [`async_std::net`].
 //!
 //! Instead of [`async_std::net`]'s constructor methods which take an address to
@@ -29,4 +27,14 @@ pub use async_std::net::{
     ToSocketAddrs,
 };
 
+// Re-export the `ipnet` types used to describe ranges of addresses in a
+// `Catalog`.
+pub use cap_primitives::ipnet::{IpNet, Ipv4Net, Ipv6Net};
+
+// Re-export things from `cap_primitives` that we can use as-is.
+pub use cap_primitives::net::{
+    HostsResolver, Operations, PeerFilter, PolicyError, Resolver, SystemResolver,
+    TcpListenerOptions, UdpSocketOptions,
+};
+
 // TODO: re-export experimental Ipv6MulticastScope?
@@ -1,4 +1,4 @@
-use crate::net::{Incoming, SocketAddr, TcpStream};
+use crate::net::{Incoming, PeerFilter, SocketAddr, TcpStream};
 #[cfg(unix)]
 use async_std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 #[cfg(windows)]
@@ -18,6 +18,7 @@ use async_std::{io, net};
 /// [`Catalog::bind_tcp_listener`]: struct.Catalog.html#method.bind_tcp_listener
 pub struct TcpListener {
     std: net::TcpListener,
+    peer_filter: Option<PeerFilter>,
 }
 
 impl TcpListener {
@@ -29,7 +30,10 @@ impl TcpListener {
     /// process has access to.
     #[inline]
     pub unsafe fn from_std(std: net::TcpListener) -> Self {
-        Self { std }
+        Self {
+            std,
+            peer_filter: None,
+        }
     }
 
     /// Returns the local socket address of this listener.
@@ -44,28 +48,58 @@ impl TcpListener {
 
     // async_std doesn't have `try_clone`.
 
+    /// Restrict the peers that this listener accepts connections from.
+    ///
+    /// With a filter set, [`accept`] and [`incoming`] close connections from
+    /// peers that the filter doesn't permit, report them to its rejection
+    /// hook, and wait for the next connection. Passing `None` removes the
+    /// filter.
+    ///
+    /// [`accept`]: #method.accept
+    /// [`incoming`]: #method.incoming
+    #[inline]
+    pub fn set_peer_filter(&mut self, peer_filter: Option<PeerFilter>) {
+        self.peer_filter = peer_filter;
+    }
+
+    /// Returns the filter set with [`set_peer_filter`], if any.
+    ///
+    /// [`set_peer_filter`]: #method.set_peer_filter
+    #[inline]
+    pub fn peer_filter(&self) -> Option<&PeerFilter> {
+        self.peer_filter.as_ref()
+    }
+
     /// Accept a new incoming connection from this listener.
     ///
-    /// This corresponds to [`async_std::net::TcpListener::accept`].
+    /// This corresponds to [`async_std::net::TcpListener::accept`], except
+    /// that connections from peers rejected by the [`peer_filter`] are
+    /// skipped.
     ///
     /// [`async_std::net::TcpListener::accept`]: https://docs.rs/async-std/latest/async_std/net/struct.TcpListener.html#method.accept
+    /// [`peer_filter`]: #method.peer_filter
     #[inline]
     pub async fn accept(&self) -> io::Result<(TcpStream, SocketAddr)> {
-        self.std
-            .accept()
-            .await
-            .map(|(tcp_stream, addr)| (unsafe { TcpStream::from_std(tcp_stream) }, addr))
+        loop {
+            let (tcp_stream, addr) = self.std.accept().await?;
+            if self.check_peer(&addr) {
+                return Ok((unsafe { TcpStream::from_std(tcp_stream) }, addr));
+            }
+        }
     }
 
     /// Returns an iterator over the connections being received on this listener.
     ///
-    /// This corresponds to [`async_std::net::TcpListener::incoming`].
+    /// This corresponds to [`async_std::net::TcpListener::incoming`], except
+    /// that connections from peers rejected by the [`peer_filter`] are
+    /// skipped.
     ///
     /// [`async_std::net::TcpListener::incoming`]: https://docs.rs/async-std/latest/async_std/net/struct.TcpListener.html#method.incoming
+    /// [`peer_filter`]: #method.peer_filter
     #[inline]
     pub fn incoming(&self) -> Incoming {
         let incoming = self.std.incoming();
-        unsafe { Incoming::from_std(incoming) }
+        unsafe { Incoming::from_std_filtered(incoming, self.peer_filter.as_ref()) }
     }
 
     // async_std doesn't have `TcpListener::set_ttl`.
@@ -75,6 +109,13 @@ impl TcpListener {
     // async_std doesn't have `TcpListener::take_error`.
 
     // async_std doesn't have `TcpListener::set_nonblocking`.
+
+    fn check_peer(&self, addr: &SocketAddr) -> bool {
+        match &self.peer_filter {
+            Some(peer_filter) => peer_filter.check_peer(addr),
+            None => true,
+        }
+    }
 }
 
 #[cfg(unix)]
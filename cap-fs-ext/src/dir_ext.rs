@@ -1,11 +1,15 @@
 #[cfg(not(windows))]
 use cap_primitives::fs::symlink;
-use cap_primitives::fs::{set_times, FollowSymlinks};
+#[cfg(unix)]
+use cap_primitives::fs::{create_device_node, create_fifo, set_owner};
+use cap_primitives::fs::{
+    follow_no_xdev, set_times, stat_no_xdev, via_parent_no_xdev, FollowSymlinks,
+};
 #[cfg(unix)]
 use std::os::unix::io::{AsRawFd, FromRawFd};
 #[cfg(target_os = "wasi")]
 use std::os::wasi::io::{AsRawFd, FromRawFd};
-use std::{io, path::Path};
+use std::{fs, io, path::Path};
 #[cfg(windows)]
 use {
     cap_primitives::fs::{symlink_dir, symlink_file},
@@ -81,6 +85,60 @@ pub trait DirExt {
     ///
     /// [`std::os::windows::fs::symlink_dir`]: https://doc.rust-lang.org/std/os/windows/fs/fn.symlink_dir.html
     fn symlink_dir<P: AsRef<Path>, Q: AsRef<Path>>(&self, src: P, dst: Q) -> io::Result<()>;
+
+    /// Changes the owner and group of a file on a filesystem. A `None` `uid`
+    /// or `gid` leaves the corresponding owner unchanged.
+    ///
+    /// This corresponds to [`std::os::unix::fs::chown`] and
+    /// [`std::os::unix::fs::lchown`], but only accesses paths relative to
+    /// `self`.
+    ///
+    /// [`std::os::unix::fs::chown`]: https://doc.rust-lang.org/std/os/unix/fs/fn.chown.html
+    /// [`std::os::unix::fs::lchown`]: https://doc.rust-lang.org/std/os/unix/fs/fn.lchown.html
+    #[cfg(unix)]
+    fn set_owner<P: AsRef<Path>>(
+        &self,
+        path: P,
+        uid: Option<u32>,
+        gid: Option<u32>,
+        follow: FollowSymlinks,
+    ) -> io::Result<()>;
+
+    /// Creates a new FIFO, also known as a named pipe, on a filesystem.
+    ///
+    /// `mode` holds the permission bits of the new FIFO, which are modified
+    /// by the process' umask.
+    ///
+    /// This corresponds to [`mkfifoat`], but only accesses paths relative to
+    /// `self`.
+    ///
+    /// [`mkfifoat`]: https://man7.org/linux/man-pages/man3/mkfifoat.3.html
+    #[cfg(unix)]
+    fn create_fifo<P: AsRef<Path>>(&self, path: P, mode: u32) -> io::Result<()>;
+
+    /// Creates a new block or character device node on a filesystem.
+    ///
+    /// `mode` holds the file type, which must be `S_IFBLK` or `S_IFCHR`,
+    /// along with the permission bits, which are modified by the process'
+    /// umask. `dev` is the device number.
+    ///
+    /// This corresponds to [`mknodat`], but only accesses paths relative to
+    /// `self`.
+    ///
+    /// # Safety
+    ///
+    /// A device node provides access to the device it names, regardless of
+    /// where it's created, so this can grant access to resources outside of
+    /// `self`.
+    ///
+    /// [`mknodat`]: https://man7.org/linux/man-pages/man2/mknodat.2.html
+    #[cfg(unix)]
+    unsafe fn create_device_node<P: AsRef<Path>>(
+        &self,
+        path: P,
+        mode: u32,
+        dev: u64,
+    ) -> io::Result<()>;
 }
 
 /// `fs_utf8` version of `DirExt`.
@@ -151,29 +209,85 @@ pub trait DirExtUtf8 {
     ///
     /// [`std::os::windows::fs::symlink_dir`]: https://doc.rust-lang.org/std/os/windows/fs/fn.symlink_dir.html
     fn symlink_dir<P: AsRef<str>, Q: AsRef<str>>(&self, src: P, dst: Q) -> io::Result<()>;
+
+    /// Changes the owner and group of a file on a filesystem. A `None` `uid`
+    /// or `gid` leaves the corresponding owner unchanged.
+    ///
+    /// This corresponds to [`std::os::unix::fs::chown`] and
+    /// [`std::os::unix::fs::lchown`], but only accesses paths relative to
+    /// `self`.
+    ///
+    /// [`std::os::unix::fs::chown`]: https://doc.rust-lang.org/std/os/unix/fs/fn.chown.html
+    /// [`std::os::unix::fs::lchown`]: https://doc.rust-lang.org/std/os/unix/fs/fn.lchown.html
+    #[cfg(unix)]
+    fn set_owner<P: AsRef<str>>(
+        &self,
+        path: P,
+        uid: Option<u32>,
+        gid: Option<u32>,
+        follow: FollowSymlinks,
+    ) -> io::Result<()>;
+
+    /// Creates a new FIFO, also known as a named pipe, on a filesystem.
+    ///
+    /// `mode` holds the permission bits of the new FIFO, which are modified
+    /// by the process' umask.
+    ///
+    /// This corresponds to [`mkfifoat`], but only accesses paths relative to
+    /// `self`.
+    ///
+    /// [`mkfifoat`]: https://man7.org/linux/man-pages/man3/mkfifoat.3.html
+    #[cfg(unix)]
+    fn create_fifo<P: AsRef<str>>(&self, path: P, mode: u32) -> io::Result<()>;
+
+    /// Creates a new block or character device node on a filesystem.
+    ///
+    /// `mode` holds the file type, which must be `S_IFBLK` or `S_IFCHR`,
+    /// along with the permission bits, which are modified by the process'
+    /// umask. `dev` is the device number.
+    ///
+    /// This corresponds to [`mknodat`], but only accesses paths relative to
+    /// `self`.
+    ///
+    /// # Safety
+    ///
+    /// A device node provides access to the device it names, regardless of
+    /// where it's created, so this can grant access to resources outside of
+    /// `self`.
+    ///
+    /// [`mknodat`]: https://man7.org/linux/man-pages/man2/mknodat.2.html
+    #[cfg(unix)]
+    unsafe fn create_device_node<P: AsRef<str>>(
+        &self,
+        path: P,
+        mode: u32,
+        dev: u64,
+    ) -> io::Result<()>;
 }
 
 #[cfg(feature = "std")]
 impl DirExt for cap_std::fs::Dir {
     #[inline]
     fn set_atime<P: AsRef<Path>>(&self, path: P, atime: SystemTimeSpec) -> io::Result<()> {
-        set_times(
+        self.access().check_modify()?;
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             path.as_ref(),
-            Some(atime),
-            None,
             FollowSymlinks::Yes,
+            |dir, basename| set_times(dir, basename, Some(atime), None, FollowSymlinks::Yes),
         )
     }
 
     #[inline]
     fn set_mtime<P: AsRef<Path>>(&self, path: P, mtime: SystemTimeSpec) -> io::Result<()> {
-        set_times(
+        self.access().check_modify()?;
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             path.as_ref(),
-            None,
-            Some(mtime),
             FollowSymlinks::Yes,
+            |dir, basename| set_times(dir, basename, None, Some(mtime), FollowSymlinks::Yes),
         )
     }
 
@@ -184,12 +298,13 @@ impl DirExt for cap_std::fs::Dir {
         atime: Option<SystemTimeSpec>,
         mtime: Option<SystemTimeSpec>,
     ) -> io::Result<()> {
-        set_times(
+        self.access().check_modify()?;
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             path.as_ref(),
-            atime,
-            mtime,
             FollowSymlinks::Yes,
+            |dir, basename| set_times(dir, basename, atime, mtime, FollowSymlinks::Yes),
         )
     }
 
@@ -200,19 +315,26 @@ impl DirExt for cap_std::fs::Dir {
         atime: Option<SystemTimeSpec>,
         mtime: Option<SystemTimeSpec>,
     ) -> io::Result<()> {
-        set_times(
+        self.access().check_modify()?;
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             path.as_ref(),
-            atime,
-            mtime,
             FollowSymlinks::No,
+            |dir, basename| set_times(dir, basename, atime, mtime, FollowSymlinks::No),
         )
     }
 
     #[cfg(not(windows))]
     #[inline]
     fn symlink<P: AsRef<Path>, Q: AsRef<Path>>(&self, src: P, dst: Q) -> io::Result<()> {
-        symlink(src.as_ref(), unsafe { &as_file(self) }, dst.as_ref())
+        self.access().check_modify()?;
+        via_parent(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            dst.as_ref(),
+            |dir, basename| symlink(src.as_ref(), dir, basename),
+        )
     }
 
     #[cfg(not(windows))]
@@ -240,13 +362,73 @@ impl DirExt for cap_std::fs::Dir {
     #[cfg(windows)]
     #[inline]
     fn symlink_file<P: AsRef<Path>, Q: AsRef<Path>>(&self, src: P, dst: Q) -> io::Result<()> {
-        symlink_file(src.as_ref(), unsafe { &as_file(self) }, dst.as_ref())
+        self.access().check_modify()?;
+        via_parent(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            dst.as_ref(),
+            |dir, basename| symlink_file(src.as_ref(), dir, basename),
+        )
     }
 
     #[cfg(windows)]
     #[inline]
     fn symlink_dir<P: AsRef<Path>, Q: AsRef<Path>>(&self, src: P, dst: Q) -> io::Result<()> {
-        symlink_dir(src.as_ref(), unsafe { &as_file(self) }, dst.as_ref())
+        self.access().check_modify()?;
+        via_parent(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            dst.as_ref(),
+            |dir, basename| symlink_dir(src.as_ref(), dir, basename),
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    fn set_owner<P: AsRef<Path>>(
+        &self,
+        path: P,
+        uid: Option<u32>,
+        gid: Option<u32>,
+        follow: FollowSymlinks,
+    ) -> io::Result<()> {
+        self.access().check_modify()?;
+        via_entry(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            path.as_ref(),
+            follow,
+            |dir, basename| set_owner(dir, basename, uid, gid, follow),
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    fn create_fifo<P: AsRef<Path>>(&self, path: P, mode: u32) -> io::Result<()> {
+        self.access().check_create()?;
+        via_parent(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            path.as_ref(),
+            |dir, basename| create_fifo(dir, basename, mode),
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    unsafe fn create_device_node<P: AsRef<Path>>(
+        &self,
+        path: P,
+        mode: u32,
+        dev: u64,
+    ) -> io::Result<()> {
+        self.access().check_modify()?;
+        via_parent(
+            &as_file(self),
+            self.is_no_xdev(),
+            path.as_ref(),
+            |dir, basename| create_device_node(dir, basename, mode, dev),
+        )
     }
 }
 
@@ -254,23 +436,25 @@ impl DirExt for cap_std::fs::Dir {
 impl DirExt for cap_async_std::fs::Dir {
     #[inline]
     fn set_atime<P: AsRef<Path>>(&self, path: P, atime: SystemTimeSpec) -> io::Result<()> {
-        set_times(
+        self.access().check_modify()?;
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             path.as_ref(),
-            Some(atime),
-            None,
             FollowSymlinks::Yes,
+            |dir, basename| set_times(dir, basename, Some(atime), None, FollowSymlinks::Yes),
         )
     }
 
     #[inline]
     fn set_mtime<P: AsRef<Path>>(&self, path: P, mtime: SystemTimeSpec) -> io::Result<()> {
-        set_times(
+        self.access().check_modify()?;
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             path.as_ref(),
-            None,
-            Some(mtime),
             FollowSymlinks::Yes,
+            |dir, basename| set_times(dir, basename, None, Some(mtime), FollowSymlinks::Yes),
         )
     }
 
@@ -281,12 +465,13 @@ impl DirExt for cap_async_std::fs::Dir {
         atime: Option<SystemTimeSpec>,
         mtime: Option<SystemTimeSpec>,
     ) -> io::Result<()> {
-        set_times(
+        self.access().check_modify()?;
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             path.as_ref(),
-            atime,
-            mtime,
             FollowSymlinks::Yes,
+            |dir, basename| set_times(dir, basename, atime, mtime, FollowSymlinks::Yes),
         )
     }
 
@@ -297,19 +482,26 @@ impl DirExt for cap_async_std::fs::Dir {
         atime: Option<SystemTimeSpec>,
         mtime: Option<SystemTimeSpec>,
     ) -> io::Result<()> {
-        set_times(
+        self.access().check_modify()?;
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             path.as_ref(),
-            atime,
-            mtime,
             FollowSymlinks::No,
+            |dir, basename| set_times(dir, basename, atime, mtime, FollowSymlinks::No),
         )
     }
 
     #[cfg(not(windows))]
     #[inline]
     fn symlink<P: AsRef<Path>, Q: AsRef<Path>>(&self, src: P, dst: Q) -> io::Result<()> {
-        symlink(src.as_ref(), unsafe { &as_file(self) }, dst.as_ref())
+        self.access().check_modify()?;
+        via_parent(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            dst.as_ref(),
+            |dir, basename| symlink(src.as_ref(), dir, basename),
+        )
     }
 
     #[cfg(not(windows))]
@@ -337,13 +529,73 @@ impl DirExt for cap_async_std::fs::Dir {
     #[cfg(windows)]
     #[inline]
     fn symlink_file<P: AsRef<Path>, Q: AsRef<Path>>(&self, src: P, dst: Q) -> io::Result<()> {
-        symlink_file(src.as_ref(), unsafe { &as_file(self) }, dst.as_ref())
+        self.access().check_modify()?;
+        via_parent(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            dst.as_ref(),
+            |dir, basename| symlink_file(src.as_ref(), dir, basename),
+        )
     }
 
     #[cfg(windows)]
     #[inline]
     fn symlink_dir<P: AsRef<Path>, Q: AsRef<Path>>(&self, src: P, dst: Q) -> io::Result<()> {
-        symlink_dir(src.as_ref(), unsafe { &as_file(self) }, dst.as_ref())
+        self.access().check_modify()?;
+        via_parent(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            dst.as_ref(),
+            |dir, basename| symlink_dir(src.as_ref(), dir, basename),
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    fn set_owner<P: AsRef<Path>>(
+        &self,
+        path: P,
+        uid: Option<u32>,
+        gid: Option<u32>,
+        follow: FollowSymlinks,
+    ) -> io::Result<()> {
+        self.access().check_modify()?;
+        via_entry(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            path.as_ref(),
+            follow,
+            |dir, basename| set_owner(dir, basename, uid, gid, follow),
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    fn create_fifo<P: AsRef<Path>>(&self, path: P, mode: u32) -> io::Result<()> {
+        self.access().check_create()?;
+        via_parent(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            path.as_ref(),
+            |dir, basename| create_fifo(dir, basename, mode),
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    unsafe fn create_device_node<P: AsRef<Path>>(
+        &self,
+        path: P,
+        mode: u32,
+        dev: u64,
+    ) -> io::Result<()> {
+        self.access().check_modify()?;
+        via_parent(
+            &as_file(self),
+            self.is_no_xdev(),
+            path.as_ref(),
+            |dir, basename| create_device_node(dir, basename, mode, dev),
+        )
     }
 }
 
@@ -351,25 +603,27 @@ impl DirExt for cap_async_std::fs::Dir {
 impl DirExtUtf8 for cap_std::fs_utf8::Dir {
     #[inline]
     fn set_atime<P: AsRef<str>>(&self, path: P, atime: SystemTimeSpec) -> io::Result<()> {
+        self.access().check_modify()?;
         let path = from_utf8(path)?;
-        set_times(
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             &path,
-            Some(atime),
-            None,
             FollowSymlinks::Yes,
+            |dir, basename| set_times(dir, basename, Some(atime), None, FollowSymlinks::Yes),
         )
     }
 
     #[inline]
     fn set_mtime<P: AsRef<str>>(&self, path: P, mtime: SystemTimeSpec) -> io::Result<()> {
+        self.access().check_modify()?;
         let path = from_utf8(path)?;
-        set_times(
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             &path,
-            None,
-            Some(mtime),
             FollowSymlinks::Yes,
+            |dir, basename| set_times(dir, basename, None, Some(mtime), FollowSymlinks::Yes),
         )
     }
 
@@ -380,13 +634,14 @@ impl DirExtUtf8 for cap_std::fs_utf8::Dir {
         atime: Option<SystemTimeSpec>,
         mtime: Option<SystemTimeSpec>,
     ) -> io::Result<()> {
+        self.access().check_modify()?;
         let path = from_utf8(path)?;
-        set_times(
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             &path,
-            atime,
-            mtime,
             FollowSymlinks::Yes,
+            |dir, basename| set_times(dir, basename, atime, mtime, FollowSymlinks::Yes),
         )
     }
 
@@ -397,13 +652,14 @@ impl DirExtUtf8 for cap_std::fs_utf8::Dir {
         atime: Option<SystemTimeSpec>,
         mtime: Option<SystemTimeSpec>,
     ) -> io::Result<()> {
+        self.access().check_modify()?;
         let path = from_utf8(path)?;
-        set_times(
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             &path,
-            atime,
-            mtime,
             FollowSymlinks::No,
+            |dir, basename| set_times(dir, basename, atime, mtime, FollowSymlinks::No),
         )
     }
 
@@ -446,31 +702,81 @@ impl DirExtUtf8 for cap_std::fs_utf8::Dir {
     fn symlink_dir<P: AsRef<str>, Q: AsRef<str>>(&self, src: P, dst: Q) -> io::Result<()> {
         Self::symlink_dir(self, src, dst)
     }
+
+    #[cfg(unix)]
+    #[inline]
+    fn set_owner<P: AsRef<str>>(
+        &self,
+        path: P,
+        uid: Option<u32>,
+        gid: Option<u32>,
+        follow: FollowSymlinks,
+    ) -> io::Result<()> {
+        self.access().check_modify()?;
+        let path = from_utf8(path)?;
+        via_entry(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            &path,
+            follow,
+            |dir, basename| set_owner(dir, basename, uid, gid, follow),
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    fn create_fifo<P: AsRef<str>>(&self, path: P, mode: u32) -> io::Result<()> {
+        self.access().check_create()?;
+        let path = from_utf8(path)?;
+        via_parent(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            &path,
+            |dir, basename| create_fifo(dir, basename, mode),
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    unsafe fn create_device_node<P: AsRef<str>>(
+        &self,
+        path: P,
+        mode: u32,
+        dev: u64,
+    ) -> io::Result<()> {
+        self.access().check_modify()?;
+        let path = from_utf8(path)?;
+        via_parent(&as_file(self), self.is_no_xdev(), &path, |dir, basename| {
+            create_device_node(dir, basename, mode, dev)
+        })
+    }
 }
 
 #[cfg(all(feature = "async_std", feature = "fs_utf8"))]
 impl DirExtUtf8 for cap_async_std::fs_utf8::Dir {
     #[inline]
     fn set_atime<P: AsRef<str>>(&self, path: P, atime: SystemTimeSpec) -> io::Result<()> {
+        self.access().check_modify()?;
         let path = from_utf8(path)?;
-        set_times(
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             &path,
-            Some(atime),
-            None,
             FollowSymlinks::Yes,
+            |dir, basename| set_times(dir, basename, Some(atime), None, FollowSymlinks::Yes),
         )
     }
 
     #[inline]
     fn set_mtime<P: AsRef<str>>(&self, path: P, mtime: SystemTimeSpec) -> io::Result<()> {
+        self.access().check_modify()?;
         let path = from_utf8(path)?;
-        set_times(
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             &path,
-            None,
-            Some(mtime),
             FollowSymlinks::Yes,
+            |dir, basename| set_times(dir, basename, None, Some(mtime), FollowSymlinks::Yes),
         )
     }
 
@@ -481,13 +787,14 @@ impl DirExtUtf8 for cap_async_std::fs_utf8::Dir {
         atime: Option<SystemTimeSpec>,
         mtime: Option<SystemTimeSpec>,
     ) -> io::Result<()> {
+        self.access().check_modify()?;
         let path = from_utf8(path)?;
-        set_times(
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             &path,
-            atime,
-            mtime,
             FollowSymlinks::Yes,
+            |dir, basename| set_times(dir, basename, atime, mtime, FollowSymlinks::Yes),
         )
     }
 
@@ -498,22 +805,29 @@ impl DirExtUtf8 for cap_async_std::fs_utf8::Dir {
         atime: Option<SystemTimeSpec>,
         mtime: Option<SystemTimeSpec>,
     ) -> io::Result<()> {
+        self.access().check_modify()?;
         let path = from_utf8(path)?;
-        set_times(
+        via_entry(
             unsafe { &as_file(self) },
+            self.is_no_xdev(),
             &path,
-            atime,
-            mtime,
             FollowSymlinks::No,
+            |dir, basename| set_times(dir, basename, atime, mtime, FollowSymlinks::No),
         )
     }
 
     #[cfg(not(windows))]
     #[inline]
     fn symlink<P: AsRef<str>, Q: AsRef<str>>(&self, src: P, dst: Q) -> io::Result<()> {
+        self.access().check_modify()?;
         let src = from_utf8(src)?;
         let dst = from_utf8(dst)?;
-        symlink(&src, unsafe { &as_file(self) }, &dst)
+        via_parent(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            &dst,
+            |dir, basename| symlink(&src, dir, basename),
+        )
     }
 
     #[cfg(not(windows))]
@@ -541,17 +855,77 @@ impl DirExtUtf8 for cap_async_std::fs_utf8::Dir {
     #[cfg(windows)]
     #[inline]
     fn symlink_file<P: AsRef<str>, Q: AsRef<str>>(&self, src: P, dst: Q) -> io::Result<()> {
+        self.access().check_modify()?;
         let src = from_utf8(src)?;
         let dst = from_utf8(dst)?;
-        symlink_file(&src, unsafe { &as_file(self) }, &dst)
+        via_parent(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            &dst,
+            |dir, basename| symlink_file(&src, dir, basename),
+        )
     }
 
     #[cfg(windows)]
     #[inline]
     fn symlink_dir<P: AsRef<str>, Q: AsRef<str>>(&self, src: P, dst: Q) -> io::Result<()> {
+        self.access().check_modify()?;
         let src = from_utf8(src)?;
         let dst = from_utf8(dst)?;
-        symlink_dir(&src, unsafe { &as_file(self) }, &dst)
+        via_parent(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            &dst,
+            |dir, basename| symlink_dir(&src, dir, basename),
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    fn set_owner<P: AsRef<str>>(
+        &self,
+        path: P,
+        uid: Option<u32>,
+        gid: Option<u32>,
+        follow: FollowSymlinks,
+    ) -> io::Result<()> {
+        self.access().check_modify()?;
+        let path = from_utf8(path)?;
+        via_entry(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            &path,
+            follow,
+            |dir, basename| set_owner(dir, basename, uid, gid, follow),
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    fn create_fifo<P: AsRef<str>>(&self, path: P, mode: u32) -> io::Result<()> {
+        self.access().check_create()?;
+        let path = from_utf8(path)?;
+        via_parent(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            &path,
+            |dir, basename| create_fifo(dir, basename, mode),
+        )
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    unsafe fn create_device_node<P: AsRef<str>>(
+        &self,
+        path: P,
+        mode: u32,
+        dev: u64,
+    ) -> io::Result<()> {
+        self.access().check_modify()?;
+        let path = from_utf8(path)?;
+        via_parent(&as_file(self), self.is_no_xdev(), &path, |dir, basename| {
+            create_device_node(dir, basename, mode, dev)
+        })
     }
 }
 
@@ -564,15 +938,62 @@ impl DirExtUtf8 for cap_async_std::fs_utf8::Dir {
 /// resulting `std::fs::File` is live, and must ensure that the resulting
 /// `std::fs::File` doesn't outlive the `async_std::fs::File`.
 #[cfg(not(windows))]
-unsafe fn as_file<Fd: AsRawFd>(fd: &Fd) -> std::mem::ManuallyDrop<std::fs::File> {
+pub(crate) unsafe fn as_file<Fd: AsRawFd>(fd: &Fd) -> std::mem::ManuallyDrop<std::fs::File> {
     std::mem::ManuallyDrop::new(std::fs::File::from_raw_fd(fd.as_raw_fd()))
 }
 
 #[cfg(windows)]
-unsafe fn as_file<Handle: AsRawHandle>(handle: &Handle) -> std::mem::ManuallyDrop<std::fs::File> {
+pub(crate) unsafe fn as_file<Handle: AsRawHandle>(
+    handle: &Handle,
+) -> std::mem::ManuallyDrop<std::fs::File> {
     std::mem::ManuallyDrop::new(std::fs::File::from_raw_handle(handle.as_raw_handle()))
 }
 
+/// Calls `f` with the directory to resolve `path` in and what's left of
+/// `path`, for an operation on an existing file. If `no_xdev` is set, as it
+/// is for a `Dir` pinned with `no_xdev`, that's the parent of `path`, opened
+/// without crossing a mount point, and its last component, which is checked
+/// to be on the same filesystem; otherwise it's `start` and `path`.
+pub(crate) fn via_entry<T, F>(
+    start: &fs::File,
+    no_xdev: bool,
+    path: &Path,
+    follow: FollowSymlinks,
+    f: F,
+) -> io::Result<T>
+where
+    F: FnOnce(&fs::File, &Path) -> io::Result<T>,
+{
+    if !no_xdev {
+        return f(start, path);
+    }
+    match follow {
+        FollowSymlinks::Yes => {
+            let path = follow_no_xdev(start, path)?;
+            via_parent_no_xdev(start, &path, f)
+        }
+        FollowSymlinks::No => via_parent_no_xdev(start, path, |dir, basename| {
+            stat_no_xdev(dir, basename, FollowSymlinks::No)?;
+            f(dir, basename)
+        }),
+    }
+}
+
+/// Like [`via_entry`], but for an operation which creates a new file at
+/// `path`, so there's nothing to check in the last component.
+///
+/// [`via_entry`]: fn.via_entry.html
+pub(crate) fn via_parent<T, F>(start: &fs::File, no_xdev: bool, path: &Path, f: F) -> io::Result<T>
+where
+    F: FnOnce(&fs::File, &Path) -> io::Result<T>,
+{
+    if no_xdev {
+        via_parent_no_xdev(start, path, f)
+    } else {
+        f(start, path)
+    }
+}
+
 #[cfg(all(any(feature = "std", feature = "async_std"), feature = "fs_utf8"))]
 fn from_utf8<P: AsRef<str>>(path: P) -> std::io::Result<std::path::PathBuf> {
     #[cfg(not(windows))]
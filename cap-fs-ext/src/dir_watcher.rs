@@ -0,0 +1,148 @@
+use crate::dir_ext::as_file;
+use cap_primitives::fs::Watcher;
+pub use cap_primitives::fs::{DirEvent, DirEventKind};
+#[cfg(feature = "std")]
+use std::path::Path;
+use std::{collections::VecDeque, io};
+#[cfg(feature = "async_std")]
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A watcher for changes to files and directories within a `Dir`.
+///
+/// Each watched path is resolved with the same sandboxing as `open`, so a
+/// symlink can't redirect the watch to somewhere outside the `Dir`. Once a
+/// path is watched, the watch follows the object the path resolved to.
+///
+/// Events are read by iterating over the `DirWatcher`, which blocks until a
+/// change happens. Paths in the events are relative to the `Dir` the watch
+/// was added through.
+///
+/// This is currently only supported on Linux, where it's implemented with
+/// [inotify].
+///
+/// [inotify]: https://man7.org/linux/man-pages/man7/inotify.7.html
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct DirWatcher {
+    watcher: Watcher,
+    events: VecDeque<DirEvent>,
+}
+
+#[cfg(feature = "std")]
+impl DirWatcher {
+    /// Creates a new `DirWatcher` which watches the file or directory at
+    /// `path` within `dir`.
+    #[inline]
+    pub fn new<P: AsRef<Path>>(dir: &cap_std::fs::Dir, path: P) -> io::Result<Self> {
+        let mut watcher = Self {
+            watcher: Watcher::new()?,
+            events: VecDeque::new(),
+        };
+        watcher.add(dir, path)?;
+        Ok(watcher)
+    }
+
+    /// Starts watching the file or directory at `path` within `dir` too.
+    #[inline]
+    pub fn add<P: AsRef<Path>>(&mut self, dir: &cap_std::fs::Dir, path: P) -> io::Result<()> {
+        self.watcher.add(unsafe { &as_file(dir) }, path.as_ref())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Iterator for DirWatcher {
+    type Item = io::Result<DirEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.events.is_empty() {
+            match self.watcher.read_events() {
+                Ok(events) => self.events.extend(events),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        self.events.pop_front().map(Ok)
+    }
+}
+
+/// An asynchronous watcher for changes to files and directories within a
+/// `Dir`.
+///
+/// This is the `async_std` version of [`DirWatcher`]; events are read by
+/// polling it as a [`Stream`], which waits for changes on `async_std`'s
+/// reactor rather than blocking a thread.
+///
+/// [`DirWatcher`]: struct.DirWatcher.html
+/// [`Stream`]: https://docs.rs/async-std/latest/async_std/stream/trait.Stream.html
+#[cfg(feature = "async_std")]
+#[derive(Debug)]
+pub struct AsyncDirWatcher {
+    watcher: async_io::Async<Watcher>,
+    events: VecDeque<DirEvent>,
+}
+
+#[cfg(feature = "async_std")]
+impl AsyncDirWatcher {
+    /// Creates a new `AsyncDirWatcher` which watches the file or directory at
+    /// `path` within `dir`.
+    #[inline]
+    pub fn new<P: AsRef<cap_async_std::path::Path>>(
+        dir: &cap_async_std::fs::Dir,
+        path: P,
+    ) -> io::Result<Self> {
+        let mut watcher = Self {
+            watcher: async_io::Async::new_nonblocking(Watcher::new_nonblocking()?)?,
+            events: VecDeque::new(),
+        };
+        watcher.add(dir, path)?;
+        Ok(watcher)
+    }
+
+    /// Starts watching the file or directory at `path` within `dir` too.
+    #[inline]
+    pub fn add<P: AsRef<cap_async_std::path::Path>>(
+        &mut self,
+        dir: &cap_async_std::fs::Dir,
+        path: P,
+    ) -> io::Result<()> {
+        self.watcher_mut()
+            .add(unsafe { &as_file(dir) }, path.as_ref().as_ref())
+    }
+
+    fn watcher_mut(&mut self) -> &mut Watcher {
+        // Safety: `Watcher` doesn't give out its file descriptor, so this
+        // can't be used to replace or close the one the reactor is
+        // registered for.
+        unsafe { self.watcher.get_mut() }
+    }
+}
+
+#[cfg(feature = "async_std")]
+impl async_std::stream::Stream for AsyncDirWatcher {
+    type Item = io::Result<DirEvent>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(event) = this.events.pop_front() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+
+            match this.watcher_mut().read_events() {
+                Ok(events) => this.events.extend(events),
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    // Nothing has happened yet, so wait for the inotify file
+                    // descriptor to become readable.
+                    match this.watcher.poll_readable(cx) {
+                        Poll::Ready(Ok(())) => {}
+                        Poll::Ready(Err(err)) => return Poll::Ready(Some(Err(err))),
+                        Poll::Pending => return Poll::Pending,
+                    }
+                }
+                Err(err) => return Poll::Ready(Some(Err(err))),
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use crate::dir_ext::as_file;
+use cap_primitives::fs::{read_at, read_vectored_at, write_at, write_vectored_at};
+use std::io::{self, IoSlice, IoSliceMut};
+
+/// Extension trait for `File` for positional I/O.
+///
+/// These methods read and write at an explicit offset, without using or
+/// changing the file's current position, so they may be used concurrently
+/// from multiple threads through shared references. For the async `File`,
+/// they perform the I/O synchronously.
+pub trait FileIoExt {
+    /// Reads a number of bytes starting from a given offset.
+    ///
+    /// This corresponds to [`std::os::unix::fs::FileExt::read_at`], except
+    /// that it's supported on Windows platforms as well, and it doesn't
+    /// change the current position there either.
+    ///
+    /// [`std::os::unix::fs::FileExt::read_at`]: https://doc.rust-lang.org/std/os/unix/fs/trait.FileExt.html#tymethod.read_at
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize>;
+
+    /// Reads the exact number of bytes required to fill `buf` from the given
+    /// offset.
+    ///
+    /// This corresponds to [`std::os::unix::fs::FileExt::read_exact_at`],
+    /// except that it's supported on Windows platforms as well.
+    ///
+    /// [`std::os::unix::fs::FileExt::read_exact_at`]: https://doc.rust-lang.org/std/os/unix/fs/trait.FileExt.html#method.read_exact_at
+    fn read_exact_at(&self, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+        while !buf.is_empty() {
+            match self.read_at(buf, offset) {
+                Ok(0) => break,
+                Ok(n) => {
+                    let tmp = buf;
+                    buf = &mut tmp[n..];
+                    offset += n as u64;
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if buf.is_empty() {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "failed to fill whole buffer",
+            ))
+        }
+    }
+
+    /// Like `read_at`, except that it reads into a slice of buffers.
+    ///
+    /// This corresponds to `preadv`. Where that isn't available, this reads
+    /// into the first non-empty buffer.
+    fn read_vectored_at(&self, bufs: &mut [IoSliceMut], offset: u64) -> io::Result<usize>;
+
+    /// Writes a number of bytes starting from a given offset.
+    ///
+    /// This corresponds to [`std::os::unix::fs::FileExt::write_at`], except
+    /// that it's supported on Windows platforms as well, and it doesn't
+    /// change the current position there either.
+    ///
+    /// [`std::os::unix::fs::FileExt::write_at`]: https://doc.rust-lang.org/std/os/unix/fs/trait.FileExt.html#tymethod.write_at
+    fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize>;
+
+    /// Attempts to write an entire buffer starting from a given offset.
+    ///
+    /// This corresponds to [`std::os::unix::fs::FileExt::write_all_at`],
+    /// except that it's supported on Windows platforms as well.
+    ///
+    /// [`std::os::unix::fs::FileExt::write_all_at`]: https://doc.rust-lang.org/std/os/unix/fs/trait.FileExt.html#method.write_all_at
+    fn write_all_at(&self, mut buf: &[u8], mut offset: u64) -> io::Result<()> {
+        while !buf.is_empty() {
+            match self.write_at(buf, offset) {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write whole buffer",
+                    ))
+                }
+                Ok(n) => {
+                    buf = &buf[n..];
+                    offset += n as u64;
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Like `write_at`, except that it writes from a slice of buffers.
+    ///
+    /// This corresponds to `pwritev`. Where that isn't available, this writes
+    /// from the first non-empty buffer.
+    fn write_vectored_at(&self, bufs: &[IoSlice], offset: u64) -> io::Result<usize>;
+}
+
+#[cfg(feature = "std")]
+impl FileIoExt for cap_std::fs::File {
+    #[inline]
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        read_at(unsafe { &as_file(self) }, buf, offset)
+    }
+
+    #[inline]
+    fn read_vectored_at(&self, bufs: &mut [IoSliceMut], offset: u64) -> io::Result<usize> {
+        read_vectored_at(unsafe { &as_file(self) }, bufs, offset)
+    }
+
+    #[inline]
+    fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
+        write_at(unsafe { &as_file(self) }, buf, offset)
+    }
+
+    #[inline]
+    fn write_vectored_at(&self, bufs: &[IoSlice], offset: u64) -> io::Result<usize> {
+        write_vectored_at(unsafe { &as_file(self) }, bufs, offset)
+    }
+}
+
+#[cfg(feature = "async_std")]
+impl FileIoExt for cap_async_std::fs::File {
+    #[inline]
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        read_at(unsafe { &as_file(self) }, buf, offset)
+    }
+
+    #[inline]
+    fn read_vectored_at(&self, bufs: &mut [IoSliceMut], offset: u64) -> io::Result<usize> {
+        read_vectored_at(unsafe { &as_file(self) }, bufs, offset)
+    }
+
+    #[inline]
+    fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
+        write_at(unsafe { &as_file(self) }, buf, offset)
+    }
+
+    #[inline]
+    fn write_vectored_at(&self, bufs: &[IoSlice], offset: u64) -> io::Result<usize> {
+        write_vectored_at(unsafe { &as_file(self) }, bufs, offset)
+    }
+}
+
+#[cfg(all(feature = "std", feature = "fs_utf8"))]
+impl FileIoExt for cap_std::fs_utf8::File {
+    #[inline]
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        read_at(unsafe { &as_file(self) }, buf, offset)
+    }
+
+    #[inline]
+    fn read_vectored_at(&self, bufs: &mut [IoSliceMut], offset: u64) -> io::Result<usize> {
+        read_vectored_at(unsafe { &as_file(self) }, bufs, offset)
+    }
+
+    #[inline]
+    fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
+        write_at(unsafe { &as_file(self) }, buf, offset)
+    }
+
+    #[inline]
+    fn write_vectored_at(&self, bufs: &[IoSlice], offset: u64) -> io::Result<usize> {
+        write_vectored_at(unsafe { &as_file(self) }, bufs, offset)
+    }
+}
+
+#[cfg(all(feature = "async_std", feature = "fs_utf8"))]
+impl FileIoExt for cap_async_std::fs_utf8::File {
+    #[inline]
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        read_at(unsafe { &as_file(self) }, buf, offset)
+    }
+
+    #[inline]
+    fn read_vectored_at(&self, bufs: &mut [IoSliceMut], offset: u64) -> io::Result<usize> {
+        read_vectored_at(unsafe { &as_file(self) }, bufs, offset)
+    }
+
+    #[inline]
+    fn write_at(&self, buf: &[u8], offset: u64) -> io::Result<usize> {
+        write_at(unsafe { &as_file(self) }, buf, offset)
+    }
+
+    #[inline]
+    fn write_vectored_at(&self, bufs: &[IoSlice], offset: u64) -> io::Result<usize> {
+        write_vectored_at(unsafe { &as_file(self) }, bufs, offset)
+    }
+}
@@ -1,4 +1,4 @@
-//! Extension traits for `Dir`
+//! Extension traits for `Dir`, `File`, etc.
 
 #![deny(missing_docs)]
 #![cfg_attr(all(windows, windows_by_handle), feature(windows_by_handle))]
@@ -10,13 +10,31 @@
 )]
 
 mod dir_ext;
+#[cfg(all(target_os = "linux", any(feature = "std", feature = "async_std")))]
+mod dir_watcher;
+mod file_io_ext;
 mod file_type_ext;
 mod metadata_ext;
 mod open_options_follow_ext;
+mod open_options_resolve_ext;
+#[cfg(all(target_os = "linux", any(feature = "std", feature = "async_std")))]
+mod statx_ext;
+mod xattr_ext;
 
 #[cfg(all(any(feature = "std", feature = "async_std"), feature = "fs_utf8"))]
 pub use dir_ext::DirExtUtf8;
 pub use dir_ext::{DirExt, SystemTimeSpec};
+#[cfg(all(target_os = "linux", feature = "async_std"))]
+pub use dir_watcher::AsyncDirWatcher;
+#[cfg(all(target_os = "linux", feature = "std"))]
+pub use dir_watcher::DirWatcher;
+#[cfg(all(target_os = "linux", any(feature = "std", feature = "async_std")))]
+pub use dir_watcher::{DirEvent, DirEventKind};
+pub use file_io_ext::FileIoExt;
 pub use file_type_ext::FileTypeExt;
 pub use metadata_ext::MetadataExt;
 pub use open_options_follow_ext::{FollowSymlinks, OpenOptionsFollowExt};
+pub use open_options_resolve_ext::OpenOptionsResolveExt;
+#[cfg(all(target_os = "linux", any(feature = "std", feature = "async_std")))]
+pub use statx_ext::{DirStatxExt, FileStatxExt, StatxFields, StatxSync};
+pub use xattr_ext::{DirXattrExt, FileXattrExt};
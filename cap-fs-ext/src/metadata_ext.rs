@@ -4,6 +4,11 @@ use std::os::windows::fs::MetadataExt as _WindowsByHandle;
 #[cfg(all(windows, not(windows_by_handle)))]
 use cap_primitives::fs::_WindowsByHandle;
 
+#[cfg(all(target_os = "linux", any(feature = "std", feature = "async_std")))]
+use cap_primitives::fs::_LinuxStatx;
+#[cfg(target_os = "linux")]
+use cap_primitives::fs::StatxFields;
+
 /// Extension trait for `Metadata`.
 pub trait MetadataExt {
     /// Returns the ID of the device containing the file.
@@ -31,8 +36,86 @@ pub trait MetadataExt {
     ///
     /// [`std::os::unix::fs::MetadataExt::nlink`]: https://doc.rust-lang.org/std/os/unix/fs/trait.MetadataExt.html#tymethod.nlink
     fn nlink(&self) -> u64;
+
+    /// Returns the block size for filesystem I/O.
+    ///
+    /// This corresponds to [`std::os::unix::fs::MetadataExt::blksize`].
+    ///
+    /// [`std::os::unix::fs::MetadataExt::blksize`]: https://doc.rust-lang.org/std/os/unix/fs/trait.MetadataExt.html#tymethod.blksize
+    #[cfg(target_os = "linux")]
+    fn blksize(&self) -> u64;
+
+    /// Returns the mount ID of the mount containing the file, if it's known.
+    ///
+    /// This corresponds to `stx_mnt_id` in [`statx`], and is only known on
+    /// Linux 5.8 and later.
+    ///
+    /// [`statx`]: https://man7.org/linux/man-pages/man2/statx.2.html
+    #[cfg(target_os = "linux")]
+    fn mnt_id(&self) -> Option<u64>;
+
+    /// Returns the `STATX_ATTR_*` flags of the file.
+    ///
+    /// This corresponds to `stx_attributes` in [`statx`]. Only the flags in
+    /// `attributes_mask` are meaningful.
+    ///
+    /// [`statx`]: https://man7.org/linux/man-pages/man2/statx.2.html
+    #[cfg(target_os = "linux")]
+    fn attributes(&self) -> u64;
+
+    /// Returns the `STATX_ATTR_*` flags that the filesystem supports.
+    ///
+    /// This corresponds to `stx_attributes_mask` in [`statx`].
+    ///
+    /// [`statx`]: https://man7.org/linux/man-pages/man2/statx.2.html
+    #[cfg(target_os = "linux")]
+    fn attributes_mask(&self) -> u64;
+
+    /// Returns `true` if the file is marked immutable.
+    ///
+    /// This corresponds to `STATX_ATTR_IMMUTABLE`.
+    #[cfg(target_os = "linux")]
+    #[inline]
+    fn is_immutable(&self) -> bool {
+        self.attributes() & STATX_ATTR_IMMUTABLE != 0
+    }
+
+    /// Returns `true` if the file is marked append-only.
+    ///
+    /// This corresponds to `STATX_ATTR_APPEND`.
+    #[cfg(target_os = "linux")]
+    #[inline]
+    fn is_append_only(&self) -> bool {
+        self.attributes() & STATX_ATTR_APPEND != 0
+    }
+
+    /// Returns `true` if the file is compressed by the filesystem.
+    ///
+    /// This corresponds to `STATX_ATTR_COMPRESSED`.
+    #[cfg(target_os = "linux")]
+    #[inline]
+    fn is_compressed(&self) -> bool {
+        self.attributes() & STATX_ATTR_COMPRESSED != 0
+    }
+
+    /// Returns the set of fields which were filled in.
+    ///
+    /// This corresponds to `stx_mask` in [`statx`]. Metadata which didn't
+    /// come from `statx` reports `StatxFields::BASIC_STATS`.
+    ///
+    /// [`statx`]: https://man7.org/linux/man-pages/man2/statx.2.html
+    #[cfg(target_os = "linux")]
+    fn statx_mask(&self) -> StatxFields;
 }
 
+// These are from <linux/stat.h>.
+#[cfg(target_os = "linux")]
+const STATX_ATTR_COMPRESSED: u64 = 0x0004;
+#[cfg(target_os = "linux")]
+const STATX_ATTR_IMMUTABLE: u64 = 0x0010;
+#[cfg(target_os = "linux")]
+const STATX_ATTR_APPEND: u64 = 0x0020;
+
 #[cfg(not(windows))]
 impl MetadataExt for std::fs::Metadata {
     #[inline]
@@ -49,6 +132,38 @@ impl MetadataExt for std::fs::Metadata {
     fn nlink(&self) -> u64 {
         std::os::unix::fs::MetadataExt::nlink(self)
     }
+
+    #[cfg(target_os = "linux")]
+    #[inline]
+    fn blksize(&self) -> u64 {
+        std::os::unix::fs::MetadataExt::blksize(self)
+    }
+
+    // `std::fs::Metadata` doesn't expose the `statx`-only fields.
+
+    #[cfg(target_os = "linux")]
+    #[inline]
+    fn mnt_id(&self) -> Option<u64> {
+        None
+    }
+
+    #[cfg(target_os = "linux")]
+    #[inline]
+    fn attributes(&self) -> u64 {
+        0
+    }
+
+    #[cfg(target_os = "linux")]
+    #[inline]
+    fn attributes_mask(&self) -> u64 {
+        0
+    }
+
+    #[cfg(target_os = "linux")]
+    #[inline]
+    fn statx_mask(&self) -> StatxFields {
+        StatxFields::BASIC_STATS
+    }
 }
 
 #[cfg(all(windows, windows_by_handle))]
@@ -90,6 +205,36 @@ impl MetadataExt for cap_primitives::fs::Metadata {
     fn nlink(&self) -> u64 {
         std::os::unix::fs::MetadataExt::nlink(self)
     }
+
+    #[cfg(target_os = "linux")]
+    #[inline]
+    fn blksize(&self) -> u64 {
+        std::os::unix::fs::MetadataExt::blksize(self)
+    }
+
+    #[cfg(target_os = "linux")]
+    #[inline]
+    fn mnt_id(&self) -> Option<u64> {
+        unsafe { _LinuxStatx::mnt_id(self) }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[inline]
+    fn attributes(&self) -> u64 {
+        unsafe { _LinuxStatx::attributes(self) }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[inline]
+    fn attributes_mask(&self) -> u64 {
+        unsafe { _LinuxStatx::attributes_mask(self) }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[inline]
+    fn statx_mask(&self) -> StatxFields {
+        StatxFields::from_bits(unsafe { _LinuxStatx::statx_mask(self) })
+    }
 }
 
 #[cfg(all(windows, any(feature = "std", feature = "async_std")))]
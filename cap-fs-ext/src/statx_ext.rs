@@ -0,0 +1,98 @@
+use crate::dir_ext::{as_file, via_entry};
+use cap_primitives::fs::{fstatx, statx, FollowSymlinks, Metadata};
+pub use cap_primitives::fs::{StatxFields, StatxSync};
+use std::{io, path::Path};
+
+/// Extension trait for `Dir` for querying metadata with [`statx`].
+///
+/// Unlike `metadata`, this can request just the fields that are needed, and
+/// control whether network filesystems synchronize with their servers, which
+/// can make it much cheaper. Fields which aren't requested may be missing
+/// from the result; `MetadataExt::statx_mask` reports which are present.
+///
+/// Paths are resolved with the same sandboxing as `open`.
+///
+/// [`statx`]: https://man7.org/linux/man-pages/man2/statx.2.html
+pub trait DirStatxExt {
+    /// Queries the `fields` of the metadata of the file at `path`.
+    ///
+    /// This fails with `ENOSYS` on Linux versions older than 4.11, which lack
+    /// `statx`.
+    fn statx<P: AsRef<Path>>(
+        &self,
+        path: P,
+        follow: FollowSymlinks,
+        fields: StatxFields,
+        sync: StatxSync,
+    ) -> io::Result<Metadata>;
+}
+
+/// Extension trait for `File` for querying metadata with [`statx`].
+///
+/// See [`DirStatxExt`] for details.
+///
+/// [`statx`]: https://man7.org/linux/man-pages/man2/statx.2.html
+/// [`DirStatxExt`]: trait.DirStatxExt.html
+pub trait FileStatxExt {
+    /// Queries the `fields` of the metadata of this file.
+    ///
+    /// This fails with `ENOSYS` on Linux versions older than 4.11, which lack
+    /// `statx`.
+    fn statx(&self, fields: StatxFields, sync: StatxSync) -> io::Result<Metadata>;
+}
+
+#[cfg(feature = "std")]
+impl DirStatxExt for cap_std::fs::Dir {
+    #[inline]
+    fn statx<P: AsRef<Path>>(
+        &self,
+        path: P,
+        follow: FollowSymlinks,
+        fields: StatxFields,
+        sync: StatxSync,
+    ) -> io::Result<Metadata> {
+        via_entry(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            path.as_ref(),
+            follow,
+            |dir, basename| statx(dir, basename, follow, fields, sync),
+        )
+    }
+}
+
+#[cfg(feature = "async_std")]
+impl DirStatxExt for cap_async_std::fs::Dir {
+    #[inline]
+    fn statx<P: AsRef<Path>>(
+        &self,
+        path: P,
+        follow: FollowSymlinks,
+        fields: StatxFields,
+        sync: StatxSync,
+    ) -> io::Result<Metadata> {
+        via_entry(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            path.as_ref(),
+            follow,
+            |dir, basename| statx(dir, basename, follow, fields, sync),
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl FileStatxExt for cap_std::fs::File {
+    #[inline]
+    fn statx(&self, fields: StatxFields, sync: StatxSync) -> io::Result<Metadata> {
+        fstatx(unsafe { &as_file(self) }, fields, sync)
+    }
+}
+
+#[cfg(feature = "async_std")]
+impl FileStatxExt for cap_async_std::fs::File {
+    #[inline]
+    fn statx(&self, fields: StatxFields, sync: StatxSync) -> io::Result<Metadata> {
+        fstatx(unsafe { &as_file(self) }, fields, sync)
+    }
+}
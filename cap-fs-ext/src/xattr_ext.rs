@@ -0,0 +1,297 @@
+use crate::dir_ext::{as_file, via_entry};
+use cap_primitives::fs::{
+    fgetxattr, flistxattr, fremovexattr, fsetxattr, getxattr, listxattr, removexattr, setxattr,
+    FollowSymlinks,
+};
+use std::{
+    ffi::{OsStr, OsString},
+    io,
+    path::Path,
+};
+
+/// Extension trait for `Dir` for extended attributes.
+///
+/// Paths are resolved with the same sandboxing as `open`. With
+/// `FollowSymlinks::No`, a symlink in the last component of the path is
+/// operated on itself, rather than its target.
+///
+/// Extended attributes are currently only supported on Linux; elsewhere these
+/// functions fail.
+pub trait DirXattrExt {
+    /// Returns the value of the extended attribute `name` of the file at
+    /// `path`.
+    ///
+    /// This corresponds to [`getxattr`] and [`lgetxattr`].
+    ///
+    /// [`getxattr`]: https://man7.org/linux/man-pages/man2/getxattr.2.html
+    /// [`lgetxattr`]: https://man7.org/linux/man-pages/man2/getxattr.2.html
+    fn getxattr<P: AsRef<Path>, N: AsRef<OsStr>>(
+        &self,
+        path: P,
+        name: N,
+        follow: FollowSymlinks,
+    ) -> io::Result<Vec<u8>>;
+
+    /// Sets the extended attribute `name` of the file at `path` to `value`,
+    /// creating it if it doesn't exist.
+    ///
+    /// This corresponds to [`setxattr`] and [`lsetxattr`].
+    ///
+    /// [`setxattr`]: https://man7.org/linux/man-pages/man2/setxattr.2.html
+    /// [`lsetxattr`]: https://man7.org/linux/man-pages/man2/setxattr.2.html
+    fn setxattr<P: AsRef<Path>, N: AsRef<OsStr>, V: AsRef<[u8]>>(
+        &self,
+        path: P,
+        name: N,
+        value: V,
+        follow: FollowSymlinks,
+    ) -> io::Result<()>;
+
+    /// Returns the names of the extended attributes of the file at `path`.
+    ///
+    /// This corresponds to [`listxattr`] and [`llistxattr`].
+    ///
+    /// [`listxattr`]: https://man7.org/linux/man-pages/man2/listxattr.2.html
+    /// [`llistxattr`]: https://man7.org/linux/man-pages/man2/listxattr.2.html
+    fn listxattr<P: AsRef<Path>>(
+        &self,
+        path: P,
+        follow: FollowSymlinks,
+    ) -> io::Result<Vec<OsString>>;
+
+    /// Removes the extended attribute `name` of the file at `path`.
+    ///
+    /// This corresponds to [`removexattr`] and [`lremovexattr`].
+    ///
+    /// [`removexattr`]: https://man7.org/linux/man-pages/man2/removexattr.2.html
+    /// [`lremovexattr`]: https://man7.org/linux/man-pages/man2/removexattr.2.html
+    fn removexattr<P: AsRef<Path>, N: AsRef<OsStr>>(
+        &self,
+        path: P,
+        name: N,
+        follow: FollowSymlinks,
+    ) -> io::Result<()>;
+}
+
+/// Extension trait for `File` for extended attributes.
+///
+/// Extended attributes are currently only supported on Linux; elsewhere these
+/// functions fail.
+pub trait FileXattrExt {
+    /// Returns the value of the extended attribute `name` of this file.
+    ///
+    /// This corresponds to [`fgetxattr`].
+    ///
+    /// [`fgetxattr`]: https://man7.org/linux/man-pages/man2/getxattr.2.html
+    fn getxattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<Vec<u8>>;
+
+    /// Sets the extended attribute `name` of this file to `value`, creating
+    /// it if it doesn't exist.
+    ///
+    /// This corresponds to [`fsetxattr`].
+    ///
+    /// [`fsetxattr`]: https://man7.org/linux/man-pages/man2/setxattr.2.html
+    fn setxattr<N: AsRef<OsStr>, V: AsRef<[u8]>>(&self, name: N, value: V) -> io::Result<()>;
+
+    /// Returns the names of the extended attributes of this file.
+    ///
+    /// This corresponds to [`flistxattr`].
+    ///
+    /// [`flistxattr`]: https://man7.org/linux/man-pages/man2/listxattr.2.html
+    fn listxattr(&self) -> io::Result<Vec<OsString>>;
+
+    /// Removes the extended attribute `name` of this file.
+    ///
+    /// This corresponds to [`fremovexattr`].
+    ///
+    /// [`fremovexattr`]: https://man7.org/linux/man-pages/man2/removexattr.2.html
+    fn removexattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<()>;
+}
+
+#[cfg(feature = "std")]
+impl DirXattrExt for cap_std::fs::Dir {
+    #[inline]
+    fn getxattr<P: AsRef<Path>, N: AsRef<OsStr>>(
+        &self,
+        path: P,
+        name: N,
+        follow: FollowSymlinks,
+    ) -> io::Result<Vec<u8>> {
+        via_entry(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            path.as_ref(),
+            follow,
+            |dir, basename| getxattr(dir, basename, name.as_ref(), follow),
+        )
+    }
+
+    #[inline]
+    fn setxattr<P: AsRef<Path>, N: AsRef<OsStr>, V: AsRef<[u8]>>(
+        &self,
+        path: P,
+        name: N,
+        value: V,
+        follow: FollowSymlinks,
+    ) -> io::Result<()> {
+        self.access().check_modify()?;
+        via_entry(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            path.as_ref(),
+            follow,
+            |dir, basename| setxattr(dir, basename, name.as_ref(), value.as_ref(), follow),
+        )
+    }
+
+    #[inline]
+    fn listxattr<P: AsRef<Path>>(
+        &self,
+        path: P,
+        follow: FollowSymlinks,
+    ) -> io::Result<Vec<OsString>> {
+        via_entry(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            path.as_ref(),
+            follow,
+            |dir, basename| listxattr(dir, basename, follow),
+        )
+    }
+
+    #[inline]
+    fn removexattr<P: AsRef<Path>, N: AsRef<OsStr>>(
+        &self,
+        path: P,
+        name: N,
+        follow: FollowSymlinks,
+    ) -> io::Result<()> {
+        self.access().check_modify()?;
+        via_entry(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            path.as_ref(),
+            follow,
+            |dir, basename| removexattr(dir, basename, name.as_ref(), follow),
+        )
+    }
+}
+
+#[cfg(feature = "async_std")]
+impl DirXattrExt for cap_async_std::fs::Dir {
+    #[inline]
+    fn getxattr<P: AsRef<Path>, N: AsRef<OsStr>>(
+        &self,
+        path: P,
+        name: N,
+        follow: FollowSymlinks,
+    ) -> io::Result<Vec<u8>> {
+        via_entry(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            path.as_ref(),
+            follow,
+            |dir, basename| getxattr(dir, basename, name.as_ref(), follow),
+        )
+    }
+
+    #[inline]
+    fn setxattr<P: AsRef<Path>, N: AsRef<OsStr>, V: AsRef<[u8]>>(
+        &self,
+        path: P,
+        name: N,
+        value: V,
+        follow: FollowSymlinks,
+    ) -> io::Result<()> {
+        self.access().check_modify()?;
+        via_entry(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            path.as_ref(),
+            follow,
+            |dir, basename| setxattr(dir, basename, name.as_ref(), value.as_ref(), follow),
+        )
+    }
+
+    #[inline]
+    fn listxattr<P: AsRef<Path>>(
+        &self,
+        path: P,
+        follow: FollowSymlinks,
+    ) -> io::Result<Vec<OsString>> {
+        via_entry(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            path.as_ref(),
+            follow,
+            |dir, basename| listxattr(dir, basename, follow),
+        )
+    }
+
+    #[inline]
+    fn removexattr<P: AsRef<Path>, N: AsRef<OsStr>>(
+        &self,
+        path: P,
+        name: N,
+        follow: FollowSymlinks,
+    ) -> io::Result<()> {
+        self.access().check_modify()?;
+        via_entry(
+            unsafe { &as_file(self) },
+            self.is_no_xdev(),
+            path.as_ref(),
+            follow,
+            |dir, basename| removexattr(dir, basename, name.as_ref(), follow),
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl FileXattrExt for cap_std::fs::File {
+    #[inline]
+    fn getxattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<Vec<u8>> {
+        fgetxattr(unsafe { &as_file(self) }, name.as_ref())
+    }
+
+    #[inline]
+    fn setxattr<N: AsRef<OsStr>, V: AsRef<[u8]>>(&self, name: N, value: V) -> io::Result<()> {
+        self.access().check_modify()?;
+        fsetxattr(unsafe { &as_file(self) }, name.as_ref(), value.as_ref())
+    }
+
+    #[inline]
+    fn listxattr(&self) -> io::Result<Vec<OsString>> {
+        flistxattr(unsafe { &as_file(self) })
+    }
+
+    #[inline]
+    fn removexattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<()> {
+        self.access().check_modify()?;
+        fremovexattr(unsafe { &as_file(self) }, name.as_ref())
+    }
+}
+
+#[cfg(feature = "async_std")]
+impl FileXattrExt for cap_async_std::fs::File {
+    #[inline]
+    fn getxattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<Vec<u8>> {
+        fgetxattr(unsafe { &as_file(self) }, name.as_ref())
+    }
+
+    #[inline]
+    fn setxattr<N: AsRef<OsStr>, V: AsRef<[u8]>>(&self, name: N, value: V) -> io::Result<()> {
+        self.access().check_modify()?;
+        fsetxattr(unsafe { &as_file(self) }, name.as_ref(), value.as_ref())
+    }
+
+    #[inline]
+    fn listxattr(&self) -> io::Result<Vec<OsString>> {
+        flistxattr(unsafe { &as_file(self) })
+    }
+
+    #[inline]
+    fn removexattr<N: AsRef<OsStr>>(&self, name: N) -> io::Result<()> {
+        self.access().check_modify()?;
+        fremovexattr(unsafe { &as_file(self) }, name.as_ref())
+    }
+}
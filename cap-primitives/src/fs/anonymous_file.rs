@@ -0,0 +1,110 @@
+//! This defines `create_anonymous_file`, which creates files without names,
+//! and `link_file_at`, which gives them names.
+
+#[cfg(target_os = "linux")]
+use crate::fs::{link_file_at_impl, open_tmpfile_impl};
+use crate::fs::{open, remove_file, OpenOptions};
+use std::{
+    collections::hash_map::RandomState,
+    ffi::{OsStr, OsString},
+    fs,
+    hash::{BuildHasher, Hasher},
+    io,
+    path::Path,
+};
+
+/// The number of times to retry when a randomly chosen temporary name is
+/// already taken.
+const MAX_ATTEMPTS: usize = 16;
+
+/// Creates a new file in the directory `start` which has no name, and which
+/// is deleted when it's closed unless it's given a name by [`link_file_at`].
+///
+/// On Linux this uses `O_TMPFILE`. Where that isn't supported, the file is
+/// created with a unique name and immediately removed, and it can't be given
+/// a name later.
+///
+/// [`link_file_at`]: fn.link_file_at.html
+pub fn create_anonymous_file(start: &fs::File) -> io::Result<fs::File> {
+    if let Some(file) = open_tmpfile(start)? {
+        return Ok(file);
+    }
+
+    let (file, name) = create_temp(start, OsStr::new("anonymous"))?;
+    remove_file(start, Path::new(&name))?;
+    Ok(file)
+}
+
+/// Gives a file created by [`create_anonymous_file`] the name `path`,
+/// ensuring that the resolution of the path never escapes the directory tree
+/// rooted at `start`.
+///
+/// This fails if `path` already exists, if the file was created without
+/// `O_TMPFILE`, or if `file` already has a name, either because it wasn't
+/// created by `create_anonymous_file` or because it's already been linked.
+///
+/// [`create_anonymous_file`]: fn.create_anonymous_file.html
+#[inline]
+pub fn link_file_at(file: &fs::File, start: &fs::File, path: &Path) -> io::Result<()> {
+    link_file_at_impl(file, start, path)
+}
+
+/// Opens an unnamed temporary file in `dir`, if the platform supports it.
+#[cfg(target_os = "linux")]
+#[inline]
+pub(crate) fn open_tmpfile(dir: &fs::File) -> io::Result<Option<fs::File>> {
+    open_tmpfile_impl(dir)
+}
+
+/// Opens an unnamed temporary file in `dir`, if the platform supports it.
+#[cfg(not(target_os = "linux"))]
+#[inline]
+#[allow(clippy::unnecessary_wraps)]
+pub(crate) fn open_tmpfile(_dir: &fs::File) -> io::Result<Option<fs::File>> {
+    Ok(None)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn link_file_at_impl(_file: &fs::File, _start: &fs::File, _path: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "linking anonymous files isn't supported on this platform",
+    ))
+}
+
+/// Returns a name for a temporary file which is likely to be unique.
+pub(crate) fn temp_name(name: &OsStr) -> OsString {
+    // Each `RandomState` is seeded differently, so this is a cheap source of
+    // randomness which doesn't need any extra dependencies.
+    let random = RandomState::new().build_hasher().finish();
+    let mut temp_name = OsString::from(".");
+    temp_name.push(name);
+    temp_name.push(format!(".{:016x}.tmp", random));
+    temp_name
+}
+
+/// Creates a new temporary file with a unique name in `dir`.
+pub(crate) fn create_temp(dir: &fs::File, name: &OsStr) -> io::Result<(fs::File, OsString)> {
+    let mut options = OpenOptions::new();
+    options.read(true).write(true).create_new(true);
+    retry_names(name, |temp_name| open(dir, Path::new(temp_name), &options))
+}
+
+/// Calls `f` with unique temporary names based on `name` until it doesn't
+/// fail with `AlreadyExists`.
+pub(crate) fn retry_names<T>(
+    name: &OsStr,
+    mut f: impl FnMut(&OsStr) -> io::Result<T>,
+) -> io::Result<(T, OsString)> {
+    let mut attempts = 0;
+    loop {
+        let temp_name = temp_name(name);
+        match f(&temp_name) {
+            Ok(t) => return Ok((t, temp_name)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempts < MAX_ATTEMPTS => {
+                attempts += 1
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! This defines `AtomicFile`, which replaces a file's contents atomically.
+
+use crate::fs::{
+    create_temp, link_file_at, open_dir, open_tmpfile, remove_file, rename, retry_names, stat,
+    FollowSymlinks,
+};
+use std::{
+    ffi::OsString,
+    fmt, fs,
+    io::{self, IoSlice, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+/// A file which replaces the file at its path only when it's committed.
+///
+/// Writes go to a temporary file in the same directory as the target. When
+/// [`commit`] is called, the temporary file is synced and renamed over the
+/// target, and the directory is synced, so that after a crash the target has
+/// either its old contents or its new contents, and never a mixture. If the
+/// `AtomicFile` is dropped without being committed, the target is left
+/// unchanged and the temporary file is removed.
+///
+/// Where possible, the temporary file is created with `O_TMPFILE`, so that it
+/// has no name until it's committed. Otherwise, it has a uniquely generated
+/// name starting with `.` and the target's name.
+///
+/// If the target already exists, the new file is given its permissions. If
+/// the target is a symlink, the symlink itself is replaced.
+///
+/// [`commit`]: #method.commit
+pub struct AtomicFile {
+    file: Option<fs::File>,
+    dir: fs::File,
+    name: OsString,
+    temp_name: Option<OsString>,
+}
+
+impl AtomicFile {
+    /// Creates a new `AtomicFile` which will replace the file at `path`,
+    /// ensuring that the resolution of the path never escapes the directory
+    /// tree rooted at `start`.
+    pub fn new(start: &fs::File, path: &Path) -> io::Result<Self> {
+        let name = path.file_name().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the path doesn't end in a file name",
+            )
+        })?;
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => open_dir(start, parent)?,
+            _ => open_dir(start, Path::new("."))?,
+        };
+
+        let (file, temp_name) = match open_tmpfile(&dir)? {
+            Some(file) => (file, None),
+            None => {
+                let (file, temp_name) = create_temp(&dir, name)?;
+                (file, Some(temp_name))
+            }
+        };
+        let atomic_file = Self {
+            file: Some(file),
+            dir,
+            name: name.to_os_string(),
+            temp_name,
+        };
+
+        if let Ok(metadata) = stat(&atomic_file.dir, Path::new(name), FollowSymlinks::No) {
+            if metadata.is_file() {
+                let file = atomic_file.file();
+                file.set_permissions(metadata.permissions().into_std(file)?)?;
+            }
+        }
+
+        Ok(atomic_file)
+    }
+
+    /// Returns the temporary file being written.
+    #[inline]
+    pub fn file(&self) -> &fs::File {
+        self.file.as_ref().unwrap()
+    }
+
+    /// Syncs the temporary file and renames it over the target, and then
+    /// syncs the directory containing it.
+    pub fn commit(mut self) -> io::Result<()> {
+        let file = self.file.take().unwrap();
+        file.sync_all()?;
+
+        if self.temp_name.is_none() {
+            let ((), temp_name) = retry_names(&self.name, |temp_name| {
+                link_file_at(&file, &self.dir, Path::new(temp_name))
+            })?;
+            self.temp_name = Some(temp_name);
+        }
+
+        // Close the file before renaming it, as Windows doesn't allow open
+        // files to be renamed.
+        drop(file);
+
+        let temp_name = self.temp_name.as_ref().unwrap();
+        rename(
+            &self.dir,
+            Path::new(temp_name),
+            &self.dir,
+            Path::new(&self.name),
+        )?;
+        self.temp_name = None;
+
+        sync_dir(&self.dir)
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        drop(self.file.take());
+        if let Some(temp_name) = &self.temp_name {
+            remove_file(&self.dir, Path::new(temp_name)).ok();
+        }
+    }
+}
+
+impl Write for AtomicFile {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file().write(buf)
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.file().write_vectored(bufs)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.file().flush()
+    }
+}
+
+impl Seek for AtomicFile {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.file().seek(pos)
+    }
+}
+
+impl fmt::Debug for AtomicFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("AtomicFile");
+        b.field("file", &self.file);
+        b.field("name", &self.name);
+        b.field("temp_name", &self.temp_name);
+        b.finish()
+    }
+}
+
+#[cfg(not(windows))]
+fn sync_dir(dir: &fs::File) -> io::Result<()> {
+    dir.sync_all()
+}
+
+// Windows doesn't support syncing directory handles.
+#[cfg(windows)]
+#[allow(clippy::unnecessary_wraps)]
+fn sync_dir(_dir: &fs::File) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,358 @@
+//! This defines `copy_dir_all`, which copies a directory tree from one
+//! directory to another, possibly different, directory.
+
+use crate::fs::{
+    copy_impl, create_dir, open, read_dir, read_dir_no_xdev, read_link, set_permissions, set_times,
+    stat, stat_no_xdev, via_parent_no_xdev, DirOptions, FileType, FollowSymlinks, Metadata,
+    OpenOptions, Permissions, ReadDir, SystemTimeSpec,
+};
+use std::{fmt, fs, io, path::Path, sync::Arc};
+
+/// Options for [`copy_dir_all`].
+///
+/// By default, permissions are preserved, as with [`copy`], and timestamps
+/// are not.
+///
+/// [`copy_dir_all`]: fn.copy_dir_all.html
+/// [`copy`]: fn.copy.html
+#[derive(Clone)]
+pub struct CopyDirOptions {
+    preserve_permissions: bool,
+    preserve_times: bool,
+    progress: Option<Arc<ProgressFn>>,
+}
+
+type ProgressFn = dyn Fn(&CopyProgress) + Send + Sync;
+
+impl CopyDirOptions {
+    /// Creates a new set of options with the defaults.
+    #[allow(clippy::new_without_default)]
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            preserve_permissions: true,
+            preserve_times: false,
+            progress: None,
+        }
+    }
+
+    /// Sets the option to give copied files and directories the permissions
+    /// of the originals. Otherwise, they have the permissions of newly
+    /// created files and directories.
+    #[inline]
+    pub fn preserve_permissions(&mut self, preserve_permissions: bool) -> &mut Self {
+        self.preserve_permissions = preserve_permissions;
+        self
+    }
+
+    /// Sets the option to give copied files and directories the access and
+    /// modification times of the originals. The times of symlinks aren't
+    /// preserved.
+    #[inline]
+    pub fn preserve_times(&mut self, preserve_times: bool) -> &mut Self {
+        self.preserve_times = preserve_times;
+        self
+    }
+
+    /// Sets a callback to be called after each file, directory, or symlink
+    /// is copied. Directories are reported after their contents.
+    #[inline]
+    pub fn progress<F>(&mut self, progress: F) -> &mut Self
+    where
+        F: Fn(&CopyProgress) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+}
+
+impl fmt::Debug for CopyDirOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("CopyDirOptions");
+        b.field("preserve_permissions", &self.preserve_permissions);
+        b.field("preserve_times", &self.preserve_times);
+        b.field("progress", &self.progress.is_some());
+        b.finish()
+    }
+}
+
+/// The progress of a [`copy_dir_all`] operation, passed to the callback set
+/// with [`CopyDirOptions::progress`].
+///
+/// [`copy_dir_all`]: fn.copy_dir_all.html
+/// [`CopyDirOptions::progress`]: struct.CopyDirOptions.html#method.progress
+#[derive(Debug)]
+pub struct CopyProgress<'a> {
+    path: &'a Path,
+    file_type: FileType,
+    bytes: u64,
+    total_bytes: u64,
+    entries: u64,
+}
+
+impl<'a> CopyProgress<'a> {
+    /// Returns the path of the entry which was just copied, relative to the
+    /// directory being copied.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        self.path
+    }
+
+    /// Returns the file type of the entry which was just copied.
+    #[inline]
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    /// Returns the number of bytes copied for the entry which was just
+    /// copied, which is zero for anything other than a regular file.
+    #[inline]
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Returns the total number of bytes copied so far.
+    #[inline]
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    /// Returns the total number of entries copied so far.
+    #[inline]
+    pub fn entries(&self) -> u64 {
+        self.entries
+    }
+}
+
+/// Recursively copies the directory at `from_path` to a new directory at
+/// `to_path`, returning the total number of bytes copied.
+///
+/// Regular files are copied with [`copy`], and symlinks are recreated with
+/// the same target, without being followed. Other kinds of files can't be
+/// copied, and cause an error. `to_path` must not already exist.
+///
+/// [`copy`]: fn.copy.html
+pub fn copy_dir_all(
+    from_start: &fs::File,
+    from_path: &Path,
+    to_start: &fs::File,
+    to_path: &Path,
+    options: &CopyDirOptions,
+) -> io::Result<u64> {
+    copy_dir_all_impl(from_start, from_path, to_start, to_path, options, false)
+}
+
+/// Like `copy_dir_all`, but fails rather than copy anything from a different
+/// filesystem than `from_start`.
+pub fn copy_dir_all_no_xdev(
+    from_start: &fs::File,
+    from_path: &Path,
+    to_start: &fs::File,
+    to_path: &Path,
+    options: &CopyDirOptions,
+) -> io::Result<u64> {
+    copy_dir_all_impl(from_start, from_path, to_start, to_path, options, true)
+}
+
+fn copy_dir_all_impl(
+    from_start: &fs::File,
+    from_path: &Path,
+    to_start: &fs::File,
+    to_path: &Path,
+    options: &CopyDirOptions,
+    no_xdev: bool,
+) -> io::Result<u64> {
+    let metadata = if no_xdev {
+        stat_no_xdev(from_start, from_path, FollowSymlinks::Yes)?
+    } else {
+        stat(from_start, from_path, FollowSymlinks::Yes)?
+    };
+    if !metadata.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the source path is not a directory",
+        ));
+    }
+
+    create_dir(to_start, to_path, &DirOptions::new())?;
+
+    let mut copier = Copier {
+        from_start,
+        to_start,
+        options,
+        no_xdev,
+        // Remember the new directory, so that copying a directory into
+        // itself doesn't recurse forever.
+        to_root: stat(to_start, to_path, FollowSymlinks::No)?,
+        total_bytes: 0,
+        entries: 0,
+    };
+    copier.copy_dir(from_path, to_path, Path::new(""), &metadata)?;
+    Ok(copier.total_bytes)
+}
+
+struct Copier<'a> {
+    from_start: &'a fs::File,
+    to_start: &'a fs::File,
+    options: &'a CopyDirOptions,
+    no_xdev: bool,
+    to_root: Metadata,
+    total_bytes: u64,
+    entries: u64,
+}
+
+impl<'a> Copier<'a> {
+    /// Copies the contents of the directory at `from`, which has already been
+    /// created at `to`, and then its attributes.
+    fn copy_dir(
+        &mut self,
+        from: &Path,
+        to: &Path,
+        relative: &Path,
+        metadata: &Metadata,
+    ) -> io::Result<()> {
+        for entry in self.read_dir(from)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let (from, to, relative) = (from.join(&name), to.join(&name), relative.join(&name));
+            let metadata = if self.no_xdev {
+                stat_no_xdev(self.from_start, &from, FollowSymlinks::No)?
+            } else {
+                stat(self.from_start, &from, FollowSymlinks::No)?
+            };
+            let file_type = metadata.file_type();
+
+            if file_type.is_dir() {
+                if self.is_to_root(&metadata) {
+                    continue;
+                }
+                create_dir(self.to_start, &to, &DirOptions::new())?;
+                self.copy_dir(&from, &to, &relative, &metadata)?;
+            } else if file_type.is_file() {
+                let bytes = self.copy_file(&from, &to, &metadata)?;
+                self.report(&relative, file_type, bytes);
+            } else if file_type.is_symlink() {
+                self.copy_symlink(&from, &to)?;
+                self.report(&relative, file_type, 0);
+            } else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("can't copy special file {}", from.display()),
+                ));
+            }
+        }
+
+        // Set the attributes last, so that read-only directories can be
+        // filled and their times aren't updated by creating their contents.
+        self.set_attributes(to, metadata)?;
+        if !relative.as_os_str().is_empty() {
+            self.report(relative, metadata.file_type(), 0);
+        }
+        Ok(())
+    }
+
+    fn copy_file(&mut self, from: &Path, to: &Path, metadata: &Metadata) -> io::Result<u64> {
+        // `copy_impl` always copies the permissions, so to not preserve them,
+        // create the file first to find out what they would have been.
+        let permissions = if self.options.preserve_permissions {
+            None
+        } else {
+            let file = open(
+                self.to_start,
+                to,
+                OpenOptions::new().write(true).create_new(true),
+            )?;
+            Some(Permissions::from_std(file.metadata()?.permissions()))
+        };
+
+        let bytes =
+            self.via_from_parent(from, |dir, from| copy_impl(dir, from, self.to_start, to))?;
+
+        if let Some(permissions) = permissions {
+            set_permissions(self.to_start, to, permissions)?;
+        }
+        self.set_times(to, metadata)?;
+        Ok(bytes)
+    }
+
+    fn read_dir(&self, from: &Path) -> io::Result<ReadDir> {
+        if self.no_xdev {
+            read_dir_no_xdev(self.from_start, from)
+        } else {
+            read_dir(self.from_start, from)
+        }
+    }
+
+    /// Calls `f` with the directory to resolve `from` in and what's left of
+    /// `from`, so that with `no_xdev`, only the last component is resolved
+    /// by `f`. Entries are already known to be on the same filesystem.
+    fn via_from_parent<T, F>(&self, from: &Path, f: F) -> io::Result<T>
+    where
+        F: FnOnce(&fs::File, &Path) -> io::Result<T>,
+    {
+        if self.no_xdev {
+            via_parent_no_xdev(self.from_start, from, f)
+        } else {
+            f(self.from_start, from)
+        }
+    }
+
+    #[cfg(not(windows))]
+    fn copy_symlink(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let target = self.via_from_parent(from, read_link)?;
+        crate::fs::symlink(&target, self.to_start, to)
+    }
+
+    #[cfg(windows)]
+    fn copy_symlink(&self, from: &Path, to: &Path) -> io::Result<()> {
+        use crate::fs::{symlink_dir, symlink_file};
+
+        let target = self.via_from_parent(from, read_link)?;
+        match stat(self.from_start, from, FollowSymlinks::Yes) {
+            Ok(metadata) if metadata.is_dir() => symlink_dir(&target, self.to_start, to),
+            _ => symlink_file(&target, self.to_start, to),
+        }
+    }
+
+    fn set_attributes(&self, to: &Path, metadata: &Metadata) -> io::Result<()> {
+        if self.options.preserve_permissions {
+            set_permissions(self.to_start, to, metadata.permissions())?;
+        }
+        self.set_times(to, metadata)
+    }
+
+    fn set_times(&self, to: &Path, metadata: &Metadata) -> io::Result<()> {
+        if !self.options.preserve_times {
+            return Ok(());
+        }
+        let atime = metadata.accessed().ok().map(SystemTimeSpec::Absolute);
+        let mtime = metadata.modified().ok().map(SystemTimeSpec::Absolute);
+        set_times(self.to_start, to, atime, mtime, FollowSymlinks::No)
+    }
+
+    fn report(&mut self, relative: &Path, file_type: FileType, bytes: u64) {
+        self.total_bytes += bytes;
+        self.entries += 1;
+        if let Some(progress) = &self.options.progress {
+            progress(&CopyProgress {
+                path: relative,
+                file_type,
+                bytes,
+                total_bytes: self.total_bytes,
+                entries: self.entries,
+            });
+        }
+    }
+
+    #[cfg(any(not(windows), windows_by_handle))]
+    fn is_to_root(&self, metadata: &Metadata) -> bool {
+        metadata.is_same_file(&self.to_root)
+    }
+
+    // TODO: Detect copying a directory into itself on Windows once
+    // `windows_by_handle` is stable.
+    #[cfg(not(any(not(windows), windows_by_handle)))]
+    fn is_to_root(&self, _metadata: &Metadata) -> bool {
+        false
+    }
+}
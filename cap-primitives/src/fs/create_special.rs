@@ -0,0 +1,40 @@
+//! This defines `create_fifo` and `create_device_node`, which create special
+//! files.
+
+use crate::fs::via_parent;
+use std::{fs, io, path::Path};
+
+/// Perform a `mkfifoat`-like operation, ensuring that the resolution of the
+/// path never escapes the directory tree rooted at `start`.
+///
+/// `mode` holds the permission bits of the new FIFO, which are modified by
+/// the process' umask, as with [`DirBuilderExt::mode`].
+///
+/// [`DirBuilderExt::mode`]: https://doc.rust-lang.org/std/os/unix/fs/trait.DirBuilderExt.html#tymethod.mode
+#[inline]
+pub fn create_fifo(start: &fs::File, path: &Path, mode: u32) -> io::Result<()> {
+    via_parent::create_fifo(start, path, mode)
+}
+
+/// Perform a `mknodat`-like operation to create a block or character device
+/// node, ensuring that the resolution of the path never escapes the directory
+/// tree rooted at `start`.
+///
+/// `mode` holds the file type, which must be `S_IFBLK` or `S_IFCHR`, along
+/// with the permission bits, which are modified by the process' umask. `dev`
+/// is the device number, as with `makedev`.
+///
+/// # Safety
+///
+/// A device node provides access to the device it names, independent of the
+/// directory it's created in, so this can grant access to resources outside
+/// of the directory tree rooted at `start`.
+#[inline]
+pub unsafe fn create_device_node(
+    start: &fs::File,
+    path: &Path,
+    mode: u32,
+    dev: u64,
+) -> io::Result<()> {
+    via_parent::create_device_node(start, path, mode, dev)
+}
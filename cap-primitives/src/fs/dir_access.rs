@@ -0,0 +1,100 @@
+//! This defines `DirAccess`, which describes which modifications a `Dir` is
+//! permitted to make within its tree.
+
+use crate::fs::{errors, OpenOptions};
+use std::{cmp, io};
+
+/// Which modifications a `Dir` permits within its tree, independent of the
+/// mode its underlying handle was opened with.
+///
+/// Every level permits reading. The levels are ordered from least to most
+/// permissive, and each permits everything the levels before it permit.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum DirAccess {
+    /// Nothing may be created, written, removed, or renamed, and permissions
+    /// may not be changed.
+    ReadOnly,
+
+    /// Like `ReadOnly`, except that new files and directories may be
+    /// created. Files may only be opened for writing if they're being
+    /// created with `create_new`.
+    CreateOnly,
+
+    /// Like `CreateOnly`, except that files may also be opened for
+    /// appending. They may not be truncated or opened for writing in any
+    /// other way.
+    AppendOnly,
+
+    /// Everything is permitted.
+    ReadWrite,
+}
+
+impl DirAccess {
+    /// Returns the less permissive of `self` and `other`.
+    #[inline]
+    pub fn restrict(self, other: Self) -> Self {
+        cmp::min(self, other)
+    }
+
+    /// Checks whether opening a file with `options` is permitted.
+    pub fn check_open(self, options: &OpenOptions) -> io::Result<()> {
+        // Platform-specific options, such as `custom_flags` with `O_TRUNC`,
+        // can also ask for modifications.
+        #[cfg(any(unix, windows, target_os = "vxworks"))]
+        let ext_modifying = options.ext.is_modifying();
+        #[cfg(not(any(unix, windows, target_os = "vxworks")))]
+        let ext_modifying = false;
+
+        let modifying = options.write
+            || options.append
+            || options.truncate
+            || options.create
+            || options.create_new
+            || ext_modifying;
+
+        let permitted = !modifying
+            || match self {
+                Self::ReadOnly => false,
+                Self::CreateOnly => options.create_new,
+                Self::AppendOnly => {
+                    !options.truncate && (options.append || !options.write) && !ext_modifying
+                }
+                Self::ReadWrite => true,
+            };
+
+        if permitted {
+            Ok(())
+        } else {
+            Err(errors::access_restricted())
+        }
+    }
+
+    /// Checks whether creating a new directory entry, such as with
+    /// `create_dir`, is permitted.
+    pub fn check_create(self) -> io::Result<()> {
+        if self >= Self::CreateOnly {
+            Ok(())
+        } else {
+            Err(errors::access_restricted())
+        }
+    }
+
+    /// Checks whether modifying existing directory entries, such as with
+    /// `remove_file`, `rename`, or `set_permissions`, is permitted. Creating
+    /// links also needs this, since a link can give an existing file a
+    /// second name.
+    pub fn check_modify(self) -> io::Result<()> {
+        if self == Self::ReadWrite {
+            Ok(())
+        } else {
+            Err(errors::access_restricted())
+        }
+    }
+}
+
+impl Default for DirAccess {
+    #[inline]
+    fn default() -> Self {
+        Self::ReadWrite
+    }
+}
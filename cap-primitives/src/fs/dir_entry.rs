@@ -1,9 +1,13 @@
-use crate::fs::{dir_options, DirEntryInner, FileType, Metadata, OpenOptions, ReadDir};
+#[cfg(unix)]
+use crate::fs::check_same_fs;
+use crate::fs::{
+    dir_options, read_dir_no_xdev, DirEntryInner, FileType, Metadata, OpenOptions, ReadDir,
+};
 #[cfg(unix)]
 use std::os::unix::fs::DirEntryExt;
 #[cfg(target_os = "wasi")]
 use std::os::wasi::fs::DirEntryExt;
-use std::{ffi::OsString, fmt, fs, io};
+use std::{ffi::OsString, fmt, fs, io, path::Component};
 
 /// Entries returned by the `ReadDir` iterator.
 ///
@@ -24,6 +28,7 @@ use std::{ffi::OsString, fmt, fs, io};
 /// [`std::fs::DirEntry`]: https://doc.rust-lang.org/std/fs/struct.DirEntry.html
 pub struct DirEntry {
     pub(crate) inner: DirEntryInner,
+    pub(crate) no_xdev: bool,
 }
 
 impl DirEntry {
@@ -36,7 +41,11 @@ impl DirEntry {
     /// Open the file with the given options.
     #[inline]
     pub fn open_with(&self, options: &OpenOptions) -> io::Result<fs::File> {
-        self.inner.open(options)
+        if self.no_xdev {
+            self.inner.open(options.clone().no_xdev(true))
+        } else {
+            self.inner.open(options)
+        }
     }
 
     /// Open the entry as a directory.
@@ -60,7 +69,11 @@ impl DirEntry {
     /// Returns an iterator over the entries within the subdirectory.
     #[inline]
     pub fn read_dir(&self) -> io::Result<ReadDir> {
-        self.inner.read_dir()
+        if self.no_xdev {
+            read_dir_no_xdev(&self.open_dir()?, Component::CurDir.as_ref())
+        } else {
+            self.inner.read_dir()
+        }
     }
 
     /// Returns the metadata for the file that this entry points at.
@@ -70,7 +83,12 @@ impl DirEntry {
     /// [`std::fs::DirEntry::metadata`]: https://doc.rust-lang.org/std/fs/struct.DirEntry.html#method.metadata
     #[inline]
     pub fn metadata(&self) -> io::Result<Metadata> {
-        self.inner.metadata()
+        let metadata = self.inner.metadata()?;
+        #[cfg(unix)]
+        if self.no_xdev {
+            check_same_fs(&self.inner.dir_metadata()?, &metadata)?;
+        }
+        Ok(metadata)
     }
 
     /// Returns the file type for the file that this entry points at.
@@ -12,3 +12,36 @@ pub(crate) fn escape_attempt() -> io::Error {
         "a path led outside of the filesystem",
     )
 }
+
+#[cold]
+pub(crate) fn xdev_attempt() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        "a path crossed a mount point",
+    )
+}
+
+#[cold]
+pub(crate) fn symlink_attempt() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        "a path traversed a symlink",
+    )
+}
+
+#[cold]
+pub(crate) fn access_restricted() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        "the directory's access restrictions don't permit this",
+    )
+}
+
+#[cfg(target_os = "linux")]
+#[cold]
+pub(crate) fn not_anonymous() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "only files without names may be linked",
+    )
+}
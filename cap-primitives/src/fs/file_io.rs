@@ -0,0 +1,54 @@
+//! This defines `read_at`, `write_at`, and their vectored forms, which do
+//! I/O at a given offset in a file without using or changing its current
+//! position.
+
+use crate::fs::{read_at_impl, read_vectored_at_impl, write_at_impl, write_vectored_at_impl};
+use std::{
+    fs,
+    io::{self, IoSlice, IoSliceMut},
+};
+
+/// Reads from `file` at `offset` into `buf`, returning the number of bytes
+/// read.
+///
+/// This corresponds to [`std::os::unix::fs::FileExt::read_at`], except that
+/// on Windows it also leaves the current position unchanged.
+///
+/// [`std::os::unix::fs::FileExt::read_at`]: https://doc.rust-lang.org/std/os/unix/fs/trait.FileExt.html#tymethod.read_at
+#[inline]
+pub fn read_at(file: &fs::File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+    read_at_impl(file, buf, offset)
+}
+
+/// Writes `buf` to `file` at `offset`, returning the number of bytes written.
+///
+/// This corresponds to [`std::os::unix::fs::FileExt::write_at`], except that
+/// on Windows it also leaves the current position unchanged.
+///
+/// [`std::os::unix::fs::FileExt::write_at`]: https://doc.rust-lang.org/std/os/unix/fs/trait.FileExt.html#tymethod.write_at
+#[inline]
+pub fn write_at(file: &fs::File, buf: &[u8], offset: u64) -> io::Result<usize> {
+    write_at_impl(file, buf, offset)
+}
+
+/// Like `read_at`, but reads into a sequence of buffers, as with `preadv`.
+///
+/// Where `preadv` isn't available, this reads into the first non-empty
+/// buffer.
+#[inline]
+pub fn read_vectored_at(
+    file: &fs::File,
+    bufs: &mut [IoSliceMut],
+    offset: u64,
+) -> io::Result<usize> {
+    read_vectored_at_impl(file, bufs, offset)
+}
+
+/// Like `write_at`, but writes from a sequence of buffers, as with `pwritev`.
+///
+/// Where `pwritev` isn't available, this writes from the first non-empty
+/// buffer.
+#[inline]
+pub fn write_vectored_at(file: &fs::File, bufs: &[IoSlice], offset: u64) -> io::Result<usize> {
+    write_vectored_at_impl(file, bufs, offset)
+}
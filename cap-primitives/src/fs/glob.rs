@@ -0,0 +1,382 @@
+//! Matching of shell-style wildcard patterns against the contents of a
+//! directory.
+
+use crate::fs::{read_dir, read_dir_no_xdev, stat, stat_no_xdev, FollowSymlinks};
+use std::{
+    collections::HashSet,
+    ffi::OsString,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// An iterator over the paths matching a glob pattern, relative to the
+/// directory it was started in.
+///
+/// Patterns are split into components at `/`, and each component may
+/// contain:
+///
+///  - `?`, which matches any single character,
+///  - `*`, which matches any sequence of characters, including none,
+///  - `[...]`, which matches any one of the enclosed characters or ranges
+///    such as `a-z`, and `[!...]` or `[^...]`, which match any character
+///    which isn't enclosed.
+///
+/// A component which is exactly `**` matches any number of directories,
+/// including none. Symlinks are followed when they're named by the pattern,
+/// but `**` doesn't descend into them.
+///
+/// A `..` component refers to the parent of the path matched so far. It's
+/// resolved lexically, and a `..` which would refer to the parent of the
+/// starting directory matches nothing, so matches never leave the starting
+/// directory.
+///
+/// Paths are yielded at most once each. The entries of each directory are
+/// visited in order of their file names.
+pub struct Glob {
+    start: fs::File,
+    components: Vec<Component>,
+    case_insensitive: bool,
+    no_xdev: bool,
+    started: bool,
+    stack: Vec<(PathBuf, usize)>,
+    seen: HashSet<PathBuf>,
+}
+
+enum Component {
+    /// `..`.
+    Parent,
+    /// `**`.
+    Recursive,
+    /// Anything else. Components without wildcards also have a `literal`
+    /// form, which can be looked up without reading the whole directory.
+    Name {
+        tokens: Vec<Token>,
+        literal: Option<String>,
+    },
+}
+
+enum Token {
+    Char(char),
+    AnyChar,
+    AnyString,
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+impl Glob {
+    /// Constructs a new `Glob` which matches `pattern` against the contents
+    /// of `start`.
+    ///
+    /// This fails with `io::ErrorKind::InvalidInput` if `pattern` is absolute
+    /// or contains an unterminated character class.
+    pub fn new(start: fs::File, pattern: &str) -> io::Result<Self> {
+        Ok(Self {
+            start,
+            components: parse(pattern)?,
+            case_insensitive: false,
+            no_xdev: false,
+            started: false,
+            stack: Vec::new(),
+            seen: HashSet::new(),
+        })
+    }
+
+    /// Sets the option to match names without regard to case.
+    #[inline]
+    pub fn case_insensitive(&mut self, case_insensitive: bool) -> &mut Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Sets the option to fail rather than match paths through a mount point.
+    #[inline]
+    pub fn no_xdev(&mut self, no_xdev: bool) -> &mut Self {
+        self.no_xdev = no_xdev;
+        self
+    }
+
+    /// Reads the entries of the directory at `path`, sorted by name, along
+    /// with whether each one is a directory, without following symlinks.
+    fn entries(&self, path: &Path) -> io::Result<Vec<(OsString, bool)>> {
+        let mut entries = Vec::new();
+        let read_dir = if self.no_xdev {
+            read_dir_no_xdev(&self.start, as_relative(path))?
+        } else {
+            read_dir(&self.start, as_relative(path))?
+        };
+        for entry in read_dir {
+            let entry = entry?;
+            let is_dir = entry.file_type()?.is_dir();
+            entries.push((entry.file_name(), is_dir));
+        }
+        entries.sort();
+        Ok(entries)
+    }
+
+    /// Tests whether `path` exists, and if `need_dir` is set, whether it
+    /// resolves to a directory.
+    fn exists(&self, path: &Path, need_dir: bool) -> bool {
+        let stat = if self.no_xdev { stat_no_xdev } else { stat };
+        if need_dir {
+            stat(&self.start, path, FollowSymlinks::Yes)
+                .map(|metadata| metadata.is_dir())
+                .unwrap_or(false)
+        } else {
+            stat(&self.start, path, FollowSymlinks::No).is_ok()
+        }
+    }
+
+    /// Pushes the matches for the next component, in reverse order so that
+    /// they're popped in order.
+    fn expand(&mut self, path: PathBuf, index: usize) -> io::Result<()> {
+        let last = index + 1 == self.components.len();
+        match &self.components[index] {
+            Component::Parent => {
+                let mut parent = path;
+                if parent.pop() {
+                    self.stack.push((parent, index + 1));
+                }
+            }
+            Component::Recursive => {
+                let entries = self.entries(&path)?;
+                for (name, is_dir) in entries.into_iter().rev() {
+                    if is_dir {
+                        self.stack.push((path.join(name), index));
+                    } else if last {
+                        self.stack.push((path.join(name), index + 1));
+                    }
+                }
+                self.stack.push((path, index + 1));
+            }
+            Component::Name {
+                literal: Some(literal),
+                ..
+            } if !self.case_insensitive => {
+                let child = path.join(literal);
+                if self.exists(&child, !last) {
+                    self.stack.push((child, index + 1));
+                }
+            }
+            Component::Name { tokens, .. } => {
+                let case_insensitive = self.case_insensitive;
+                let matches = self
+                    .entries(&path)?
+                    .into_iter()
+                    .filter(|(name, _)| {
+                        let name = name.to_string_lossy().chars().collect::<Vec<_>>();
+                        match_tokens(tokens, &name, case_insensitive)
+                    })
+                    .collect::<Vec<_>>();
+                for (name, _) in matches.into_iter().rev() {
+                    let child = path.join(name);
+                    if last || self.exists(&child, true) {
+                        self.stack.push((child, index + 1));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for Glob {
+    type Item = io::Result<PathBuf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            self.stack.push((PathBuf::new(), 0));
+        }
+
+        loop {
+            let (path, index) = self.stack.pop()?;
+            if index == self.components.len() {
+                // The starting directory itself isn't an entry of itself.
+                if path.as_os_str().is_empty() || !self.seen.insert(path.clone()) {
+                    continue;
+                }
+                return Some(Ok(path));
+            }
+            if let Err(e) = self.expand(path, index) {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Glob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("Glob");
+        b.field("start", &self.start);
+        b.field("case_insensitive", &self.case_insensitive);
+        b.field("no_xdev", &self.no_xdev);
+        b.finish()
+    }
+}
+
+fn as_relative(path: &Path) -> &Path {
+    if path.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        path
+    }
+}
+
+fn invalid_pattern(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+fn parse(pattern: &str) -> io::Result<Vec<Component>> {
+    if Path::new(pattern).has_root() || Path::new(pattern).is_absolute() {
+        return Err(invalid_pattern("glob patterns must be relative"));
+    }
+
+    let mut components = Vec::new();
+    for component in pattern.split('/') {
+        match component {
+            "" | "." => (),
+            ".." => components.push(Component::Parent),
+            "**" => components.push(Component::Recursive),
+            _ => {
+                let tokens = parse_tokens(component)?;
+                let literal = if tokens.iter().all(|token| matches!(token, Token::Char(_))) {
+                    Some(component.to_owned())
+                } else {
+                    None
+                };
+                components.push(Component::Name { tokens, literal });
+            }
+        }
+    }
+    Ok(components)
+}
+
+fn parse_tokens(component: &str) -> io::Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = component.chars().peekable();
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            '?' => Token::AnyChar,
+            '*' => {
+                // Consecutive stars are equivalent to one.
+                while chars.peek() == Some(&'*') {
+                    chars.next();
+                }
+                Token::AnyString
+            }
+            '[' => {
+                let negated = match chars.peek() {
+                    Some('!') | Some('^') => {
+                        chars.next();
+                        true
+                    }
+                    _ => false,
+                };
+                let mut ranges = Vec::new();
+                let mut first = true;
+                loop {
+                    let lo = match chars.next() {
+                        Some(']') if !first => break,
+                        Some(c) => c,
+                        None => return Err(invalid_pattern("unterminated character class")),
+                    };
+                    first = false;
+                    let mut lookahead = chars.clone();
+                    let hi = match (lookahead.next(), lookahead.next()) {
+                        (Some('-'), Some(hi)) if hi != ']' => {
+                            chars.next();
+                            chars.next();
+                            hi
+                        }
+                        _ => lo,
+                    };
+                    ranges.push((lo, hi));
+                }
+                Token::Class { negated, ranges }
+            }
+            c => Token::Char(c),
+        });
+    }
+    Ok(tokens)
+}
+
+impl Token {
+    fn matches(&self, c: char, case_insensitive: bool) -> bool {
+        match self {
+            Self::Char(p) => *p == c || (case_insensitive && eq_ignore_case(*p, c)),
+            Self::AnyChar => true,
+            Self::AnyString => unreachable!(),
+            Self::Class { negated, ranges } => {
+                let in_class = |c: char| ranges.iter().any(|(lo, hi)| *lo <= c && c <= *hi);
+                let found = in_class(c)
+                    || (case_insensitive
+                        && (c.to_lowercase().any(in_class) || c.to_uppercase().any(in_class)));
+                found != *negated
+            }
+        }
+    }
+}
+
+fn eq_ignore_case(a: char, b: char) -> bool {
+    a.to_lowercase().eq(b.to_lowercase())
+}
+
+/// Tests whether `name` matches `tokens`, backtracking to the most recent
+/// `*` on a mismatch.
+fn match_tokens(tokens: &[Token], name: &[char], case_insensitive: bool) -> bool {
+    let (mut t, mut n) = (0, 0);
+    let mut backtrack = None;
+    while n < name.len() {
+        match tokens.get(t) {
+            Some(Token::AnyString) => {
+                backtrack = Some((t, n));
+                t += 1;
+                continue;
+            }
+            Some(token) if token.matches(name[n], case_insensitive) => {
+                t += 1;
+                n += 1;
+                continue;
+            }
+            _ => (),
+        }
+        match backtrack {
+            Some((star, start)) => {
+                backtrack = Some((star, start + 1));
+                t = star + 1;
+                n = start + 1;
+            }
+            None => return false,
+        }
+    }
+    tokens[t..]
+        .iter()
+        .all(|token| matches!(token, Token::AnyString))
+}
+
+#[test]
+fn glob_matches() {
+    fn m(pattern: &str, name: &str, case_insensitive: bool) -> bool {
+        let tokens = parse_tokens(pattern).unwrap();
+        match_tokens(&tokens, &name.chars().collect::<Vec<_>>(), case_insensitive)
+    }
+
+    assert!(m("*", "", false));
+    assert!(m("*.rs", "lib.rs", false));
+    assert!(!m("*.rs", "lib.rs.bak", false));
+    assert!(m("a*b*c", "aXXbYYbc", false));
+    assert!(m("?.txt", "a.txt", false));
+    assert!(!m("?.txt", ".txt", false));
+    assert!(m("[a-c]x", "bx", false));
+    assert!(!m("[!a-c]x", "bx", false));
+    assert!(m("[^a-c]x", "dx", false));
+    assert!(m("[]]", "]", false));
+    assert!(m("[a-]", "-", false));
+    assert!(!m("README", "readme", false));
+    assert!(m("README", "readme", true));
+    assert!(m("[A-Z]*", "lower", true));
+    assert!(parse_tokens("[ab").is_err());
+    assert!(parse("/etc/*").is_err());
+}
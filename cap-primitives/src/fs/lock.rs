@@ -0,0 +1,40 @@
+//! This defines `lock`, `try_lock`, and `unlock`, which manage advisory
+//! locks on whole files.
+
+use crate::fs::{lock_impl, try_lock_impl, unlock_impl};
+use std::{fs, io};
+
+/// The kind of an advisory file lock.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum FileLockKind {
+    /// A lock which may be held by any number of holders at once, as long as
+    /// nobody holds an exclusive lock.
+    Shared,
+
+    /// A lock which may only be held by one holder at a time.
+    Exclusive,
+}
+
+/// Acquires an advisory lock on the whole of `file`, blocking until it's
+/// available.
+///
+/// If `file` already holds a lock, it's converted to `kind`. On Unix this
+/// uses `flock`, so the lock is associated with the open file description
+/// and is released when the last handle to it is closed.
+#[inline]
+pub fn lock(file: &fs::File, kind: FileLockKind) -> io::Result<()> {
+    lock_impl(file, kind)
+}
+
+/// Like `lock`, but returns `Ok(false)` instead of blocking if the lock is
+/// held elsewhere.
+#[inline]
+pub fn try_lock(file: &fs::File, kind: FileLockKind) -> io::Result<bool> {
+    try_lock_impl(file, kind)
+}
+
+/// Releases an advisory lock acquired by `lock` or `try_lock`.
+#[inline]
+pub fn unlock(file: &fs::File) -> io::Result<()> {
+    unlock_impl(file)
+}
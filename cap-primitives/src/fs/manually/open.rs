@@ -52,6 +52,14 @@ struct Context<'start> {
     /// allocations.
     reuse: PathBuf,
 
+    /// If we're forbidden from crossing mount points, the device ID of the
+    /// starting directory, which every component must also have.
+    #[cfg(unix)]
+    start_dev: Option<u64>,
+
+    /// Are we forbidden from traversing symlinks?
+    no_symlinks: bool,
+
     #[cfg(racy_asserts)]
     start_clone: MaybeOwnedFile<'start>,
 }
@@ -61,7 +69,7 @@ impl<'start> Context<'start> {
     fn new(
         start: MaybeOwnedFile<'start>,
         path: &'start Path,
-        _options: &OpenOptions,
+        options: &OpenOptions,
         canonical_path: Option<&'start mut PathBuf>,
     ) -> Self {
         let components = path
@@ -81,7 +89,7 @@ impl<'start> Context<'start> {
             dir_required: path_requires_dir(path),
 
             #[cfg(not(windows))]
-            dir_precluded: _options.write || _options.append,
+            dir_precluded: options.write || options.append,
 
             #[cfg(windows)]
             dir_precluded: false,
@@ -90,11 +98,30 @@ impl<'start> Context<'start> {
 
             reuse: PathBuf::new(),
 
+            #[cfg(unix)]
+            start_dev: None,
+
+            no_symlinks: options.no_symlinks,
+
             #[cfg(racy_asserts)]
             start_clone,
         }
     }
 
+    /// If we're forbidden from crossing mount points, check that `file` is on
+    /// the same device as the starting directory.
+    #[cfg(unix)]
+    fn check_xdev(&self, file: &fs::File) -> io::Result<()> {
+        use std::os::unix::fs::MetadataExt;
+
+        if let Some(start_dev) = self.start_dev {
+            if Metadata::from_file(file)?.dev() != start_dev {
+                return Err(errors::xdev_attempt());
+            }
+        }
+        Ok(())
+    }
+
     /// The last component of a path is special -- unlike all other components,
     /// it is not required to be a directory.
     fn at_last_component(&self) -> bool {
@@ -236,6 +263,7 @@ impl<'start> Context<'start> {
                         symlink_count,
                         mem::take(&mut self.reuse),
                     ) {
+                        Ok(_) if self.no_symlinks => return Err(errors::symlink_attempt()),
                         Ok(destination) => {
                             self.dir_required |=
                                 self.components.is_empty() && path_requires_dir(&destination);
@@ -254,6 +282,8 @@ impl<'start> Context<'start> {
                 }
 
                 // Normal case
+                #[cfg(unix)]
+                self.check_xdev(&file)?;
                 let prev_base = self.base.descend_to(MaybeOwnedFile::owned(file));
                 self.dirs.push(prev_base);
                 self.canonical_path.push(one);
@@ -323,6 +353,10 @@ impl<'start> Context<'start> {
             return Err(err);
         }
 
+        if self.no_symlinks {
+            return Err(errors::symlink_attempt());
+        }
+
         self.symlink(one, symlink_count)
     }
 }
@@ -354,6 +388,12 @@ pub(super) fn internal_open<'start>(
 
     let mut ctx = Context::new(start, path, options, canonical_path);
 
+    #[cfg(unix)]
+    if options.no_xdev {
+        use std::os::unix::fs::MetadataExt;
+        ctx.start_dev = Some(Metadata::from_file(&ctx.base)?.dev());
+    }
+
     while let Some(c) = ctx.components.pop() {
         match c {
             CowComponent::PrefixOrRootDir => return Err(errors::escape_attempt()),
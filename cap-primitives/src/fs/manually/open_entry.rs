@@ -1,5 +1,9 @@
 use super::{internal_open, read_link_one};
-use crate::fs::{open_unchecked, FollowSymlinks, MaybeOwnedFile, OpenOptions, OpenUncheckedError};
+#[cfg(unix)]
+use crate::fs::{check_same_fs, Metadata};
+use crate::fs::{
+    errors, open_unchecked, FollowSymlinks, MaybeOwnedFile, OpenOptions, OpenUncheckedError,
+};
 use std::{ffi::OsStr, fs, io, path::PathBuf};
 
 pub(crate) fn open_entry(
@@ -12,7 +16,18 @@ pub(crate) fn open_entry(
         path.as_ref(),
         options.clone().follow(FollowSymlinks::No),
     ) {
-        Ok(file) => Ok(file),
+        Ok(file) => {
+            #[cfg(unix)]
+            if options.no_xdev {
+                check_same_fs(&Metadata::from_file(start)?, &Metadata::from_file(&file)?)?;
+            }
+            Ok(file)
+        }
+        Err(OpenUncheckedError::Symlink(_, _))
+            if options.follow == FollowSymlinks::Yes && options.no_symlinks =>
+        {
+            Err(errors::symlink_attempt())
+        }
         Err(OpenUncheckedError::Symlink(_, _)) if options.follow == FollowSymlinks::Yes => {
             let mut symlink_count = 0;
             let destination = read_link_one(start, path, &mut symlink_count, PathBuf::new())?;
@@ -30,6 +30,13 @@ impl Metadata {
     /// Constructs a new instance of `Self` from the given `std::fs::File`.
     #[inline]
     pub fn from_file(file: &fs::File) -> io::Result<Self> {
+        // On Linux, use `statx` when we can, so that we get the creation time
+        // and the other fields that only `statx` provides.
+        #[cfg(target_os = "linux")]
+        if let Some(metadata) = crate::fs::file_metadata_statx(file)? {
+            return Ok(metadata);
+        }
+
         let std = file.metadata()?;
         let ext = MetadataExt::from(file, &std)?;
         let file_type = FileTypeExt::from(file, &std)?;
@@ -407,3 +414,19 @@ pub trait _WindowsByHandle {
     unsafe fn number_of_links(&self) -> Option<u32>;
     unsafe fn file_index(&self) -> Option<u64>;
 }
+
+/// Extension trait to allow the fields that only `statx` provides to be
+/// exposed by the `cap-fs-ext` crate.
+///
+/// # Safety
+///
+/// This is hidden from the main API since this functionality isn't present in `std`.
+/// Use `cap_fs_ext::MetadataExt` instead of calling this directly.
+#[cfg(target_os = "linux")]
+#[doc(hidden)]
+pub trait _LinuxStatx {
+    unsafe fn statx_mask(&self) -> u32;
+    unsafe fn attributes(&self) -> u64;
+    unsafe fn attributes_mask(&self) -> u64;
+    unsafe fn mnt_id(&self) -> Option<u64>;
+}
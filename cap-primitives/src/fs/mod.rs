@@ -4,21 +4,31 @@
 #[macro_use]
 pub(crate) mod assert_same_file;
 
+mod anonymous_file;
+mod atomic_file;
 mod canonicalize;
 mod copy;
+mod copy_dir_all;
 mod create_dir;
+#[cfg(unix)]
+mod create_special;
+mod dir_access;
 mod dir_builder;
 mod dir_entry;
 mod dir_options;
 #[cfg(any(test, racy_asserts))]
 #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "ios", windows)))]
 mod file_path_by_searching;
+mod file_io;
 mod file_type;
 mod follow_symlinks;
+mod glob;
 mod hard_link;
 mod is_read_write;
+mod lock;
 mod maybe_owned_file;
 mod metadata;
+mod no_xdev;
 mod open;
 mod open_dir;
 mod open_options;
@@ -31,11 +41,19 @@ mod remove_dir_all;
 mod remove_file;
 mod remove_open_dir;
 mod rename;
+#[cfg(unix)]
+mod set_owner;
 mod set_permissions;
 mod set_times;
 mod stat;
+#[cfg(target_os = "linux")]
+mod statx;
 mod symlink;
 mod system_time_spec;
+mod walk;
+#[cfg(target_os = "linux")]
+mod watch;
+mod xattr;
 
 pub(crate) mod errors;
 pub(crate) mod manually;
@@ -53,17 +71,27 @@ pub(crate) use super::posish::fs::*;
 #[cfg(windows)]
 pub(crate) use super::winx::fs::*;
 
+pub use anonymous_file::*;
+pub use atomic_file::*;
 pub use canonicalize::*;
 pub use copy::*;
+pub use copy_dir_all::*;
 pub use create_dir::*;
+#[cfg(unix)]
+pub use create_special::*;
+pub use dir_access::*;
 pub use dir_builder::*;
 pub use dir_entry::*;
 pub use dir_options::*;
+pub use file_io::*;
 pub use file_type::*;
 pub use follow_symlinks::*;
+pub use glob::*;
 pub use hard_link::*;
 pub use is_read_write::is_read_write;
+pub use lock::*;
 pub use metadata::*;
+pub use no_xdev::*;
 pub use open::*;
 pub use open_dir::*;
 pub use open_options::*;
@@ -75,11 +103,19 @@ pub use remove_dir_all::*;
 pub use remove_file::*;
 pub use remove_open_dir::*;
 pub use rename::*;
+#[cfg(unix)]
+pub use set_owner::*;
 pub use set_permissions::*;
 pub use set_times::*;
 pub use stat::*;
+#[cfg(target_os = "linux")]
+pub use statx::*;
 pub use symlink::*;
 pub use system_time_spec::*;
+pub use walk::*;
+#[cfg(target_os = "linux")]
+pub use watch::*;
+pub use xattr::*;
 
 #[cfg(racy_asserts)]
 fn map_result<T: Clone>(result: &std::io::Result<T>) -> Result<T, (std::io::ErrorKind, String)> {
@@ -0,0 +1,149 @@
+//! Variants of the path-resolving functions for directories which are pinned
+//! to the filesystem they're on. These fail rather than resolve a path
+//! through a mount point.
+
+use crate::fs::{
+    dir_options, errors, open, read_dir, read_link, remove_dir, remove_file, remove_open_dir, stat,
+    via_parent, FollowSymlinks, Metadata, OpenOptions, ReadDir, MAX_SYMLINK_EXPANSIONS,
+};
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+/// Like `open`, but fails if resolving `path` would cross a mount point.
+#[inline]
+pub fn open_no_xdev(start: &fs::File, path: &Path, options: &OpenOptions) -> io::Result<fs::File> {
+    open(start, path, options.clone().no_xdev(true))
+}
+
+/// Like `open_dir`, but fails if resolving `path` would cross a mount point.
+#[inline]
+pub fn open_dir_no_xdev(start: &fs::File, path: &Path) -> io::Result<fs::File> {
+    open_no_xdev(start, path, &dir_options())
+}
+
+/// Like `read_dir`, but fails if resolving `path` would cross a mount point.
+///
+/// The `DirEntry`s it yields can't be used to cross a mount point either.
+#[inline]
+pub fn read_dir_no_xdev(start: &fs::File, path: &Path) -> io::Result<ReadDir> {
+    let dir = open_dir_no_xdev(start, path)?;
+    let mut read_dir = read_dir(&dir, Component::CurDir.as_ref())?;
+    read_dir.no_xdev = true;
+    Ok(read_dir)
+}
+
+/// Like `stat`, but fails if resolving `path` would cross a mount point, or
+/// if it names a file on a different filesystem, such as a mount point.
+pub fn stat_no_xdev(start: &fs::File, path: &Path, follow: FollowSymlinks) -> io::Result<Metadata> {
+    let resolved;
+    let path = match follow {
+        FollowSymlinks::Yes => {
+            resolved = follow_no_xdev(start, path)?;
+            &resolved
+        }
+        FollowSymlinks::No => path,
+    };
+    via_parent_no_xdev(start, path, |dir, basename| {
+        let metadata = stat(dir, basename, FollowSymlinks::No)?;
+        check_same_fs(&Metadata::from_file(dir)?, &metadata)?;
+        Ok(metadata)
+    })
+}
+
+/// Follows any symlinks in the last component of `path` relative to `start`,
+/// without crossing a mount point, and returns a path relative to `start`
+/// which names the same file without ending in a symlink.
+///
+/// As with `open`, a symlink may lead anywhere within `start`, but not
+/// outside of it.
+pub fn follow_no_xdev(start: &fs::File, path: &Path) -> io::Result<PathBuf> {
+    let mut path = path.to_path_buf();
+    for _ in 0..=MAX_SYMLINK_EXPANSIONS {
+        let target = via_parent_no_xdev(start, &path, |dir, basename| {
+            let metadata = stat(dir, basename, FollowSymlinks::No)?;
+            check_same_fs(&Metadata::from_file(dir)?, &metadata)?;
+            if metadata.file_type().is_symlink() {
+                read_link(dir, basename).map(Some)
+            } else {
+                Ok(None)
+            }
+        })?;
+        match target {
+            Some(target) => {
+                path.pop();
+                path.push(target);
+            }
+            None => return Ok(path),
+        }
+    }
+    Err(errors::too_many_symlinks())
+}
+
+/// Opens the parent directory of `path` relative to `start` without crossing
+/// a mount point, and calls `f` with it and the last component of `path`.
+///
+/// `f` is called with `start` itself if `path` names an entry of `start`.
+/// Note that `f` is responsible for not following a symlink in the last
+/// component to a different filesystem.
+pub fn via_parent_no_xdev<T, F>(start: &fs::File, path: &Path, f: F) -> io::Result<T>
+where
+    F: FnOnce(&fs::File, &Path) -> io::Result<T>,
+{
+    let (dir, basename) = via_parent::open_parent_no_xdev(start, path)?;
+    f(dir.as_ref().unwrap_or(start), basename.as_ref())
+}
+
+/// Like `remove_dir_all`, but fails rather than remove anything on a
+/// different filesystem.
+pub fn remove_dir_all_no_xdev(start: &fs::File, path: &Path) -> io::Result<()> {
+    via_parent_no_xdev(start, path, |dir, basename| {
+        let metadata = stat(dir, basename, FollowSymlinks::No)?;
+        if metadata.file_type().is_symlink() {
+            remove_file(dir, basename)
+        } else {
+            remove_contents(read_dir_no_xdev(dir, basename)?)?;
+            remove_dir(dir, basename)
+        }
+    })
+}
+
+/// Like `remove_open_dir_all`, but fails rather than remove anything on a
+/// different filesystem.
+pub fn remove_open_dir_all_no_xdev(dir: fs::File) -> io::Result<()> {
+    remove_contents(read_dir_no_xdev(&dir, Component::CurDir.as_ref())?)?;
+    remove_open_dir(dir)
+}
+
+fn remove_contents(children: ReadDir) -> io::Result<()> {
+    for child in children {
+        let child = child?;
+        if child.file_type()?.is_dir() {
+            remove_contents(child.read_dir()?)?;
+            child.remove_dir()?;
+        } else {
+            child.remove_file()?;
+        }
+    }
+    Ok(())
+}
+
+/// Fails if `metadata` is for a file on a different filesystem than the
+/// directory `dir_metadata` is for.
+#[cfg(unix)]
+pub(crate) fn check_same_fs(dir_metadata: &Metadata, metadata: &Metadata) -> io::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    if metadata.dev() != dir_metadata.dev() {
+        return Err(errors::xdev_attempt());
+    }
+    Ok(())
+}
+
+/// Device numbers aren't available here, so this can't check anything.
+#[cfg(not(unix))]
+#[allow(clippy::unnecessary_wraps)]
+pub(crate) fn check_same_fs(_dir_metadata: &Metadata, _metadata: &Metadata) -> io::Result<()> {
+    Ok(())
+}
@@ -26,6 +26,8 @@ pub struct OpenOptions {
     pub(crate) create_new: bool,
     pub(crate) dir_required: bool,
     pub(crate) follow: FollowSymlinks,
+    pub(crate) no_xdev: bool,
+    pub(crate) no_symlinks: bool,
 
     #[cfg(any(unix, windows, target_os = "vxworks"))]
     pub(crate) ext: OpenOptionsExt,
@@ -49,6 +51,8 @@ impl OpenOptions {
             create_new: false,
             dir_required: false,
             follow: FollowSymlinks::Yes,
+            no_xdev: false,
+            no_symlinks: false,
 
             #[cfg(any(unix, windows, target_os = "vxworks"))]
             ext: OpenOptionsExt::new(),
@@ -128,6 +132,22 @@ impl OpenOptions {
         self
     }
 
+    /// Sets the option to fail if path resolution would cross a mount point.
+    ///
+    /// This is only enforced on Unix-family platforms.
+    #[inline]
+    pub(crate) fn no_xdev(&mut self, no_xdev: bool) -> &mut Self {
+        self.no_xdev = no_xdev;
+        self
+    }
+
+    /// Sets the option to fail if path resolution would traverse any symlink.
+    #[inline]
+    pub(crate) fn no_symlinks(&mut self, no_symlinks: bool) -> &mut Self {
+        self.no_symlinks = no_symlinks;
+        self
+    }
+
     /// Sets the option to enable an error if the opened object is not a directory.
     #[inline]
     pub(crate) fn dir_required(&mut self, dir_required: bool) -> &mut Self {
@@ -146,6 +166,31 @@ impl OpenOptions {
     pub unsafe fn _cap_fs_ext_follow(&mut self, follow: FollowSymlinks) -> &mut Self {
         self.follow(follow)
     }
+
+    /// Wrapper to allow `no_xdev` to be exposed by the `cap-fs-ext` crate.
+    ///
+    /// # Safety
+    ///
+    /// This is hidden from the main API since this functionality isn't present in `std`.
+    /// Use `cap_fs_ext::OpenOptionsResolveExt` instead of calling this directly.
+    #[cfg(unix)]
+    #[doc(hidden)]
+    #[inline]
+    pub unsafe fn _cap_fs_ext_no_xdev(&mut self, no_xdev: bool) -> &mut Self {
+        self.no_xdev(no_xdev)
+    }
+
+    /// Wrapper to allow `no_symlinks` to be exposed by the `cap-fs-ext` crate.
+    ///
+    /// # Safety
+    ///
+    /// This is hidden from the main API since this functionality isn't present in `std`.
+    /// Use `cap_fs_ext::OpenOptionsResolveExt` instead of calling this directly.
+    #[doc(hidden)]
+    #[inline]
+    pub unsafe fn _cap_fs_ext_no_symlinks(&mut self, no_symlinks: bool) -> &mut Self {
+        self.no_symlinks(no_symlinks)
+    }
 }
 
 #[cfg(unix)]
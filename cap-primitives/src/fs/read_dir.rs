@@ -8,6 +8,7 @@ use std::{fmt, fs, io, path::Path};
 pub fn read_dir(start: &fs::File, path: &Path) -> io::Result<ReadDir> {
     Ok(ReadDir {
         inner: ReadDirInner::new(start, path)?,
+        no_xdev: false,
     })
 }
 
@@ -16,6 +17,7 @@ pub fn read_dir(start: &fs::File, path: &Path) -> io::Result<ReadDir> {
 pub(crate) fn read_dir_unchecked(start: &fs::File, path: &Path) -> io::Result<ReadDir> {
     Ok(ReadDir {
         inner: ReadDirInner::new_unchecked(start, path)?,
+        no_xdev: false,
     })
 }
 
@@ -30,6 +32,7 @@ pub(crate) fn read_dir_unchecked(start: &fs::File, path: &Path) -> io::Result<Re
 /// [`std::fs::ReadDir`]: https://doc.rust-lang.org/std/fs/struct.ReadDir.html
 pub struct ReadDir {
     pub(crate) inner: ReadDirInner,
+    pub(crate) no_xdev: bool,
 }
 
 impl Iterator for ReadDir {
@@ -37,9 +40,10 @@ impl Iterator for ReadDir {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
+        let no_xdev = self.no_xdev;
         self.inner
             .next()
-            .map(|inner| inner.map(|inner| DirEntry { inner }))
+            .map(|inner| inner.map(|inner| DirEntry { inner, no_xdev }))
     }
 }
 
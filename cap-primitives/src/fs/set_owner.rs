@@ -0,0 +1,28 @@
+//! This defines `set_owner`, the primary entrypoint to sandboxed file
+//! ownership modification.
+
+use crate::fs::{set_file_owner_impl, set_owner_impl, FollowSymlinks};
+use std::{fs, io, path::Path};
+
+/// Perform a `fchownat`-like operation, ensuring that the resolution of the
+/// path never escapes the directory tree rooted at `start`.
+///
+/// A `None` `uid` or `gid` leaves the corresponding owner unchanged.
+#[inline]
+pub fn set_owner(
+    start: &fs::File,
+    path: &Path,
+    uid: Option<u32>,
+    gid: Option<u32>,
+    follow: FollowSymlinks,
+) -> io::Result<()> {
+    set_owner_impl(start, path, uid, gid, follow)
+}
+
+/// Perform a `fchown`-like operation on `file`.
+///
+/// A `None` `uid` or `gid` leaves the corresponding owner unchanged.
+#[inline]
+pub fn set_file_owner(file: &fs::File, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+    set_file_owner_impl(file, uid, gid)
+}
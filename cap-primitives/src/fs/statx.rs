@@ -0,0 +1,136 @@
+//! This defines `statx` and `fstatx`, which query metadata with control over
+//! which fields are retrieved and how they're synchronized with the
+//! filesystem.
+//!
+//! These are only available on Linux.
+
+use crate::fs::{fstatx_impl, statx_impl, FollowSymlinks, Metadata};
+use std::{fs, io, ops, path::Path};
+
+/// A set of metadata fields to request from [`statx`].
+///
+/// This corresponds to the `STATX_*` mask flags. The kernel may return more
+/// fields than requested, and may omit fields the filesystem doesn't support;
+/// `cap_fs_ext::MetadataExt::statx_mask` reports which fields were actually
+/// filled in.
+///
+/// [`statx`]: https://man7.org/linux/man-pages/man2/statx.2.html
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct StatxFields(u32);
+
+impl StatxFields {
+    /// The file type.
+    pub const TYPE: Self = Self(libc::STATX_TYPE);
+    /// The permission bits.
+    pub const MODE: Self = Self(libc::STATX_MODE);
+    /// The number of hard links.
+    pub const NLINK: Self = Self(libc::STATX_NLINK);
+    /// The owner's user ID.
+    pub const UID: Self = Self(libc::STATX_UID);
+    /// The owner's group ID.
+    pub const GID: Self = Self(libc::STATX_GID);
+    /// The last access time.
+    pub const ATIME: Self = Self(libc::STATX_ATIME);
+    /// The last modification time.
+    pub const MTIME: Self = Self(libc::STATX_MTIME);
+    /// The last status change time.
+    pub const CTIME: Self = Self(libc::STATX_CTIME);
+    /// The inode number.
+    pub const INO: Self = Self(libc::STATX_INO);
+    /// The size.
+    pub const SIZE: Self = Self(libc::STATX_SIZE);
+    /// The number of allocated blocks.
+    pub const BLOCKS: Self = Self(libc::STATX_BLOCKS);
+    /// Everything that `stat` returns.
+    pub const BASIC_STATS: Self = Self(libc::STATX_BASIC_STATS);
+    /// The creation (birth) time.
+    pub const BTIME: Self = Self(libc::STATX_BTIME);
+    /// The mount ID.
+    pub const MNT_ID: Self = Self(libc::STATX_MNT_ID);
+    /// All of the fields above.
+    pub const ALL: Self = Self(libc::STATX_BASIC_STATS | libc::STATX_BTIME | libc::STATX_MNT_ID);
+
+    /// Returns an empty set of fields.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Constructs a set of fields from raw `STATX_*` bits.
+    #[inline]
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw `STATX_*` bits.
+    #[inline]
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns `true` if all of the fields in `other` are in `self`.
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl ops::BitOr for StatxFields {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl ops::BitOrAssign for StatxFields {
+    #[inline]
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0
+    }
+}
+
+/// How [`statx`] synchronizes with the filesystem, which matters for network
+/// filesystems.
+///
+/// [`statx`]: https://man7.org/linux/man-pages/man2/statx.2.html
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum StatxSync {
+    /// Do whatever `stat` does. This corresponds to `AT_STATX_SYNC_AS_STAT`.
+    AsStat,
+
+    /// Force the attributes to be synchronized with the server. This
+    /// corresponds to `AT_STATX_FORCE_SYNC`.
+    Force,
+
+    /// Don't synchronize anything, and use whatever is cached, which may be
+    /// out of date. This corresponds to `AT_STATX_DONT_SYNC`.
+    DontSync,
+}
+
+/// Queries the `fields` of the metadata of the file at `path`, ensuring that
+/// the resolution of the path never escapes the directory tree rooted at
+/// `start`.
+///
+/// This fails with `ENOSYS` on Linux versions older than 4.11, which lack
+/// `statx`.
+#[inline]
+pub fn statx(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+    fields: StatxFields,
+    sync: StatxSync,
+) -> io::Result<Metadata> {
+    statx_impl(start, path, follow, fields, sync)
+}
+
+/// Queries the `fields` of the metadata of `file`.
+///
+/// This fails with `ENOSYS` on Linux versions older than 4.11, which lack
+/// `statx`.
+#[inline]
+pub fn fstatx(file: &fs::File, fields: StatxFields, sync: StatxSync) -> io::Result<Metadata> {
+    fstatx_impl(file, fields, sync)
+}
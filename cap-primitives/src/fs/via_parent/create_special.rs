@@ -0,0 +1,29 @@
+use super::open_parent;
+use crate::fs::{create_device_node_unchecked, create_fifo_unchecked, MaybeOwnedFile};
+use std::{fs, io, path::Path};
+
+/// Implement `create_fifo` by `open`ing up the parent component of the path
+/// and then calling `create_fifo_unchecked` on the last component.
+pub(crate) fn create_fifo(start: &fs::File, path: &Path, mode: u32) -> io::Result<()> {
+    let start = MaybeOwnedFile::borrowed(start);
+
+    let (dir, basename) = open_parent(start, path)?;
+
+    create_fifo_unchecked(&dir, basename.as_ref(), mode)
+}
+
+/// Implement `create_device_node` by `open`ing up the parent component of the
+/// path and then calling `create_device_node_unchecked` on the last
+/// component.
+pub(crate) fn create_device_node(
+    start: &fs::File,
+    path: &Path,
+    mode: u32,
+    dev: u64,
+) -> io::Result<()> {
+    let start = MaybeOwnedFile::borrowed(start);
+
+    let (dir, basename) = open_parent(start, path)?;
+
+    create_device_node_unchecked(&dir, basename.as_ref(), mode, dev)
+}
@@ -0,0 +1,13 @@
+use super::open_parent;
+use crate::fs::{link_tmpfile_impl, MaybeOwnedFile};
+use std::{fs, io, path::Path};
+
+/// Implement `link_file_at` by `open`ing up the parent component of the path
+/// and then calling `link_tmpfile_impl` on the last component.
+pub(crate) fn link_file(file: &fs::File, new_start: &fs::File, new_path: &Path) -> io::Result<()> {
+    let new_start = MaybeOwnedFile::borrowed(new_start);
+
+    let (new_dir, new_basename) = open_parent(new_start, new_path)?;
+
+    link_tmpfile_impl(file, &new_dir, new_basename)
+}
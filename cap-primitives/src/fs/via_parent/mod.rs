@@ -3,13 +3,19 @@
 //! rest of the components just name the place to create it in.
 
 mod create_dir;
+#[cfg(unix)]
+mod create_special;
 mod hard_link;
+#[cfg(target_os = "linux")]
+mod link_file;
 mod open_parent;
 #[cfg(not(windows))] // doesn't work on windows; use a windows-specific impl
 mod read_link;
 mod remove_dir;
 mod remove_file;
 mod rename;
+#[cfg(unix)]
+mod set_owner_nofollow;
 #[cfg(windows)]
 mod set_permissions;
 #[cfg(not(windows))]
@@ -18,13 +24,21 @@ mod symlink;
 
 use open_parent::open_parent;
 
+pub(crate) use open_parent::open_parent_no_xdev;
+
 pub(crate) use create_dir::create_dir;
+#[cfg(unix)]
+pub(crate) use create_special::{create_device_node, create_fifo};
 pub(crate) use hard_link::hard_link;
+#[cfg(target_os = "linux")]
+pub(crate) use link_file::link_file;
 #[cfg(not(windows))] // doesn't work on windows; use a windows-specific impl
 pub(crate) use read_link::read_link;
 pub(crate) use remove_dir::remove_dir;
 pub(crate) use remove_file::remove_file;
 pub(crate) use rename::rename;
+#[cfg(unix)]
+pub(crate) use set_owner_nofollow::set_owner_nofollow;
 #[cfg(windows)]
 pub(crate) use set_permissions::set_permissions;
 #[cfg(not(windows))]
@@ -2,10 +2,10 @@
 /// It opens the parent directory of the given path, and returns the basename,
 /// so that all the `via_parent` functions need to do is make sure they
 /// don't follow symlinks in the basename.
-use crate::fs::{errors, open_dir_path, path_requires_dir, MaybeOwnedFile};
+use crate::fs::{dir_path_options, errors, open, open_dir_path, path_requires_dir, MaybeOwnedFile};
 use std::{
     ffi::OsStr,
-    io,
+    fs, io,
     path::{Component, Path},
 };
 
@@ -29,6 +29,24 @@ pub(super) fn open_parent<'path, 'borrow>(
     Ok((dir, basename.as_os_str()))
 }
 
+/// Like `open_parent`, but fails rather than cross a mount point while
+/// opening the parent, and returns `None` instead of `start` when `path`
+/// names an entry of `start` itself.
+pub(crate) fn open_parent_no_xdev<'path>(
+    start: &fs::File,
+    path: &'path Path,
+) -> io::Result<(Option<fs::File>, &'path OsStr)> {
+    let (dirname, basename) = split_parent(path).ok_or_else(errors::no_such_file_or_directory)?;
+
+    let dir = if dirname.as_os_str().is_empty() {
+        None
+    } else {
+        Some(open(start, dirname, dir_path_options().no_xdev(true))?)
+    };
+
+    Ok((dir, basename.as_os_str()))
+}
+
 /// Split `path` into parent and basename parts. Return `None` if `path`
 /// is empty.
 ///
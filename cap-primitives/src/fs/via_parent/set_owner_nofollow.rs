@@ -0,0 +1,17 @@
+use super::open_parent;
+use crate::fs::{set_owner_nofollow_unchecked, MaybeOwnedFile};
+use std::{fs, io, path::Path};
+
+#[inline]
+pub(crate) fn set_owner_nofollow(
+    start: &fs::File,
+    path: &Path,
+    uid: Option<u32>,
+    gid: Option<u32>,
+) -> io::Result<()> {
+    let start = MaybeOwnedFile::borrowed(start);
+
+    let (dir, basename) = open_parent(start, path)?;
+
+    set_owner_nofollow_unchecked(&dir, basename.as_ref(), uid, gid)
+}
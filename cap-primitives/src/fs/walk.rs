@@ -0,0 +1,430 @@
+//! Recursive iteration over the contents of a directory.
+
+use crate::fs::{
+    open_dir, open_dir_no_xdev, read_dir, read_dir_no_xdev, stat, stat_no_xdev, DirEntry, FileType,
+    FollowSymlinks, Metadata, ReadDir,
+};
+use std::{
+    cmp::Ordering,
+    ffi::OsString,
+    fmt, fs, io,
+    path::{Component, Path, PathBuf},
+    vec,
+};
+
+/// An iterator over the entries of a directory tree.
+///
+/// Entries are yielded in pre-order, so each directory is yielded before its
+/// contents. The directory being walked isn't yielded itself; its immediate
+/// entries are at depth 1.
+///
+/// Subdirectories are opened with [`DirEntry::open_dir`], and symlinks are
+/// resolved relative to the directory being walked, so the walk never leaves
+/// the directory it started in.
+///
+/// Entries are yielded as `E`, which is `DirEntry` unless the `Walk` is
+/// constructed with [`Walk::with_entry_type`].
+///
+/// [`DirEntry::open_dir`]: struct.DirEntry.html#method.open_dir
+/// [`Walk::with_entry_type`]: #method.with_entry_type
+pub struct Walk<E = DirEntry> {
+    root: fs::File,
+    wrap: Box<WrapFn<E>>,
+    unwrap: fn(&E) -> &DirEntry,
+    min_depth: usize,
+    max_depth: usize,
+    follow_symlinks: bool,
+    no_xdev: bool,
+    sort: Option<Box<CompareFn<E>>>,
+    started: bool,
+    stack: Vec<Frame<E>>,
+    pending: Option<io::Result<Frame<E>>>,
+    /// Whether the most recently yielded entry was a directory, which
+    /// `skip_current_dir` uses to decide what to skip.
+    yielded_dir: bool,
+}
+
+type WrapFn<E> = dyn FnMut(DirEntry) -> E + Send + Sync;
+type CompareFn<E> = dyn FnMut(&E, &E) -> Ordering + Send + Sync;
+
+/// A directory which is being walked.
+struct Frame<E> {
+    entries: Entries<E>,
+    path: PathBuf,
+    depth: usize,
+    /// The device and inode numbers of the directory, used to detect loops.
+    id: Option<(u64, u64)>,
+}
+
+enum Entries<E> {
+    Unsorted(ReadDir),
+    Sorted(vec::IntoIter<io::Result<E>>),
+}
+
+impl Walk<DirEntry> {
+    /// Constructs a new `Walk` over the contents of `root`.
+    #[inline]
+    pub fn new(root: fs::File) -> Self {
+        Self::with_entry_type(root, |entry| entry, identity)
+    }
+}
+
+impl<E: 'static> Walk<E> {
+    /// Constructs a new `Walk` over the contents of `root`, which yields
+    /// entries converted from `DirEntry`s with `wrap`. `unwrap` returns the
+    /// `DirEntry` that an entry was converted from.
+    ///
+    /// This allows crates which wrap `DirEntry` to yield, and sort, their own
+    /// entry type.
+    pub fn with_entry_type<W>(root: fs::File, wrap: W, unwrap: fn(&E) -> &DirEntry) -> Self
+    where
+        W: FnMut(DirEntry) -> E + Send + Sync + 'static,
+    {
+        Self {
+            root,
+            wrap: Box::new(wrap),
+            unwrap,
+            min_depth: 1,
+            max_depth: usize::MAX,
+            follow_symlinks: false,
+            no_xdev: false,
+            sort: None,
+            started: false,
+            stack: Vec::new(),
+            pending: None,
+            yielded_dir: false,
+        }
+    }
+
+    /// Only yield entries at least `depth` levels below the directory being
+    /// walked. Shallower directories are still descended into.
+    #[inline]
+    pub fn min_depth(&mut self, depth: usize) -> &mut Self {
+        self.min_depth = depth;
+        self
+    }
+
+    /// Don't yield or descend into entries more than `depth` levels below the
+    /// directory being walked.
+    #[inline]
+    pub fn max_depth(&mut self, depth: usize) -> &mut Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Sets the option to follow symlinks to directories and descend into
+    /// them. By default, symlinks are yielded but not followed.
+    ///
+    /// Symlinks are resolved within the directory being walked. When they're
+    /// followed, a directory which is its own ancestor is reported as an
+    /// error instead of being descended into.
+    #[inline]
+    pub fn follow_symlinks(&mut self, follow_symlinks: bool) -> &mut Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// Sets the option to fail rather than descend into a directory on a
+    /// different filesystem than the directory being walked, such as a mount
+    /// point.
+    #[inline]
+    pub fn no_xdev(&mut self, no_xdev: bool) -> &mut Self {
+        self.no_xdev = no_xdev;
+        self
+    }
+
+    /// Yield the entries of each directory in the order given by `cmp`.
+    ///
+    /// This reads each directory in full before yielding any of its entries.
+    #[inline]
+    pub fn sort_by<F>(&mut self, cmp: F) -> &mut Self
+    where
+        F: FnMut(&E, &E) -> Ordering + Send + Sync + 'static,
+    {
+        self.sort = Some(Box::new(cmp));
+        self
+    }
+
+    /// Yield the entries of each directory in order of their file names.
+    #[inline]
+    pub fn sort_by_file_name(&mut self) -> &mut Self {
+        let unwrap = self.unwrap;
+        self.sort_by(move |a, b| unwrap(a).file_name().cmp(&unwrap(b).file_name()))
+    }
+
+    /// Don't descend into the most recently yielded directory. If the most
+    /// recently yielded entry isn't a directory, skip the remaining entries of
+    /// the directory containing it.
+    #[inline]
+    pub fn skip_current_dir(&mut self) {
+        // A directory at `max_depth` isn't opened, so there may be nothing
+        // pending even when the most recently yielded entry is a directory.
+        self.pending = None;
+        if !self.yielded_dir {
+            self.stack.pop();
+        }
+    }
+
+    fn open_root(&mut self) -> io::Result<Frame<E>> {
+        let id = if self.follow_symlinks {
+            dir_id(&Metadata::from_file(&self.root)?)
+        } else {
+            None
+        };
+        let read_dir = self.read_dir(&self.root)?;
+        Ok(Frame {
+            entries: self.sort_entries(read_dir),
+            path: PathBuf::new(),
+            depth: 0,
+            id,
+        })
+    }
+
+    fn open_child(
+        &mut self,
+        entry: &DirEntry,
+        is_symlink: bool,
+        path: PathBuf,
+        depth: usize,
+    ) -> io::Result<Frame<E>> {
+        let dir = if is_symlink && self.no_xdev {
+            open_dir_no_xdev(&self.root, &path)?
+        } else if is_symlink {
+            open_dir(&self.root, &path)?
+        } else {
+            entry.open_dir()?
+        };
+        let id = if self.follow_symlinks {
+            dir_id(&Metadata::from_file(&dir)?)
+        } else {
+            None
+        };
+        if id.is_some() && self.stack.iter().any(|frame| frame.id == id) {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("filesystem loop detected at {}", path.display()),
+            ));
+        }
+        let read_dir = self.read_dir(&dir)?;
+        Ok(Frame {
+            entries: self.sort_entries(read_dir),
+            path,
+            depth,
+            id,
+        })
+    }
+
+    /// Starts reading the entries of `dir`. With `no_xdev`, the entries can't
+    /// be opened if they're on a different filesystem.
+    fn read_dir(&self, dir: &fs::File) -> io::Result<ReadDir> {
+        if self.no_xdev {
+            read_dir_no_xdev(dir, Component::CurDir.as_ref())
+        } else {
+            read_dir(dir, Component::CurDir.as_ref())
+        }
+    }
+
+    /// Reads all of the entries of `read_dir` and sorts them, if sorting is
+    /// enabled.
+    fn sort_entries(&mut self, read_dir: ReadDir) -> Entries<E> {
+        match &mut self.sort {
+            None => Entries::Unsorted(read_dir),
+            Some(cmp) => {
+                let wrap = &mut self.wrap;
+                let mut entries = read_dir
+                    .map(|entry| entry.map(&mut *wrap))
+                    .collect::<Vec<_>>();
+                entries.sort_by(|a, b| match (a, b) {
+                    (Ok(a), Ok(b)) => cmp(a, b),
+                    (Err(_), Ok(_)) => Ordering::Less,
+                    (Ok(_), Err(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => Ordering::Equal,
+                });
+                Entries::Sorted(entries.into_iter())
+            }
+        }
+    }
+}
+
+impl<E: 'static> Iterator for Walk<E> {
+    type Item = io::Result<WalkEntry<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            self.pending = Some(self.open_root());
+        }
+
+        loop {
+            match self.pending.take() {
+                Some(Ok(frame)) => self.stack.push(frame),
+                Some(Err(e)) => return Some(Err(e)),
+                None => {}
+            }
+
+            let frame = self.stack.last_mut()?;
+            let wrap = &mut self.wrap;
+            let entry = match &mut frame.entries {
+                Entries::Unsorted(read_dir) => read_dir.next().map(|entry| entry.map(&mut *wrap)),
+                Entries::Sorted(entries) => entries.next(),
+            };
+            let entry = match entry {
+                Some(Ok(entry)) => entry,
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.stack.pop();
+                    continue;
+                }
+            };
+            let depth = frame.depth + 1;
+            let inner = (self.unwrap)(&entry);
+            let path = frame.path.join(inner.file_name());
+
+            let mut file_type = match inner.file_type() {
+                Ok(file_type) => file_type,
+                Err(e) => return Some(Err(e)),
+            };
+            let is_symlink = file_type.is_symlink();
+            if is_symlink && self.follow_symlinks {
+                // Resolve the symlink within the directory being walked, so
+                // that it may refer to anything inside it, but nothing
+                // outside. Dangling symlinks are yielded as-is.
+                let metadata = if self.no_xdev {
+                    stat_no_xdev(&self.root, &path, FollowSymlinks::Yes)
+                } else {
+                    stat(&self.root, &path, FollowSymlinks::Yes)
+                };
+                if let Ok(metadata) = metadata {
+                    file_type = metadata.file_type();
+                }
+            }
+
+            if file_type.is_dir() && depth < self.max_depth {
+                self.pending =
+                    Some(self.open_child((self.unwrap)(&entry), is_symlink, path.clone(), depth));
+            }
+
+            if self.min_depth <= depth && depth <= self.max_depth {
+                self.yielded_dir = file_type.is_dir();
+                return Some(Ok(WalkEntry {
+                    entry,
+                    unwrap: self.unwrap,
+                    path,
+                    depth,
+                    file_type,
+                    is_symlink,
+                }));
+            }
+        }
+    }
+}
+
+impl<E> fmt::Debug for Walk<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("Walk");
+        b.field("min_depth", &self.min_depth);
+        b.field("max_depth", &self.max_depth);
+        b.field("follow_symlinks", &self.follow_symlinks);
+        b.field("no_xdev", &self.no_xdev);
+        b.field("sorted", &self.sort.is_some());
+        b.finish()
+    }
+}
+
+fn identity(entry: &DirEntry) -> &DirEntry {
+    entry
+}
+
+/// Returns the device and inode numbers in `metadata`, where available.
+#[cfg(unix)]
+fn dir_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// Returns the device and inode numbers in `metadata`, where available.
+#[cfg(not(unix))]
+fn dir_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    // TODO: Use the volume serial number and file index on Windows once
+    // they're available on stable Rust.
+    None
+}
+
+/// An entry yielded by the [`Walk`] iterator.
+///
+/// [`Walk`]: struct.Walk.html
+pub struct WalkEntry<E = DirEntry> {
+    entry: E,
+    unwrap: fn(&E) -> &DirEntry,
+    path: PathBuf,
+    depth: usize,
+    file_type: FileType,
+    is_symlink: bool,
+}
+
+impl<E> WalkEntry<E> {
+    /// Returns the path of this entry, relative to the directory being
+    /// walked.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Consumes `self` and returns the path of this entry, relative to the
+    /// directory being walked.
+    #[inline]
+    pub fn into_path(self) -> PathBuf {
+        self.path
+    }
+
+    /// Returns the number of levels below the directory being walked that
+    /// this entry is at, where its immediate entries are at depth 1.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the file type of this entry. If symlinks are being followed,
+    /// this is the type of the symlink's target.
+    #[inline]
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    /// Returns `true` if this entry is a symlink, whether or not it was
+    /// followed.
+    #[inline]
+    pub fn path_is_symlink(&self) -> bool {
+        self.is_symlink
+    }
+
+    /// Returns the bare file name of this entry without any other leading
+    /// path component.
+    #[inline]
+    pub fn file_name(&self) -> OsString {
+        (self.unwrap)(&self.entry).file_name()
+    }
+
+    /// Returns the underlying `DirEntry`, which may be used to open the
+    /// entry.
+    #[inline]
+    pub fn dir_entry(&self) -> &E {
+        &self.entry
+    }
+
+    /// Consumes `self` and returns the underlying `DirEntry`.
+    #[inline]
+    pub fn into_dir_entry(self) -> E {
+        self.entry
+    }
+}
+
+impl<E> fmt::Debug for WalkEntry<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("WalkEntry");
+        b.field("path", &self.path);
+        b.field("depth", &self.depth);
+        b.field("file_type", &self.file_type);
+        b.finish()
+    }
+}
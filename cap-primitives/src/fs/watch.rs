@@ -0,0 +1,148 @@
+//! This defines `Watcher`, which reports changes to files and directories
+//! named by paths relative to a directory.
+//!
+//! Watching is currently only supported on Linux, where it's implemented with
+//! inotify.
+
+use crate::fs::WatcherImpl;
+use std::{
+    fmt, fs, io,
+    os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd},
+    path::{Path, PathBuf},
+};
+
+/// The kind of change described by a `DirEvent`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum DirEventKind {
+    /// A file or directory was created, or moved in from outside of the
+    /// watched directories.
+    Created,
+
+    /// The contents of a file were modified.
+    Modified,
+
+    /// A file or directory was removed, or moved out to outside of the
+    /// watched directories.
+    Removed,
+
+    /// A file or directory was renamed from the path in `from`.
+    Renamed {
+        /// The path the file or directory had before it was renamed.
+        from: PathBuf,
+    },
+
+    /// The metadata of a file or directory, such as its permissions,
+    /// timestamps, or extended attributes, was changed.
+    AttributesChanged,
+}
+
+/// A change to a file or directory reported by a `Watcher`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct DirEvent {
+    kind: DirEventKind,
+    path: PathBuf,
+    is_dir: bool,
+}
+
+impl DirEvent {
+    #[inline]
+    pub(crate) const fn new(kind: DirEventKind, path: PathBuf, is_dir: bool) -> Self {
+        Self { kind, path, is_dir }
+    }
+
+    /// Returns the kind of change this event describes.
+    #[inline]
+    pub const fn kind(&self) -> &DirEventKind {
+        &self.kind
+    }
+
+    /// Returns the path of the changed file or directory, relative to the
+    /// directory the watch was added through. For `DirEventKind::Renamed`
+    /// events, this is the new path.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns `true` if the changed object is a directory.
+    #[inline]
+    pub const fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+}
+
+/// A watcher which reports changes to files and directories.
+///
+/// Each watch is added through a path relative to a directory, which is
+/// resolved with the same sandboxing as `open`, so the resolution can't
+/// escape the directory tree. Once the watch is added, it follows the
+/// object the path resolved to, even if the path is later renamed or
+/// replaced by a symlink.
+pub struct Watcher {
+    inner: WatcherImpl,
+}
+
+impl Watcher {
+    /// Creates a new `Watcher` with no watches.
+    #[inline]
+    pub fn new() -> io::Result<Self> {
+        Ok(Self {
+            inner: WatcherImpl::new(false)?,
+        })
+    }
+
+    /// Creates a new `Watcher` with no watches, whose `read_events` fails
+    /// with `io::ErrorKind::WouldBlock` instead of blocking when no change
+    /// has happened.
+    ///
+    /// This is for use with an event loop, which can wait for the watcher's
+    /// file descriptor to become readable.
+    #[inline]
+    pub fn new_nonblocking() -> io::Result<Self> {
+        Ok(Self {
+            inner: WatcherImpl::new(true)?,
+        })
+    }
+
+    /// Starts watching the file or directory at `path`, ensuring that the
+    /// resolution of the path never escapes the directory tree rooted at
+    /// `start`.
+    ///
+    /// If `path` names a directory, changes to the entries in it are
+    /// reported, and the paths in the events are `path` joined with the
+    /// entry names.
+    #[inline]
+    pub fn add(&mut self, start: &fs::File, path: &Path) -> io::Result<()> {
+        self.inner.add(start, path)
+    }
+
+    /// Blocks until at least one change has happened, and returns the events
+    /// describing the changes.
+    ///
+    /// This may return an empty `Vec` if the only changes were internal to
+    /// the watcher, such as a watched object being removed.
+    #[inline]
+    pub fn read_events(&mut self) -> io::Result<Vec<DirEvent>> {
+        self.inner.read_events()
+    }
+}
+
+impl AsRawFd for Watcher {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl AsFd for Watcher {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+impl fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
@@ -0,0 +1,161 @@
+//! This defines functions for reading and writing extended attributes, both
+//! through paths relative to a directory and through open files.
+//!
+//! Extended attributes are currently only supported on Linux.
+
+use crate::fs::FollowSymlinks;
+#[cfg(target_os = "linux")]
+use crate::fs::{
+    fgetxattr_impl, flistxattr_impl, fremovexattr_impl, fsetxattr_impl, getxattr_impl,
+    listxattr_impl, removexattr_impl, setxattr_impl,
+};
+use std::{
+    ffi::{OsStr, OsString},
+    fs, io,
+    path::Path,
+};
+
+/// Returns the value of the extended attribute `name` of the file at `path`,
+/// ensuring that the resolution of the path never escapes the directory tree
+/// rooted at `start`.
+#[inline]
+pub fn getxattr(
+    start: &fs::File,
+    path: &Path,
+    name: &OsStr,
+    follow: FollowSymlinks,
+) -> io::Result<Vec<u8>> {
+    getxattr_impl(start, path, name, follow)
+}
+
+/// Sets the extended attribute `name` of the file at `path` to `value`,
+/// creating it if it doesn't exist, ensuring that the resolution of the path
+/// never escapes the directory tree rooted at `start`.
+#[inline]
+pub fn setxattr(
+    start: &fs::File,
+    path: &Path,
+    name: &OsStr,
+    value: &[u8],
+    follow: FollowSymlinks,
+) -> io::Result<()> {
+    setxattr_impl(start, path, name, value, follow)
+}
+
+/// Returns the names of the extended attributes of the file at `path`,
+/// ensuring that the resolution of the path never escapes the directory tree
+/// rooted at `start`.
+#[inline]
+pub fn listxattr(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+) -> io::Result<Vec<OsString>> {
+    listxattr_impl(start, path, follow)
+}
+
+/// Removes the extended attribute `name` of the file at `path`, ensuring that
+/// the resolution of the path never escapes the directory tree rooted at
+/// `start`.
+#[inline]
+pub fn removexattr(
+    start: &fs::File,
+    path: &Path,
+    name: &OsStr,
+    follow: FollowSymlinks,
+) -> io::Result<()> {
+    removexattr_impl(start, path, name, follow)
+}
+
+/// Returns the value of the extended attribute `name` of `file`.
+#[inline]
+pub fn fgetxattr(file: &fs::File, name: &OsStr) -> io::Result<Vec<u8>> {
+    fgetxattr_impl(file, name)
+}
+
+/// Sets the extended attribute `name` of `file` to `value`, creating it if it
+/// doesn't exist.
+#[inline]
+pub fn fsetxattr(file: &fs::File, name: &OsStr, value: &[u8]) -> io::Result<()> {
+    fsetxattr_impl(file, name, value)
+}
+
+/// Returns the names of the extended attributes of `file`.
+#[inline]
+pub fn flistxattr(file: &fs::File) -> io::Result<Vec<OsString>> {
+    flistxattr_impl(file)
+}
+
+/// Removes the extended attribute `name` of `file`.
+#[inline]
+pub fn fremovexattr(file: &fs::File, name: &OsStr) -> io::Result<()> {
+    fremovexattr_impl(file, name)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unsupported<T>() -> io::Result<T> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "extended attributes aren't supported on this platform",
+    ))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn getxattr_impl(
+    _start: &fs::File,
+    _path: &Path,
+    _name: &OsStr,
+    _follow: FollowSymlinks,
+) -> io::Result<Vec<u8>> {
+    unsupported()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn setxattr_impl(
+    _start: &fs::File,
+    _path: &Path,
+    _name: &OsStr,
+    _value: &[u8],
+    _follow: FollowSymlinks,
+) -> io::Result<()> {
+    unsupported()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn listxattr_impl(
+    _start: &fs::File,
+    _path: &Path,
+    _follow: FollowSymlinks,
+) -> io::Result<Vec<OsString>> {
+    unsupported()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn removexattr_impl(
+    _start: &fs::File,
+    _path: &Path,
+    _name: &OsStr,
+    _follow: FollowSymlinks,
+) -> io::Result<()> {
+    unsupported()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn fgetxattr_impl(_file: &fs::File, _name: &OsStr) -> io::Result<Vec<u8>> {
+    unsupported()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn fsetxattr_impl(_file: &fs::File, _name: &OsStr, _value: &[u8]) -> io::Result<()> {
+    unsupported()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn flistxattr_impl(_file: &fs::File) -> io::Result<Vec<OsString>> {
+    unsupported()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn fremovexattr_impl(_file: &fs::File, _name: &OsStr) -> io::Result<()> {
+    unsupported()
+}
@@ -19,3 +19,6 @@ mod winx;
 pub mod fs;
 pub mod net;
 pub mod time;
+
+// Re-export `ipnet` so that users can name the types used in `net::Catalog`.
+pub use ipnet;
@@ -0,0 +1,84 @@
+use crate::net::{TcpListenerOptions, UdpSocketOptions};
+use socket2::{Domain, SockAddr, Socket, Type};
+use std::{io, net};
+
+pub(crate) fn bind_tcp_listener_impl(
+    addr: &net::SocketAddr,
+    options: &TcpListenerOptions,
+) -> io::Result<net::TcpListener> {
+    let socket = Socket::new(Domain::for_address(*addr), Type::STREAM, None)?;
+
+    set_common_options(
+        &socket,
+        addr,
+        options.reuse_address,
+        options.reuse_port,
+        options.only_v6,
+        options.recv_buffer_size,
+        options.send_buffer_size,
+    )?;
+    socket.bind(&SockAddr::from(*addr))?;
+    socket.listen(options.backlog)?;
+
+    Ok(socket.into())
+}
+
+pub(crate) fn bind_udp_socket_impl(
+    addr: &net::SocketAddr,
+    options: &UdpSocketOptions,
+) -> io::Result<net::UdpSocket> {
+    let socket = Socket::new(Domain::for_address(*addr), Type::DGRAM, None)?;
+
+    set_common_options(
+        &socket,
+        addr,
+        options.reuse_address,
+        options.reuse_port,
+        options.only_v6,
+        options.recv_buffer_size,
+        options.send_buffer_size,
+    )?;
+    socket.bind(&SockAddr::from(*addr))?;
+
+    Ok(socket.into())
+}
+
+fn set_common_options(
+    socket: &Socket,
+    addr: &net::SocketAddr,
+    reuse_address: bool,
+    reuse_port: bool,
+    only_v6: Option<bool>,
+    recv_buffer_size: Option<usize>,
+    send_buffer_size: Option<usize>,
+) -> io::Result<()> {
+    if reuse_address {
+        socket.set_reuse_address(true)?;
+    }
+    if reuse_port {
+        set_reuse_port(socket)?;
+    }
+    if let (Some(only_v6), net::SocketAddr::V6(_)) = (only_v6, addr) {
+        socket.set_only_v6(only_v6)?;
+    }
+    if let Some(size) = recv_buffer_size {
+        socket.set_recv_buffer_size(size)?;
+    }
+    if let Some(size) = send_buffer_size {
+        socket.set_send_buffer_size(size)?;
+    }
+    Ok(())
+}
+
+#[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+fn set_reuse_port(socket: &Socket) -> io::Result<()> {
+    socket.set_reuse_port(true)
+}
+
+#[cfg(not(all(unix, not(any(target_os = "solaris", target_os = "illumos")))))]
+fn set_reuse_port(_socket: &Socket) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "SO_REUSEPORT isn't supported on this platform",
+    ))
+}
@@ -1,12 +1,11 @@
-#![allow(missing_docs)] // TODO: add docs
-#![allow(dead_code, unused_variables)] // TODO: When more things are implemented, remove these.
-
+use crate::net::{Operations, Resolver, SystemResolver};
 use ipnet::IpNet;
-use std::{io, net};
+use std::{io, net, sync::Arc};
 
 // FIXME: lots more to do here
 
-enum AddrSet {
+#[derive(Clone)]
+pub(super) enum AddrSet {
     Net(IpNet),
     NameWildcard(String),
 }
@@ -15,34 +14,365 @@ impl AddrSet {
     fn contains(&self, addr: net::IpAddr) -> bool {
         match self {
             Self::Net(ip_net) => ip_net.contains(&addr),
-            Self::NameWildcard(name) => false,
+            Self::NameWildcard(_) => false,
+        }
+    }
+
+    fn matches_name(&self, name: &str) -> bool {
+        match self {
+            Self::Net(_) => false,
+            Self::NameWildcard(pattern) => name_matches(pattern, &normalize_name(name)),
+        }
+    }
+
+    fn intersect(&self, other: &Self) -> Option<Self> {
+        match (self, other) {
+            (Self::Net(a), Self::Net(b)) => {
+                if a.contains(b) {
+                    Some(Self::Net(*b))
+                } else if b.contains(a) {
+                    Some(Self::Net(*a))
+                } else {
+                    None
+                }
+            }
+            (Self::NameWildcard(a), Self::NameWildcard(b)) => {
+                if name_matches(a, b) {
+                    Some(Self::NameWildcard(b.clone()))
+                } else if name_matches(b, a) {
+                    Some(Self::NameWildcard(a.clone()))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Host names are compared case-insensitively, and without a trailing dot.
+pub(super) fn normalize_name(name: &str) -> String {
+    name.trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// Test whether `pattern` matches `name`, which are both normalized. A
+/// pattern of the form `*.suffix` matches any name with one or more labels
+/// in front of `.suffix`, and also matches any narrower pattern of that form.
+fn name_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_prefix('*') {
+        Some(suffix) if suffix.starts_with('.') => {
+            name.len() > suffix.len() && name.ends_with(suffix)
         }
+        _ => pattern == name,
     }
 }
 
-struct IpGrant {
-    set: AddrSet,
-    port: u16, // TODO: IANA port names, TODO: range
+#[derive(Clone)]
+pub(super) struct IpGrant {
+    pub(super) set: AddrSet,
+    // TODO: IANA port names
+    pub(super) ports_start: u16,
+    pub(super) ports_end: u16,
+    pub(super) operations: Operations,
 }
 
 impl IpGrant {
-    fn contains(&self, addr: &net::SocketAddr) -> bool {
-        self.set.contains(addr.ip()) && addr.port() == self.port
+    fn contains(&self, addr: &net::SocketAddr, operations: Operations) -> bool {
+        self.operations.contains(operations)
+            && self.set.contains(addr.ip())
+            && self.ports_start <= addr.port()
+            && addr.port() <= self.ports_end
+    }
+
+    fn contains_name(&self, name: &str, port: u16, operations: Operations) -> bool {
+        self.operations.contains(operations)
+            && self.set.matches_name(name)
+            && self.ports_start <= port
+            && port <= self.ports_end
+    }
+
+    fn intersect(&self, other: &Self) -> Option<Self> {
+        let set = self.set.intersect(&other.set)?;
+        let ports_start = self.ports_start.max(other.ports_start);
+        let ports_end = self.ports_end.min(other.ports_end);
+        let operations = self.operations & other.operations;
+        if ports_start > ports_end || operations.is_empty() {
+            return None;
+        }
+        Some(Self {
+            set,
+            ports_start,
+            ports_end,
+            operations,
+        })
     }
 }
 
 /// A representation of a set of network resources that may be accessed.
 /// This is presently a very incomplete concept.
 ///
+/// A new `Catalog` is empty and permits nothing; grants are added with the
+/// `insert_*` methods, which are `unsafe` because they confer access to
+/// network addresses using the host process' ambient authority.
+///
+/// Grants may name IP address ranges, or host names. Addresses are only
+/// permitted by host name grants once `lookup_host` has resolved a permitted
+/// name with the catalog's `Resolver` and inserted grants for the resulting
+/// addresses.
+///
 /// TODO: rename this?
+#[derive(Clone, Default)]
 pub struct Catalog {
     // TODO: when compiling for WASI, use WASI-specific handle instead
-    grants: Vec<IpGrant>,
+    pub(super) grants: Vec<IpGrant>,
+    resolver: Option<Arc<dyn Resolver>>,
 }
 
 impl Catalog {
-    pub fn check_addr(&self, addr: &net::SocketAddr) -> io::Result<()> {
-        if self.grants.iter().any(|grant| grant.contains(addr)) {
+    /// Construct a new empty catalog.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            grants: Vec::new(),
+            resolver: None,
+        }
+    }
+
+    /// Set the `Resolver` used to look up host names permitted by this
+    /// catalog. By default, the `SystemResolver` is used.
+    ///
+    /// # Safety
+    ///
+    /// The resolver decides which addresses the host names in this catalog
+    /// refer to, which may be any address that the host process has access
+    /// to.
+    #[inline]
+    pub unsafe fn set_resolver(&mut self, resolver: Arc<dyn Resolver>) -> &mut Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    /// Return the `Resolver` used to look up host names permitted by this
+    /// catalog.
+    #[inline]
+    pub fn resolver(&self) -> Arc<dyn Resolver> {
+        match &self.resolver {
+            Some(resolver) => Arc::clone(resolver),
+            None => Arc::new(SystemResolver),
+        }
+    }
+
+    /// Add a range of network addresses, accepting any port, to the catalog.
+    ///
+    /// The addresses may be used for all `Operations`.
+    ///
+    /// # Safety
+    ///
+    /// This grants access to any address in `ip_net`, which may be any
+    /// address that the host process has access to.
+    #[inline]
+    pub unsafe fn insert_ip_net_port_any(&mut self, ip_net: IpNet) -> &mut Self {
+        self.insert_ip_net_port_range(ip_net, 0, u16::MAX)
+    }
+
+    /// Add a range of network addresses, accepting a range of ports, to the
+    /// catalog.
+    ///
+    /// The range of ports is inclusive of both `ports_start` and `ports_end`.
+    /// The addresses may be used for all `Operations`.
+    ///
+    /// # Safety
+    ///
+    /// This grants access to any address in `ip_net`, which may be any
+    /// address that the host process has access to.
+    #[inline]
+    pub unsafe fn insert_ip_net_port_range(
+        &mut self,
+        ip_net: IpNet,
+        ports_start: u16,
+        ports_end: u16,
+    ) -> &mut Self {
+        self.insert_ip_net_operations(ip_net, ports_start, ports_end, Operations::all())
+    }
+
+    /// Add a range of network addresses, accepting a range of ports, to the
+    /// catalog, permitting only the given `operations` on them.
+    ///
+    /// The range of ports is inclusive of both `ports_start` and `ports_end`.
+    ///
+    /// # Safety
+    ///
+    /// This grants access to any address in `ip_net`, which may be any
+    /// address that the host process has access to.
+    #[inline]
+    pub unsafe fn insert_ip_net_operations(
+        &mut self,
+        ip_net: IpNet,
+        ports_start: u16,
+        ports_end: u16,
+        operations: Operations,
+    ) -> &mut Self {
+        self.grants.push(IpGrant {
+            set: AddrSet::Net(ip_net),
+            ports_start,
+            ports_end,
+            operations,
+        });
+        self
+    }
+
+    /// Add a range of network addresses with a specific port to the catalog.
+    ///
+    /// The addresses may be used for all `Operations`.
+    ///
+    /// # Safety
+    ///
+    /// This grants access to any address in `ip_net`, which may be any
+    /// address that the host process has access to.
+    #[inline]
+    pub unsafe fn insert_ip_net(&mut self, ip_net: IpNet, port: u16) -> &mut Self {
+        self.insert_ip_net_port_range(ip_net, port, port)
+    }
+
+    /// Add a host name pattern with a specific port to the catalog.
+    ///
+    /// The pattern is either a host name, such as `db.internal.example`, or
+    /// a wildcard, such as `*.internal.example`, which matches any name with
+    /// one or more labels in front of `.internal.example`. Names are compared
+    /// case-insensitively. The names may be used for all `Operations`.
+    ///
+    /// # Safety
+    ///
+    /// This grants access to any address that the catalog's resolver
+    /// produces for a matching name, which may be any address that the host
+    /// process has access to.
+    #[inline]
+    pub unsafe fn insert_name(&mut self, pattern: &str, port: u16) -> &mut Self {
+        self.insert_name_operations(pattern, port, port, Operations::all())
+    }
+
+    /// Add a host name pattern, accepting a range of ports, to the catalog,
+    /// permitting only the given `operations` on it.
+    ///
+    /// The range of ports is inclusive of both `ports_start` and `ports_end`.
+    /// See [`insert_name`] for the syntax of `pattern`.
+    ///
+    /// # Safety
+    ///
+    /// This grants access to any address that the catalog's resolver
+    /// produces for a matching name, which may be any address that the host
+    /// process has access to.
+    ///
+    /// [`insert_name`]: #method.insert_name
+    #[inline]
+    pub unsafe fn insert_name_operations(
+        &mut self,
+        pattern: &str,
+        ports_start: u16,
+        ports_end: u16,
+        operations: Operations,
+    ) -> &mut Self {
+        self.grants.push(IpGrant {
+            set: AddrSet::NameWildcard(normalize_name(pattern)),
+            ports_start,
+            ports_end,
+            operations,
+        });
+        self
+    }
+
+    /// Add a specific `SocketAddr` to the catalog.
+    ///
+    /// The address may be used for all `Operations`.
+    ///
+    /// # Safety
+    ///
+    /// This grants access to `addr`, which may be any address that the host
+    /// process has access to.
+    #[inline]
+    pub unsafe fn insert_socket_addr(&mut self, addr: net::SocketAddr) -> &mut Self {
+        self.insert_ip_net(addr.ip().into(), addr.port())
+    }
+
+    /// Construct a new catalog which permits only the addresses in `ip_net`
+    /// with ports in the inclusive range from `ports_start` to `ports_end`
+    /// which are also permitted by `self`.
+    ///
+    /// This never permits anything that `self` doesn't, so it doesn't require
+    /// any ambient authority.
+    pub fn restrict(&self, ip_net: IpNet, ports_start: u16, ports_end: u16) -> Self {
+        let request = IpGrant {
+            set: AddrSet::Net(ip_net),
+            ports_start,
+            ports_end,
+            operations: Operations::all(),
+        };
+        Self {
+            grants: self
+                .grants
+                .iter()
+                .filter_map(|grant| grant.intersect(&request))
+                .collect(),
+            resolver: self.resolver.clone(),
+        }
+    }
+
+    /// Construct a new catalog which permits only the addresses permitted by
+    /// both `self` and `other`.
+    ///
+    /// This never permits anything that `self` doesn't, so it doesn't require
+    /// any ambient authority. The new catalog uses the resolver of `self`.
+    pub fn attenuate(&self, other: &Self) -> Self {
+        Self {
+            grants: self
+                .grants
+                .iter()
+                .flat_map(|grant| {
+                    other
+                        .grants
+                        .iter()
+                        .filter_map(move |request| grant.intersect(request))
+                })
+                .collect(),
+            resolver: self.resolver.clone(),
+        }
+    }
+
+    /// Construct a new catalog which permits the same addresses as `self`,
+    /// but only for the given `operations`.
+    ///
+    /// This never permits anything that `self` doesn't, so it doesn't require
+    /// any ambient authority.
+    pub fn restrict_operations(&self, operations: Operations) -> Self {
+        Self {
+            grants: self
+                .grants
+                .iter()
+                .filter_map(|grant| {
+                    let operations = grant.operations & operations;
+                    if operations.is_empty() {
+                        None
+                    } else {
+                        Some(IpGrant {
+                            operations,
+                            ..grant.clone()
+                        })
+                    }
+                })
+                .collect(),
+            resolver: self.resolver.clone(),
+        }
+    }
+
+    /// Check whether the given address is permitted by this catalog for all
+    /// of the given `operations`, and fail with `PermissionDenied` if it is
+    /// not.
+    pub fn check_addr(&self, addr: &net::SocketAddr, operations: Operations) -> io::Result<()> {
+        if self
+            .grants
+            .iter()
+            .any(|grant| grant.contains(addr, operations))
+        {
             Ok(())
         } else {
             Err(io::Error::new(
@@ -51,6 +381,94 @@ impl Catalog {
             ))
         }
     }
+
+    /// Check whether the given host name and port are permitted by this
+    /// catalog for all of the given `operations`, and fail with
+    /// `PermissionDenied` if they are not.
+    ///
+    /// Only host name grants are consulted; an IP address grant doesn't
+    /// permit any host names, even ones which resolve to addresses within it.
+    pub fn check_name(&self, name: &str, port: u16, operations: Operations) -> io::Result<()> {
+        if self
+            .grants
+            .iter()
+            .any(|grant| grant.contains_name(name, port, operations))
+        {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "A host name led outside the catalog",
+            ))
+        }
+    }
+
+    /// Check whether the given host name and port are permitted by this
+    /// catalog for all of the given `operations`, and if so, look up the
+    /// name with the catalog's resolver.
+    pub fn resolve_name(
+        &self,
+        name: &str,
+        port: u16,
+        operations: Operations,
+    ) -> io::Result<Vec<net::SocketAddr>> {
+        self.check_name(name, port, operations)?;
+        Ok(self
+            .resolver()
+            .resolve_host(name)?
+            .into_iter()
+            .map(|ip| net::SocketAddr::new(ip, port))
+            .collect())
+    }
+
+    /// Look up the given host name with the catalog's resolver, if the name
+    /// and port are permitted by this catalog for any operations, and insert
+    /// grants for the resulting addresses.
+    ///
+    /// Each returned address is granted, at `port` only, the operations that
+    /// the name is permitted for, so it may then be passed to `check_addr`.
+    /// This takes `&mut self` because it widens the addresses this catalog
+    /// permits; catalogs cloned or derived from this one beforehand are
+    /// unaffected, and the grants remain even if the name's addresses change
+    /// later.
+    pub fn lookup_host(&mut self, name: &str, port: u16) -> io::Result<Vec<net::SocketAddr>> {
+        let operations = self
+            .grants
+            .iter()
+            .filter(|grant| grant.contains_name(name, port, Operations::empty()))
+            .fold(Operations::empty(), |ops, grant| ops | grant.operations);
+        if operations.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "A host name led outside the catalog",
+            ));
+        }
+
+        let addrs = self
+            .resolver()
+            .resolve_host(name)?
+            .into_iter()
+            .map(|ip| net::SocketAddr::new(ip, port))
+            .collect::<Vec<_>>();
+
+        for addr in &addrs {
+            if !self
+                .grants
+                .iter()
+                .any(|grant| grant.contains(addr, operations))
+            {
+                self.grants.push(IpGrant {
+                    set: AddrSet::Net(addr.ip().into()),
+                    ports_start: port,
+                    ports_end: port,
+                    operations,
+                });
+            }
+        }
+
+        Ok(addrs)
+    }
 }
 
+/// An empty array of `SocketAddr`s.
 pub const NO_SOCKET_ADDRS: &[net::SocketAddr] = &[];
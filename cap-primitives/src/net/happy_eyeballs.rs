@@ -0,0 +1,166 @@
+//! Connecting to the first responsive address among several, following
+//! [RFC 8305] ("Happy Eyeballs Version 2").
+//!
+//! [RFC 8305]: https://tools.ietf.org/html/rfc8305
+
+use crate::net::{connect_in_progress, poll_connect_impl, Catalog, Operations, NO_SOCKET_ADDRS};
+use socket2::{Domain, SockAddr, Socket, Type};
+use std::{
+    io, net,
+    time::{Duration, Instant},
+};
+
+/// The time to wait for a connection attempt before starting the next one,
+/// as recommended in RFC 8305 section 8.
+pub const CONNECTION_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+impl Catalog {
+    /// Connect to whichever of `addrs` responds first, racing IPv6 and IPv4
+    /// candidates as described in RFC 8305.
+    ///
+    /// The candidates are chosen by [`happy_eyeballs_candidates`], and a new
+    /// attempt is started whenever the previous one fails or has been
+    /// pending for [`CONNECTION_ATTEMPT_DELAY`]. The attempts are
+    /// non-blocking connects on the calling thread. The first connection to
+    /// succeed is returned and the others are closed. If no connection
+    /// succeeds within `timeout`, this fails with `TimedOut`.
+    ///
+    /// [`happy_eyeballs_candidates`]: #method.happy_eyeballs_candidates
+    /// [`CONNECTION_ATTEMPT_DELAY`]: constant.CONNECTION_ATTEMPT_DELAY.html
+    pub fn connect_happy_eyeballs(
+        &self,
+        addrs: &[net::SocketAddr],
+        timeout: Duration,
+    ) -> io::Result<net::TcpStream> {
+        let deadline = Instant::now() + timeout;
+
+        let mut candidates = self.happy_eyeballs_candidates(addrs)?.into_iter();
+        let mut attempts = Vec::new();
+        let mut last_err = None;
+        let mut next_start = Instant::now();
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "connection attempts timed out",
+                ));
+            }
+
+            if now >= next_start {
+                if let Some(addr) = candidates.next() {
+                    match start_connect(&addr) {
+                        Ok(socket) => {
+                            attempts.push(socket);
+                            next_start = now + CONNECTION_ATTEMPT_DELAY;
+                        }
+                        Err(e) => last_err = Some(e),
+                    }
+                    continue;
+                }
+            }
+
+            if attempts.is_empty() {
+                return Err(last_err.unwrap());
+            }
+
+            let wake = if candidates.as_slice().is_empty() {
+                deadline
+            } else {
+                next_start.min(deadline)
+            };
+            let ready = poll_connect_impl(&attempts, wake.saturating_duration_since(now))?;
+
+            // Check the ready attempts in the order they were started, so
+            // that earlier candidates win ties.
+            let mut index = 0;
+            for ready in ready {
+                if !ready {
+                    index += 1;
+                    continue;
+                }
+                match finish_connect(&attempts[index]) {
+                    Ok(()) => {
+                        let socket = attempts.swap_remove(index);
+                        socket.set_nonblocking(false)?;
+                        return Ok(socket.into());
+                    }
+                    Err(e) => {
+                        // Start the next attempt right away.
+                        attempts.remove(index);
+                        last_err = Some(e);
+                        next_start = Instant::now();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Return the addresses in `addrs` to attempt, in the order to attempt
+    /// them, when connecting as described in RFC 8305.
+    ///
+    /// Addresses not permitted by this catalog for `Operations::CONNECT` are
+    /// skipped. The remaining addresses are interleaved by address family,
+    /// starting with the family of the first one. If none remain, this fails
+    /// with the error for the last denied address.
+    pub fn happy_eyeballs_candidates(
+        &self,
+        addrs: &[net::SocketAddr],
+    ) -> io::Result<Vec<net::SocketAddr>> {
+        let mut permitted = Vec::with_capacity(addrs.len());
+        let mut denied = None;
+        for addr in addrs {
+            match self.check_addr(addr, Operations::CONNECT) {
+                Ok(()) => permitted.push(*addr),
+                Err(e) => denied = Some(e),
+            }
+        }
+        if permitted.is_empty() {
+            return Err(
+                denied.unwrap_or_else(|| net::TcpStream::connect(NO_SOCKET_ADDRS).unwrap_err())
+            );
+        }
+        Ok(interleave(permitted))
+    }
+}
+
+/// Start a non-blocking connection to `addr`.
+fn start_connect(addr: &net::SocketAddr) -> io::Result<Socket> {
+    let socket = Socket::new(Domain::for_address(*addr), Type::STREAM, None)?;
+    socket.set_nonblocking(true)?;
+    match socket.connect(&SockAddr::from(*addr)) {
+        Ok(()) => Ok(socket),
+        Err(e) if connect_in_progress(&e) => Ok(socket),
+        Err(e) => Err(e),
+    }
+}
+
+/// Report the outcome of a connection attempt which `poll_connect_impl` has
+/// reported as ready.
+fn finish_connect(socket: &Socket) -> io::Result<()> {
+    match socket.take_error()? {
+        Some(e) => Err(e),
+        None => socket.peer_addr().map(|_| ()),
+    }
+}
+
+/// Order `addrs` so that address families alternate, starting with the
+/// family of the first address, as described in RFC 8305 section 4.
+fn interleave(addrs: Vec<net::SocketAddr>) -> Vec<net::SocketAddr> {
+    let first_is_ipv6 = addrs[0].is_ipv6();
+    let (preferred, other): (Vec<_>, Vec<_>) = addrs
+        .into_iter()
+        .partition(|addr| addr.is_ipv6() == first_is_ipv6);
+    let mut result = Vec::with_capacity(preferred.len() + other.len());
+    let mut preferred = preferred.into_iter();
+    let mut other = other.into_iter();
+    loop {
+        match (preferred.next(), other.next()) {
+            (None, None) => return result,
+            (a, b) => {
+                result.extend(a);
+                result.extend(b);
+            }
+        }
+    }
+}
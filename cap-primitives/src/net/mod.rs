@@ -1,5 +1,28 @@
 //! Networking utilities.
 
+#[cfg(not(target_os = "wasi"))]
+mod bind_impl;
 mod catalog;
+#[cfg(not(target_os = "wasi"))]
+mod happy_eyeballs;
+mod operations;
+mod peer_filter;
+mod policy;
+mod resolver;
+mod socket_options;
+
+#[cfg(not(target_os = "wasi"))]
+pub(crate) use bind_impl::*;
+#[cfg(not(windows))]
+pub(crate) use super::posish::net::*;
+#[cfg(windows)]
+pub(crate) use super::winx::net::*;
 
 pub use catalog::*;
+#[cfg(not(target_os = "wasi"))]
+pub use happy_eyeballs::*;
+pub use operations::*;
+pub use peer_filter::*;
+pub use policy::*;
+pub use resolver::*;
+pub use socket_options::*;
@@ -0,0 +1,112 @@
+use std::{fmt, ops};
+
+/// A set of network operations that a grant in a `Catalog` may permit.
+///
+/// Sets may be combined with `|` and intersected with `&`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Operations {
+    bits: u8,
+}
+
+impl Operations {
+    /// Binding a TCP listener or a UDP socket to a local address.
+    pub const BIND: Self = Self { bits: 0b0001 };
+
+    /// Connecting a TCP stream to a remote address.
+    pub const CONNECT: Self = Self { bits: 0b0010 };
+
+    /// Sending UDP datagrams to a remote address.
+    pub const UDP_SEND: Self = Self { bits: 0b0100 };
+
+    /// Receiving UDP datagrams from a remote address.
+    pub const UDP_RECEIVE: Self = Self { bits: 0b1000 };
+
+    /// Accepting TCP connections from a remote address.
+    pub const ACCEPT: Self = Self { bits: 0b1_0000 };
+
+    /// Returns the empty set of operations.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self { bits: 0 }
+    }
+
+    /// Returns the set of all operations.
+    #[inline]
+    pub const fn all() -> Self {
+        Self { bits: 0b1_1111 }
+    }
+
+    /// Returns `true` if `self` contains no operations.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.bits == 0
+    }
+
+    /// Returns `true` if all of the operations in `other` are in `self`.
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.bits & other.bits == other.bits
+    }
+}
+
+impl ops::BitOr for Operations {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        Self {
+            bits: self.bits | other.bits,
+        }
+    }
+}
+
+impl ops::BitOrAssign for Operations {
+    #[inline]
+    fn bitor_assign(&mut self, other: Self) {
+        self.bits |= other.bits;
+    }
+}
+
+impl ops::BitAnd for Operations {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        Self {
+            bits: self.bits & other.bits,
+        }
+    }
+}
+
+impl ops::BitAndAssign for Operations {
+    #[inline]
+    fn bitand_assign(&mut self, other: Self) {
+        self.bits &= other.bits;
+    }
+}
+
+impl fmt::Debug for Operations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = [
+            (Self::BIND, "BIND"),
+            (Self::CONNECT, "CONNECT"),
+            (Self::UDP_SEND, "UDP_SEND"),
+            (Self::UDP_RECEIVE, "UDP_RECEIVE"),
+            (Self::ACCEPT, "ACCEPT"),
+        ];
+        let mut first = true;
+        for (op, name) in &names {
+            if self.contains(*op) {
+                if !first {
+                    f.write_str(" | ")?;
+                }
+                f.write_str(name)?;
+                first = false;
+            }
+        }
+        if first {
+            f.write_str("(empty)")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use crate::net::{Catalog, Operations};
+use ipnet::IpNet;
+use std::{fmt, net, sync::Arc};
+
+#[derive(Clone)]
+enum Allowed {
+    Catalog(Arc<Catalog>),
+    IpNets(Vec<IpNet>),
+}
+
+/// A set of remote addresses which a TCP listener accepts connections from.
+///
+/// Connections from other addresses are closed as soon as they're accepted,
+/// and reported to the rejection hook, if one is set.
+///
+/// Unlike a `Catalog`, a filter doesn't grant access to anything; it only
+/// narrows the set of peers that a listener which has already been bound
+/// will talk to.
+#[derive(Clone)]
+pub struct PeerFilter {
+    allowed: Allowed,
+    on_reject: Option<Arc<dyn Fn(net::SocketAddr) + Send + Sync>>,
+}
+
+impl PeerFilter {
+    /// Construct a new filter which accepts connections from addresses that
+    /// `catalog` permits for `Operations::ACCEPT`.
+    #[inline]
+    pub fn from_catalog(catalog: Arc<Catalog>) -> Self {
+        Self {
+            allowed: Allowed::Catalog(catalog),
+            on_reject: None,
+        }
+    }
+
+    /// Construct a new filter which accepts connections from addresses in any
+    /// of the given `ip_nets`, on any port.
+    #[inline]
+    pub fn from_ip_nets<I: IntoIterator<Item = IpNet>>(ip_nets: I) -> Self {
+        Self {
+            allowed: Allowed::IpNets(ip_nets.into_iter().collect()),
+            on_reject: None,
+        }
+    }
+
+    /// Set a hook which is called with the address of each peer whose
+    /// connection is rejected.
+    pub fn on_reject<F: Fn(net::SocketAddr) + Send + Sync + 'static>(
+        &mut self,
+        on_reject: F,
+    ) -> &mut Self {
+        self.on_reject = Some(Arc::new(on_reject));
+        self
+    }
+
+    /// Test whether connections from `peer` are accepted by this filter.
+    pub fn permits(&self, peer: &net::SocketAddr) -> bool {
+        match &self.allowed {
+            Allowed::Catalog(catalog) => catalog.check_addr(peer, Operations::ACCEPT).is_ok(),
+            Allowed::IpNets(ip_nets) => ip_nets.iter().any(|ip_net| ip_net.contains(&peer.ip())),
+        }
+    }
+
+    /// Test whether connections from `peer` are accepted by this filter, and
+    /// if not, report the rejection to the hook.
+    pub fn check_peer(&self, peer: &net::SocketAddr) -> bool {
+        if self.permits(peer) {
+            return true;
+        }
+        if let Some(on_reject) = &self.on_reject {
+            on_reject(*peer);
+        }
+        false
+    }
+}
+
+impl fmt::Debug for PeerFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Don't leak the addresses.
+        let mut res = f.debug_struct("PeerFilter");
+        res.field("on_reject", &self.on_reject.is_some());
+        res.finish()
+    }
+}
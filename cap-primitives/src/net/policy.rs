@@ -0,0 +1,265 @@
+//! Parsing and printing of textual policies describing `Catalog`s.
+
+use crate::net::{
+    catalog::{normalize_name, AddrSet, IpGrant},
+    Catalog, Operations,
+};
+use ipnet::IpNet;
+use std::{error, fmt, io, net};
+
+const OPERATION_NAMES: [(Operations, &str); 5] = [
+    (Operations::BIND, "bind"),
+    (Operations::CONNECT, "connect"),
+    (Operations::UDP_SEND, "udp-send"),
+    (Operations::UDP_RECEIVE, "udp-receive"),
+    (Operations::ACCEPT, "accept"),
+];
+
+/// An error encountered while parsing a policy with `Catalog::from_policy`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyError {
+    line: usize,
+    column: usize,
+    message: String,
+}
+
+impl PolicyError {
+    fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the 1-based line number of the error.
+    #[inline]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the 1-based column number, in characters, of the error.
+    #[inline]
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns a description of the error, without its position.
+    #[inline]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl error::Error for PolicyError {}
+
+impl From<PolicyError> for io::Error {
+    fn from(err: PolicyError) -> Self {
+        Self::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+impl Catalog {
+    /// Construct a new catalog from the grants in a textual policy.
+    ///
+    /// A policy consists of one grant per line. Each grant is an address, a
+    /// port specification, and optionally a comma-separated list of
+    /// operations:
+    ///
+    /// ```text
+    /// # Comments extend to the end of the line.
+    /// 10.0.0.0/8          443        connect
+    /// 192.168.1.0/24      8000-8099  bind,connect
+    /// fd00::/8            *          all
+    /// 203.0.113.7         53         udp-send,udp-receive
+    /// *.internal.example  8080       connect
+    /// ```
+    ///
+    /// Addresses are CIDR blocks, single IP addresses, or host name patterns
+    /// as accepted by `insert_name`. Ports are a single port, an inclusive
+    /// range, or `*` for any port. Operations are `bind`, `connect`,
+    /// `udp-send`, `udp-receive`, `accept`, or `all`; if they're omitted,
+    /// all operations are permitted.
+    ///
+    /// # Safety
+    ///
+    /// This grants access to all of the addresses in `policy`, which may be
+    /// any addresses that the host process has access to.
+    pub unsafe fn from_policy(policy: &str) -> Result<Self, PolicyError> {
+        let mut catalog = Self::new();
+        for (index, line) in policy.lines().enumerate() {
+            if let Some(grant) = parse_line(index + 1, line)? {
+                catalog.grants.push(grant);
+            }
+        }
+        Ok(catalog)
+    }
+
+    /// Return a textual policy describing the grants in this catalog, which
+    /// may be parsed with `from_policy`.
+    ///
+    /// The catalog's resolver isn't included. Grants inserted by
+    /// `lookup_host` are included, as IP address grants.
+    pub fn to_policy(&self) -> String {
+        let mut policy = String::new();
+        for grant in &self.grants {
+            // Grants with no operations permit nothing, and there's no way
+            // to write them.
+            if grant.operations.is_empty() {
+                continue;
+            }
+            let addr = match &grant.set {
+                AddrSet::Net(ip_net) => ip_net.to_string(),
+                AddrSet::NameWildcard(pattern) => pattern.clone(),
+            };
+            let ports = if (grant.ports_start, grant.ports_end) == (0, u16::MAX) {
+                "*".to_string()
+            } else if grant.ports_start == grant.ports_end {
+                grant.ports_start.to_string()
+            } else {
+                format!("{}-{}", grant.ports_start, grant.ports_end)
+            };
+            let operations = if grant.operations == Operations::all() {
+                "all".to_string()
+            } else {
+                OPERATION_NAMES
+                    .iter()
+                    .filter(|(op, _)| grant.operations.contains(*op))
+                    .map(|(_, name)| *name)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            };
+            policy.push_str(&format!("{} {} {}\n", addr, ports, operations));
+        }
+        policy
+    }
+}
+
+/// Split `line` into whitespace-separated tokens, with their 1-based
+/// character columns, stopping at a comment.
+fn tokens(line: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut column = 0;
+    for (byte, c) in line.char_indices() {
+        column += 1;
+        if c == '#' && start.is_none() {
+            return tokens;
+        }
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some((column, byte)),
+            (true, Some((start_column, start_byte))) => {
+                tokens.push((start_column, &line[start_byte..byte]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((start_column, start_byte)) = start {
+        tokens.push((start_column, &line[start_byte..]));
+    }
+    tokens
+}
+
+fn parse_line(line_number: usize, line: &str) -> Result<Option<IpGrant>, PolicyError> {
+    let tokens = tokens(line);
+    let err = |column, message: &str| PolicyError::new(line_number, column, message);
+    let end_column = line.chars().count() + 1;
+
+    let (addr_column, addr) = match tokens.first() {
+        Some(token) => *token,
+        None => return Ok(None),
+    };
+    let set = parse_addr(addr).ok_or_else(|| err(addr_column, "invalid address"))?;
+
+    let (ports_start, ports_end) = match tokens.get(1) {
+        Some((column, ports)) => parse_ports(ports).ok_or_else(|| err(*column, "invalid ports"))?,
+        None => return Err(err(end_column, "expected ports")),
+    };
+
+    let operations = match tokens.get(2) {
+        Some((column, operations)) => parse_operations(*column, operations)
+            .map_err(|(column, message)| err(column, message))?,
+        None => Operations::all(),
+    };
+
+    if let Some((column, _)) = tokens.get(3) {
+        return Err(err(*column, "unexpected token"));
+    }
+
+    Ok(Some(IpGrant {
+        set,
+        ports_start,
+        ports_end,
+        operations,
+    }))
+}
+
+fn parse_addr(addr: &str) -> Option<AddrSet> {
+    if let Ok(ip_net) = addr.parse::<IpNet>() {
+        return Some(AddrSet::Net(ip_net));
+    }
+    if let Ok(ip) = addr.parse::<net::IpAddr>() {
+        return Some(AddrSet::Net(ip.into()));
+    }
+    if is_name_pattern(addr) {
+        return Some(AddrSet::NameWildcard(normalize_name(addr)));
+    }
+    None
+}
+
+fn is_name_pattern(pattern: &str) -> bool {
+    let name = pattern.strip_prefix("*.").unwrap_or(pattern);
+    let name = name.strip_suffix('.').unwrap_or(name);
+    // Anything made of only digits and dots is meant to be an IPv4 address,
+    // so if it didn't parse as one, it's a typo rather than a host name.
+    !name.is_empty()
+        && !name.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && !name.contains('/')
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+fn parse_ports(ports: &str) -> Option<(u16, u16)> {
+    if ports == "*" {
+        return Some((0, u16::MAX));
+    }
+    let (start, end) = match ports.find('-') {
+        Some(dash) => (ports[..dash].parse().ok()?, ports[dash + 1..].parse().ok()?),
+        None => {
+            let port = ports.parse().ok()?;
+            (port, port)
+        }
+    };
+    if start > end {
+        return None;
+    }
+    Some((start, end))
+}
+
+fn parse_operations(column: usize, operations: &str) -> Result<Operations, (usize, &'static str)> {
+    let mut result = Operations::empty();
+    let mut column = column;
+    for name in operations.split(',') {
+        if name == "all" {
+            result |= Operations::all();
+        } else {
+            match OPERATION_NAMES.iter().find(|(_, n)| *n == name) {
+                Some((op, _)) => result |= *op,
+                None => return Err((column, "unknown operation")),
+            }
+        }
+        column += name.chars().count() + 1;
+    }
+    Ok(result)
+}
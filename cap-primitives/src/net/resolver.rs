@@ -0,0 +1,66 @@
+use crate::net::catalog::normalize_name;
+use std::{
+    collections::HashMap,
+    io,
+    net::{self, ToSocketAddrs},
+};
+
+/// A source of address lookups for host names permitted by a `Catalog`.
+///
+/// A `Catalog` consults its resolver only for names which one of its name
+/// grants permits, so a resolver never widens the set of names that may be
+/// used, however it does determine which addresses those names reach.
+pub trait Resolver: Send + Sync {
+    /// Look up the addresses of `host`.
+    fn resolve_host(&self, host: &str) -> io::Result<Vec<net::IpAddr>>;
+}
+
+/// A `Resolver` which uses the host process' system resolver.
+///
+/// This is used by `Catalog`s which haven't been given another resolver.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemResolver;
+
+impl Resolver for SystemResolver {
+    fn resolve_host(&self, host: &str) -> io::Result<Vec<net::IpAddr>> {
+        Ok((host, 0).to_socket_addrs()?.map(|addr| addr.ip()).collect())
+    }
+}
+
+/// A `Resolver` which looks up names in an in-memory table, in the manner of
+/// a hosts file, without using the network.
+///
+/// Names are compared case-insensitively, and without a trailing dot.
+#[derive(Debug, Clone, Default)]
+pub struct HostsResolver {
+    hosts: HashMap<String, Vec<net::IpAddr>>,
+}
+
+impl HostsResolver {
+    /// Construct a new empty table.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            hosts: HashMap::new(),
+        }
+    }
+
+    /// Add an address for `host` to the table. A host may have several
+    /// addresses, which are returned in the order they were inserted.
+    pub fn insert(&mut self, host: &str, ip: net::IpAddr) -> &mut Self {
+        self.hosts.entry(normalize_name(host)).or_default().push(ip);
+        self
+    }
+}
+
+impl Resolver for HostsResolver {
+    fn resolve_host(&self, host: &str) -> io::Result<Vec<net::IpAddr>> {
+        match self.hosts.get(&normalize_name(host)) {
+            Some(ips) => Ok(ips.clone()),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "host not found in the hosts table",
+            )),
+        }
+    }
+}
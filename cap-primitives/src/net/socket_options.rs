@@ -0,0 +1,194 @@
+use crate::net::{bind_tcp_listener_impl, bind_udp_socket_impl, Catalog, Operations};
+use std::{io, net};
+
+/// Options which can be used to configure how a TCP listener is created.
+///
+/// Note that this `TcpListenerOptions` has no `bind` method. To bind a
+/// listener with a `TcpListenerOptions`, you must first obtain a [`Catalog`]
+/// permitting the address, and then call [`Catalog::bind_tcp_listener_with`].
+///
+/// The defaults match the options that [`std::net::TcpListener::bind`] uses.
+///
+/// [`Catalog`]: struct.Catalog.html
+/// [`Catalog::bind_tcp_listener_with`]: struct.Catalog.html#method.bind_tcp_listener_with
+/// [`std::net::TcpListener::bind`]: https://doc.rust-lang.org/std/net/struct.TcpListener.html#method.bind
+#[derive(Debug, Clone)]
+pub struct TcpListenerOptions {
+    pub(crate) reuse_address: bool,
+    pub(crate) reuse_port: bool,
+    pub(crate) backlog: i32,
+    pub(crate) only_v6: Option<bool>,
+    pub(crate) recv_buffer_size: Option<usize>,
+    pub(crate) send_buffer_size: Option<usize>,
+}
+
+impl TcpListenerOptions {
+    /// Creates a new set of options with the same defaults as
+    /// `std::net::TcpListener::bind`.
+    #[allow(clippy::new_without_default)]
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            // `std` sets `SO_REUSEADDR` on Unix-family platforms, where it
+            // only permits binding over connections in `TIME_WAIT`. On
+            // Windows it permits stealing ports, so it isn't set there.
+            reuse_address: cfg!(not(windows)),
+            reuse_port: false,
+            backlog: 128,
+            only_v6: None,
+            recv_buffer_size: None,
+            send_buffer_size: None,
+        }
+    }
+
+    /// Sets the option for the `SO_REUSEADDR` socket option.
+    ///
+    /// On Windows, this permits other sockets to bind to the same address,
+    /// including ones in other processes.
+    #[inline]
+    pub fn reuse_address(&mut self, reuse_address: bool) -> &mut Self {
+        self.reuse_address = reuse_address;
+        self
+    }
+
+    /// Sets the option for the `SO_REUSEPORT` socket option, which allows
+    /// several listeners to bind to the same address.
+    ///
+    /// This isn't supported on all platforms.
+    #[inline]
+    pub fn reuse_port(&mut self, reuse_port: bool) -> &mut Self {
+        self.reuse_port = reuse_port;
+        self
+    }
+
+    /// Sets the maximum number of pending connections, passed to `listen`.
+    #[inline]
+    pub fn backlog(&mut self, backlog: i32) -> &mut Self {
+        self.backlog = backlog;
+        self
+    }
+
+    /// Sets the option for the `IPV6_V6ONLY` socket option. This only applies
+    /// to listeners bound to IPv6 addresses.
+    #[inline]
+    pub fn only_v6(&mut self, only_v6: bool) -> &mut Self {
+        self.only_v6 = Some(only_v6);
+        self
+    }
+
+    /// Sets the option for the `SO_RCVBUF` socket option.
+    #[inline]
+    pub fn recv_buffer_size(&mut self, size: usize) -> &mut Self {
+        self.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Sets the option for the `SO_SNDBUF` socket option.
+    #[inline]
+    pub fn send_buffer_size(&mut self, size: usize) -> &mut Self {
+        self.send_buffer_size = Some(size);
+        self
+    }
+}
+
+/// Options which can be used to configure how a UDP socket is created.
+///
+/// Note that this `UdpSocketOptions` has no `bind` method. To bind a socket
+/// with a `UdpSocketOptions`, you must first obtain a [`Catalog`] permitting
+/// the address, and then call [`Catalog::bind_udp_socket_with`].
+///
+/// The defaults match the options that [`std::net::UdpSocket::bind`] uses.
+///
+/// [`Catalog`]: struct.Catalog.html
+/// [`Catalog::bind_udp_socket_with`]: struct.Catalog.html#method.bind_udp_socket_with
+/// [`std::net::UdpSocket::bind`]: https://doc.rust-lang.org/std/net/struct.UdpSocket.html#method.bind
+#[derive(Debug, Clone)]
+pub struct UdpSocketOptions {
+    pub(crate) reuse_address: bool,
+    pub(crate) reuse_port: bool,
+    pub(crate) only_v6: Option<bool>,
+    pub(crate) recv_buffer_size: Option<usize>,
+    pub(crate) send_buffer_size: Option<usize>,
+}
+
+impl UdpSocketOptions {
+    /// Creates a new set of options with the same defaults as
+    /// `std::net::UdpSocket::bind`.
+    #[allow(clippy::new_without_default)]
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            reuse_address: false,
+            reuse_port: false,
+            only_v6: None,
+            recv_buffer_size: None,
+            send_buffer_size: None,
+        }
+    }
+
+    /// Sets the option for the `SO_REUSEADDR` socket option.
+    ///
+    /// On Windows, this permits other sockets to bind to the same address,
+    /// including ones in other processes.
+    #[inline]
+    pub fn reuse_address(&mut self, reuse_address: bool) -> &mut Self {
+        self.reuse_address = reuse_address;
+        self
+    }
+
+    /// Sets the option for the `SO_REUSEPORT` socket option, which allows
+    /// several sockets to bind to the same address.
+    ///
+    /// This isn't supported on all platforms.
+    #[inline]
+    pub fn reuse_port(&mut self, reuse_port: bool) -> &mut Self {
+        self.reuse_port = reuse_port;
+        self
+    }
+
+    /// Sets the option for the `IPV6_V6ONLY` socket option. This only applies
+    /// to sockets bound to IPv6 addresses.
+    #[inline]
+    pub fn only_v6(&mut self, only_v6: bool) -> &mut Self {
+        self.only_v6 = Some(only_v6);
+        self
+    }
+
+    /// Sets the option for the `SO_RCVBUF` socket option.
+    #[inline]
+    pub fn recv_buffer_size(&mut self, size: usize) -> &mut Self {
+        self.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Sets the option for the `SO_SNDBUF` socket option.
+    #[inline]
+    pub fn send_buffer_size(&mut self, size: usize) -> &mut Self {
+        self.send_buffer_size = Some(size);
+        self
+    }
+}
+
+impl Catalog {
+    /// Create a TCP listener bound to `addr`, configured with `options`, if
+    /// `addr` is permitted by this catalog for `Operations::BIND`.
+    pub fn bind_tcp_listener_with(
+        &self,
+        addr: &net::SocketAddr,
+        options: &TcpListenerOptions,
+    ) -> io::Result<net::TcpListener> {
+        self.check_addr(addr, Operations::BIND)?;
+        bind_tcp_listener_impl(addr, options)
+    }
+
+    /// Create a UDP socket bound to `addr`, configured with `options`, if
+    /// `addr` is permitted by this catalog for `Operations::BIND`.
+    pub fn bind_udp_socket_with(
+        &self,
+        addr: &net::SocketAddr,
+        options: &UdpSocketOptions,
+    ) -> io::Result<net::UdpSocket> {
+        self.check_addr(addr, Operations::BIND)?;
+        bind_udp_socket_impl(addr, options)
+    }
+}
@@ -0,0 +1,57 @@
+use super::c_str;
+use crate::fs::errors;
+use std::{fs, io, os::unix::io::AsRawFd, path::Path};
+
+/// *Unsandboxed* function similar to `create_fifo`, but which does not
+/// perform sandboxing.
+pub(crate) fn create_fifo_unchecked(start: &fs::File, path: &Path, mode: u32) -> io::Result<()> {
+    let mode = permission_bits(mode)?;
+    let path = c_str(path)?;
+
+    // Android's libc doesn't have `mkfifoat`, but `mknodat` can create FIFOs.
+    #[cfg(not(target_os = "android"))]
+    let result = unsafe { libc::mkfifoat(start.as_raw_fd(), path.as_ptr(), mode) };
+    #[cfg(target_os = "android")]
+    let result =
+        unsafe { libc::mknodat(start.as_raw_fd(), path.as_ptr(), libc::S_IFIFO | mode, 0) };
+
+    cvt(result)
+}
+
+/// *Unsandboxed* function similar to `create_device_node`, but which does
+/// not perform sandboxing.
+// The `S_IF*` constants and `mode_t` are `u16` on some platforms.
+#[allow(clippy::unnecessary_cast)]
+pub(crate) fn create_device_node_unchecked(
+    start: &fs::File,
+    path: &Path,
+    mode: u32,
+    dev: u64,
+) -> io::Result<()> {
+    let kind = mode & libc::S_IFMT as u32;
+    if kind != libc::S_IFBLK as u32 && kind != libc::S_IFCHR as u32 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "device nodes must be block or character devices",
+        ));
+    }
+    let mode = kind as libc::mode_t | permission_bits(mode & !(libc::S_IFMT as u32))?;
+    let path = c_str(path)?;
+    cvt(unsafe { libc::mknodat(start.as_raw_fd(), path.as_ptr(), mode, dev as libc::dev_t) })
+}
+
+/// Check that `mode` only holds permission bits, as with `DirOptions`.
+fn permission_bits(mode: u32) -> io::Result<libc::mode_t> {
+    if mode & !0o7777 != 0 {
+        return Err(errors::invalid_flags());
+    }
+    Ok(mode as libc::mode_t)
+}
+
+fn cvt(result: libc::c_int) -> io::Result<()> {
+    if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
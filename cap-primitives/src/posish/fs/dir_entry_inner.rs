@@ -27,6 +27,13 @@ impl DirEntryInner {
         self.read_dir.metadata(self.file_name_bytes())
     }
 
+    /// Returns the metadata for the directory containing this entry.
+    #[cfg(unix)]
+    #[inline]
+    pub(crate) fn dir_metadata(&self) -> io::Result<Metadata> {
+        self.read_dir.self_metadata()
+    }
+
     #[inline]
     pub(crate) fn remove_file(&self) -> io::Result<()> {
         self.read_dir.remove_file(self.file_name_bytes())
@@ -0,0 +1,77 @@
+#[cfg(not(any(target_os = "ios", target_os = "macos", target_os = "redox")))]
+use posish::fs::{preadv, pwritev};
+use std::{
+    fs,
+    io::{self, IoSlice, IoSliceMut},
+};
+
+#[cfg(unix)]
+#[inline]
+pub(crate) fn read_at_impl(file: &fs::File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+    std::os::unix::fs::FileExt::read_at(file, buf, offset)
+}
+
+#[cfg(unix)]
+#[inline]
+pub(crate) fn write_at_impl(file: &fs::File, buf: &[u8], offset: u64) -> io::Result<usize> {
+    std::os::unix::fs::FileExt::write_at(file, buf, offset)
+}
+
+// `std::os::wasi::fs::FileExt` isn't stable yet, so use the vectored forms.
+#[cfg(target_os = "wasi")]
+#[inline]
+pub(crate) fn read_at_impl(file: &fs::File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+    preadv(file, &[IoSliceMut::new(buf)], offset)
+}
+
+#[cfg(target_os = "wasi")]
+#[inline]
+pub(crate) fn write_at_impl(file: &fs::File, buf: &[u8], offset: u64) -> io::Result<usize> {
+    pwritev(file, &[IoSlice::new(buf)], offset)
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos", target_os = "redox")))]
+#[inline]
+pub(crate) fn read_vectored_at_impl(
+    file: &fs::File,
+    bufs: &mut [IoSliceMut],
+    offset: u64,
+) -> io::Result<usize> {
+    preadv(file, bufs, offset)
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "macos", target_os = "redox")))]
+#[inline]
+pub(crate) fn write_vectored_at_impl(
+    file: &fs::File,
+    bufs: &[IoSlice],
+    offset: u64,
+) -> io::Result<usize> {
+    pwritev(file, bufs, offset)
+}
+
+#[cfg(any(target_os = "ios", target_os = "macos", target_os = "redox"))]
+pub(crate) fn read_vectored_at_impl(
+    file: &fs::File,
+    bufs: &mut [IoSliceMut],
+    offset: u64,
+) -> io::Result<usize> {
+    let buf = bufs
+        .iter_mut()
+        .find(|buf| !buf.is_empty())
+        .map_or(&mut [][..], |buf| &mut **buf);
+    read_at_impl(file, buf, offset)
+}
+
+#[cfg(any(target_os = "ios", target_os = "macos", target_os = "redox"))]
+pub(crate) fn write_vectored_at_impl(
+    file: &fs::File,
+    bufs: &[IoSlice],
+    offset: u64,
+) -> io::Result<usize> {
+    let buf = bufs
+        .iter()
+        .find(|buf| !buf.is_empty())
+        .map_or(&[][..], |buf| &**buf);
+    write_at_impl(file, buf, offset)
+}
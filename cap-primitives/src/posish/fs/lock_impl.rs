@@ -0,0 +1,66 @@
+use crate::fs::FileLockKind;
+use std::{fs, io};
+
+#[cfg(not(target_os = "wasi"))]
+pub(crate) fn lock_impl(file: &fs::File, kind: FileLockKind) -> io::Result<()> {
+    flock(file, operation(kind))
+}
+
+#[cfg(not(target_os = "wasi"))]
+pub(crate) fn try_lock_impl(file: &fs::File, kind: FileLockKind) -> io::Result<bool> {
+    match flock(file, operation(kind) | libc::LOCK_NB) {
+        Ok(()) => Ok(true),
+        Err(e) if e.raw_os_error() == Some(libc::EWOULDBLOCK) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(not(target_os = "wasi"))]
+pub(crate) fn unlock_impl(file: &fs::File) -> io::Result<()> {
+    flock(file, libc::LOCK_UN)
+}
+
+#[cfg(not(target_os = "wasi"))]
+fn operation(kind: FileLockKind) -> libc::c_int {
+    match kind {
+        FileLockKind::Shared => libc::LOCK_SH,
+        FileLockKind::Exclusive => libc::LOCK_EX,
+    }
+}
+
+#[cfg(not(target_os = "wasi"))]
+fn flock(file: &fs::File, operation: libc::c_int) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    loop {
+        if unsafe { libc::flock(file.as_raw_fd(), operation) } == 0 {
+            return Ok(());
+        }
+        let e = io::Error::last_os_error();
+        if e.kind() != io::ErrorKind::Interrupted {
+            return Err(e);
+        }
+    }
+}
+
+// WASI doesn't have file locking yet.
+
+#[cfg(target_os = "wasi")]
+pub(crate) fn lock_impl(_file: &fs::File, _kind: FileLockKind) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(target_os = "wasi")]
+pub(crate) fn try_lock_impl(_file: &fs::File, _kind: FileLockKind) -> io::Result<bool> {
+    Err(unsupported())
+}
+
+#[cfg(target_os = "wasi")]
+pub(crate) fn unlock_impl(_file: &fs::File) -> io::Result<()> {
+    Err(unsupported())
+}
+
+#[cfg(target_os = "wasi")]
+fn unsupported() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "file locking isn't supported on WASI")
+}
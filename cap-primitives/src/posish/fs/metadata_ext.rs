@@ -4,9 +4,9 @@ use crate::{
     fs::{FileTypeExt, Metadata, PermissionsExt},
     time::{Duration, SystemClock, SystemTime},
 };
+#[cfg(target_os = "linux")]
+use posish::fs::makedev;
 use posish::fs::LibcStat;
-#[cfg(all(target_os = "linux", target_env = "gnu"))]
-use posish::fs::{makedev, LibcStatx};
 use std::{convert::TryFrom, fs, io};
 
 #[derive(Debug, Clone)]
@@ -27,6 +27,32 @@ pub(crate) struct MetadataExt {
     ctime_nsec: i64,
     blksize: u64,
     blocks: u64,
+    #[cfg(target_os = "linux")]
+    statx: StatxExt,
+}
+
+/// The fields that only `statx` provides.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone)]
+struct StatxExt {
+    mask: u32,
+    attributes: u64,
+    attributes_mask: u64,
+    mnt_id: Option<u64>,
+}
+
+#[cfg(target_os = "linux")]
+impl StatxExt {
+    /// The fields that `stat` provides, for metadata that didn't come from
+    /// `statx`.
+    const fn basic_stats() -> Self {
+        Self {
+            mask: libc::STATX_BASIC_STATS,
+            attributes: 0,
+            attributes_mask: 0,
+            mnt_id: None,
+        }
+    }
 }
 
 impl MetadataExt {
@@ -60,6 +86,8 @@ impl MetadataExt {
             ctime_nsec: std.ctime_nsec(),
             blksize: std.blksize(),
             blocks: std.blocks(),
+            #[cfg(target_os = "linux")]
+            statx: StatxExt::basic_stats(),
         }
     }
 
@@ -128,31 +156,38 @@ impl MetadataExt {
                 ctime_nsec: i64::from(stat.st_ctimensec),
                 blksize: u64::try_from(stat.st_blksize).unwrap(),
                 blocks: u64::try_from(stat.st_blocks).unwrap(),
+                #[cfg(target_os = "linux")]
+                statx: StatxExt::basic_stats(),
             },
         }
     }
 
-    /// Constructs a new instance of `Metadata` from the given `LibcStatx`.
-    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    /// Constructs a new instance of `Metadata` from the given `libc::statx`.
+    ///
+    /// Times which aren't in `stx_mask` are left as `None`.
+    #[cfg(target_os = "linux")]
     #[inline]
-    #[allow(dead_code)] // TODO: use `statx` when possible.
-    pub(crate) fn from_libc_statx(statx: LibcStatx) -> Metadata {
+    pub(crate) fn from_libc_statx(statx: libc::statx) -> Metadata {
+        let has = |field| statx.stx_mask & field == field;
         Metadata {
             file_type: FileTypeExt::from_libc(libc::mode_t::from(statx.stx_mode)),
             len: u64::try_from(statx.stx_size).unwrap(),
             permissions: PermissionsExt::from_libc(libc::mode_t::from(statx.stx_mode)),
-            modified: system_time_from_libc(
-                statx.stx_mtime.tv_sec,
-                i64::from(statx.stx_mtime.tv_nsec),
-            ),
-            accessed: system_time_from_libc(
-                statx.stx_atime.tv_sec,
-                i64::from(statx.stx_atime.tv_nsec),
-            ),
-            created: system_time_from_libc(
-                statx.stx_btime.tv_sec,
-                i64::from(statx.stx_btime.tv_nsec),
-            ),
+            modified: if has(libc::STATX_MTIME) {
+                system_time_from_libc(statx.stx_mtime.tv_sec, i64::from(statx.stx_mtime.tv_nsec))
+            } else {
+                None
+            },
+            accessed: if has(libc::STATX_ATIME) {
+                system_time_from_libc(statx.stx_atime.tv_sec, i64::from(statx.stx_atime.tv_nsec))
+            } else {
+                None
+            },
+            created: if has(libc::STATX_BTIME) {
+                system_time_from_libc(statx.stx_btime.tv_sec, i64::from(statx.stx_btime.tv_nsec))
+            } else {
+                None
+            },
 
             ext: Self {
                 dev: makedev(statx.stx_dev_major, statx.stx_dev_minor),
@@ -171,6 +206,16 @@ impl MetadataExt {
                 ctime_nsec: i64::from(statx.stx_ctime.tv_nsec),
                 blksize: u64::from(statx.stx_blksize),
                 blocks: statx.stx_blocks,
+                statx: StatxExt {
+                    mask: statx.stx_mask,
+                    attributes: statx.stx_attributes,
+                    attributes_mask: statx.stx_attributes_mask,
+                    mnt_id: if has(libc::STATX_MNT_ID) {
+                        Some(statx.stx_mnt_id)
+                    } else {
+                        None
+                    },
+                },
             },
         }
     }
@@ -181,6 +226,30 @@ impl MetadataExt {
     }
 }
 
+#[cfg(target_os = "linux")]
+#[doc(hidden)]
+impl crate::fs::_LinuxStatx for Metadata {
+    #[inline]
+    unsafe fn statx_mask(&self) -> u32 {
+        self.ext.statx.mask
+    }
+
+    #[inline]
+    unsafe fn attributes(&self) -> u64 {
+        self.ext.statx.attributes
+    }
+
+    #[inline]
+    unsafe fn attributes_mask(&self) -> u64 {
+        self.ext.statx.attributes_mask
+    }
+
+    #[inline]
+    unsafe fn mnt_id(&self) -> Option<u64> {
+        self.ext.statx.mnt_id
+    }
+}
+
 #[allow(clippy::similar_names)]
 fn system_time_from_libc(sec: i64, nsec: i64) -> Option<SystemTime> {
     SystemClock::UNIX_EPOCH.checked_add(Duration::new(
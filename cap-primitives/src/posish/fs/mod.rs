@@ -1,15 +1,19 @@
 mod c_str;
 mod copy;
 mod create_dir_unchecked;
+#[cfg(unix)]
+mod create_special_unchecked;
 mod dir_entry_inner;
 mod dir_options_ext;
 mod dir_utils;
+mod file_io_impl;
 mod file_type_ext;
 mod hard_link_unchecked;
 mod is_read_write_impl;
 mod is_root_dir;
 #[cfg(racy_asserts)]
 mod is_same_file;
+mod lock_impl;
 mod metadata_ext;
 mod oflags;
 mod open_options_ext;
@@ -22,6 +26,8 @@ mod remove_dir_unchecked;
 mod remove_file_unchecked;
 mod remove_open_dir_by_searching;
 mod rename_unchecked;
+#[cfg(unix)]
+mod set_owner_impl;
 #[cfg(not(target_os = "linux"))]
 mod set_permissions_impl;
 #[cfg(not(target_os = "linux"))]
@@ -76,15 +82,19 @@ pub(crate) use crate::fs::{
 pub(crate) use c_str::c_str;
 pub(crate) use copy::*;
 pub(crate) use create_dir_unchecked::*;
+#[cfg(unix)]
+pub(crate) use create_special_unchecked::*;
 pub(crate) use dir_entry_inner::*;
 pub(crate) use dir_options_ext::*;
 pub(crate) use dir_utils::*;
+pub(crate) use file_io_impl::*;
 pub(crate) use file_type_ext::*;
 pub(crate) use hard_link_unchecked::*;
 pub(crate) use is_read_write_impl::*;
 pub(crate) use is_root_dir::*;
 #[cfg(racy_asserts)]
 pub(crate) use is_same_file::*;
+pub(crate) use lock_impl::*;
 pub(crate) use metadata_ext::*;
 pub(crate) use open_options_ext::*;
 pub(crate) use open_unchecked::*;
@@ -96,6 +106,8 @@ pub(crate) use remove_dir_unchecked::*;
 pub(crate) use remove_file_unchecked::*;
 pub(crate) use remove_open_dir_by_searching::*;
 pub(crate) use rename_unchecked::*;
+#[cfg(unix)]
+pub(crate) use set_owner_impl::*;
 pub(crate) use stat_unchecked::*;
 pub(crate) use symlink_unchecked::*;
 #[allow(unused_imports)]
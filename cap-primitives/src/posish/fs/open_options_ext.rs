@@ -11,6 +11,11 @@ impl OpenOptionsExt {
             custom_flags: 0,
         }
     }
+
+    /// Do the custom flags ask to create or truncate the file?
+    pub(crate) fn is_modifying(&self) -> bool {
+        self.custom_flags & (libc::O_CREAT | libc::O_TRUNC) != 0
+    }
 }
 
 impl std::os::unix::fs::OpenOptionsExt for OpenOptionsExt {
@@ -0,0 +1,128 @@
+//! This module consists of helper functions for dealing with setting the
+//! owner and group of files.
+
+use super::c_str;
+use crate::fs::{open, via_parent, FollowSymlinks, OpenOptions};
+#[cfg(target_os = "linux")]
+use std::os::unix::fs::OpenOptionsExt;
+use std::{fs, io, os::unix::io::AsRawFd, path::Path};
+
+pub(crate) fn set_owner_impl(
+    start: &fs::File,
+    path: &Path,
+    uid: Option<u32>,
+    gid: Option<u32>,
+    follow: FollowSymlinks,
+) -> io::Result<()> {
+    match follow {
+        FollowSymlinks::Yes => set_owner_follow(start, path, uid, gid),
+        FollowSymlinks::No => via_parent::set_owner_nofollow(start, path, uid, gid),
+    }
+}
+
+/// On Linux, open the path with `O_PATH`, which doesn't need any access to
+/// the file, and use `AT_EMPTY_PATH` to operate on the handle.
+#[cfg(target_os = "linux")]
+fn set_owner_follow(
+    start: &fs::File,
+    path: &Path,
+    uid: Option<u32>,
+    gid: Option<u32>,
+) -> io::Result<()> {
+    let file = open(
+        start,
+        path,
+        OpenOptions::new().read(true).custom_flags(libc::O_PATH),
+    )?;
+    set_file_owner_impl(&file, uid, gid)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_owner_follow(
+    start: &fs::File,
+    path: &Path,
+    uid: Option<u32>,
+    gid: Option<u32>,
+) -> io::Result<()> {
+    // Try `fchown` with a normal handle. Normal handles need some kind of
+    // access, so first try read.
+    match open(start, path, OpenOptions::new().read(true)) {
+        Ok(file) => return set_file_owner_impl(&file, uid, gid),
+        Err(err) => match err.raw_os_error() {
+            Some(libc::EACCES) => (),
+            _ => return Err(err),
+        },
+    }
+
+    // Next try write.
+    match open(start, path, OpenOptions::new().write(true)) {
+        Ok(file) => return set_file_owner_impl(&file, uid, gid),
+        Err(err) => match err.raw_os_error() {
+            Some(libc::EACCES) | Some(libc::EISDIR) => (),
+            _ => return Err(err),
+        },
+    }
+
+    // As with `set_times`, plain `fchownat` would either follow symlinks
+    // without being able to detect escapes, or change the symlink itself.
+    Err(io::Error::from_raw_os_error(libc::ENOTSUP))
+}
+
+/// Like `file.set_owner(uid, gid)`. On Linux, this uses `AT_EMPTY_PATH`
+/// so that it works on `O_PATH` handles.
+pub(crate) fn set_file_owner_impl(
+    file: &fs::File,
+    uid: Option<u32>,
+    gid: Option<u32>,
+) -> io::Result<()> {
+    #[cfg(target_os = "linux")]
+    let result = unsafe {
+        libc::fchownat(
+            file.as_raw_fd(),
+            b"\0".as_ptr().cast(),
+            to_uid(uid),
+            to_gid(gid),
+            libc::AT_EMPTY_PATH,
+        )
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = unsafe { libc::fchown(file.as_raw_fd(), to_uid(uid), to_gid(gid)) };
+
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+pub(crate) fn set_owner_nofollow_unchecked(
+    start: &fs::File,
+    path: &Path,
+    uid: Option<u32>,
+    gid: Option<u32>,
+) -> io::Result<()> {
+    let path = c_str(path)?;
+    let result = unsafe {
+        libc::fchownat(
+            start.as_raw_fd(),
+            path.as_ptr(),
+            to_uid(uid),
+            to_gid(gid),
+            libc::AT_SYMLINK_NOFOLLOW,
+        )
+    };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// `fchownat` interprets an all-ones id as "don't change".
+fn to_uid(uid: Option<u32>) -> libc::uid_t {
+    uid.map_or(!0, |uid| uid as libc::uid_t)
+}
+
+fn to_gid(gid: Option<u32>) -> libc::gid_t {
+    gid.map_or(!0, |gid| gid as libc::gid_t)
+}
@@ -9,7 +9,12 @@ mod procfs;
 mod set_permissions_impl;
 mod set_times_impl;
 mod stat_impl;
+mod statx_impl;
+mod tmpfile_impl;
+mod watch_impl;
+mod xattr_impl;
 
+pub(crate) use crate::fs::via_parent::link_file as link_file_at_impl;
 pub(crate) use crate::fs::via_parent::set_times_nofollow as set_times_nofollow_impl;
 pub(crate) use canonicalize_impl::*;
 pub(crate) use ensure_cloexec::*;
@@ -20,6 +25,10 @@ pub(crate) use open_impl::*;
 pub(crate) use set_permissions_impl::*;
 pub(crate) use set_times_impl::*;
 pub(crate) use stat_impl::*;
+pub(crate) use statx_impl::*;
+pub(crate) use tmpfile_impl::*;
+pub(crate) use watch_impl::*;
+pub(crate) use xattr_impl::*;
 
 use file_metadata::file_metadata;
 
@@ -18,6 +18,12 @@ use std::{
     sync::atomic::{AtomicBool, Ordering::Relaxed},
 };
 
+/// `RESOLVE_NO_XDEV` from <linux/openat2.h>.
+const RESOLVE_NO_XDEV: u64 = 0x01;
+
+/// `RESOLVE_NO_SYMLINKS` from <linux/openat2.h>.
+const RESOLVE_NO_SYMLINKS: u64 = 0x04;
+
 /// Call the `openat2` system call, or use a fallback if that's unavailable.
 pub(crate) fn open_impl(
     start: &fs::File,
@@ -60,18 +66,22 @@ pub(crate) fn open_beneath(
         // each iteration of the loop below, allocate the `CString` now.
         let path_c_str = c_str(path)?;
 
+        let mut resolve = ResolveFlags::BENEATH | ResolveFlags::NO_MAGICLINKS;
+        if options.no_xdev {
+            // posish doesn't have a constant for `RESOLVE_NO_XDEV` yet.
+            resolve |= unsafe { ResolveFlags::from_bits_unchecked(RESOLVE_NO_XDEV) };
+        }
+        if options.no_symlinks {
+            // Likewise for `RESOLVE_NO_SYMLINKS`.
+            resolve |= unsafe { ResolveFlags::from_bits_unchecked(RESOLVE_NO_SYMLINKS) };
+        }
+
         // `openat2` fails with `EAGAIN` if a rename happens anywhere on the host
         // while it's running, so use a loop to retry it a few times. But not too many
         // times, because there's no limit on how often this can happen. The actual
         // number here is currently an arbitrarily chosen guess.
         for _ in 0..4 {
-            match openat2(
-                start,
-                path_c_str.as_c_str(),
-                oflags,
-                mode,
-                ResolveFlags::BENEATH | ResolveFlags::NO_MAGICLINKS,
-            ) {
+            match openat2(start, path_c_str.as_c_str(), oflags, mode, resolve) {
                 Ok(file) => {
                     // Note that we don't bother with `ensure_cloexec` here
                     // because Linux has supported `O_CLOEXEC` since 2.6.18,
@@ -84,7 +94,20 @@ pub(crate) fn open_beneath(
                 }
                 Err(err) => match err.raw_os_error() {
                     Some(libc::EAGAIN) => continue,
+                    // With `RESOLVE_NO_XDEV`, `EXDEV` may mean either an escape
+                    // attempt or a mount point crossing; let `manually::open`
+                    // work out which.
+                    Some(libc::EXDEV) if options.no_xdev => {
+                        return manually::open(start, path, options)
+                    }
                     Some(libc::EXDEV) => return Err(errors::escape_attempt()),
+                    // With `RESOLVE_NO_SYMLINKS`, `ELOOP` may mean either a
+                    // symlink was traversed, or that the last component is a
+                    // symlink and we were asked not to follow it; let
+                    // `manually::open` work out which.
+                    Some(libc::ELOOP) if options.no_symlinks => {
+                        return manually::open(start, path, options)
+                    }
                     Some(libc::ENOSYS) => {
                         // `openat2` is permanently unavailable; mark it so and
                         // exit the loop.
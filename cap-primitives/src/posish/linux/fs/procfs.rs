@@ -178,7 +178,7 @@ fn is_mountpoint(file: &fs::File) -> io::Result<bool> {
     }
 }
 
-fn proc_self_fd() -> io::Result<&'static fs::File> {
+pub(super) fn proc_self_fd() -> io::Result<&'static fs::File> {
     #[allow(clippy::useless_conversion)]
     static PROC_SELF_FD: Lazy<io::Result<fs::File>> = Lazy::new(|| {
         // When libc does have this constant, check that our copy has the same value.
@@ -29,7 +29,8 @@ fn set_times_follow(
     // access, so first try write.
     match open(start, path, OpenOptions::new().write(true)) {
         Ok(file) => {
-            return file.set_times(
+            return SetTimes::set_times(
+                &file,
                 atime.map(SystemTimeSpec::into_std),
                 mtime.map(SystemTimeSpec::into_std),
             )
@@ -43,7 +44,8 @@ fn set_times_follow(
     // Next try read.
     match open(start, path, OpenOptions::new().read(true)) {
         Ok(file) => {
-            return file.set_times(
+            return SetTimes::set_times(
+                &file,
                 atime.map(SystemTimeSpec::into_std),
                 mtime.map(SystemTimeSpec::into_std),
             )
@@ -0,0 +1,86 @@
+use crate::fs::{open, FollowSymlinks, Metadata, MetadataExt, OpenOptions, StatxFields, StatxSync};
+use std::{
+    fs, io,
+    mem::MaybeUninit,
+    os::unix::{fs::OpenOptionsExt, io::AsRawFd},
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering::Relaxed},
+};
+
+pub(crate) fn statx_impl(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+    fields: StatxFields,
+    sync: StatxSync,
+) -> io::Result<Metadata> {
+    // Open the path with `O_PATH`, using the same sandboxed resolution as
+    // `open`, and query the resulting file descriptor. Use `read(true)` even
+    // though we don't need `read` permissions, because Rust's libstd requires
+    // an access mode, and Linux ignores `O_RDONLY` with `O_PATH`.
+    let opath = open(
+        start,
+        path,
+        OpenOptions::new()
+            .read(true)
+            .follow(follow)
+            .custom_flags(libc::O_PATH),
+    )?;
+    fstatx_impl(&opath, fields, sync)
+}
+
+pub(crate) fn fstatx_impl(
+    file: &fs::File,
+    fields: StatxFields,
+    sync: StatxSync,
+) -> io::Result<Metadata> {
+    let sync = match sync {
+        StatxSync::AsStat => libc::AT_STATX_SYNC_AS_STAT,
+        StatxSync::Force => libc::AT_STATX_FORCE_SYNC,
+        StatxSync::DontSync => libc::AT_STATX_DONT_SYNC,
+    };
+
+    // Call the syscall directly rather than the libc wrapper, which older
+    // versions of glibc and other libcs don't have.
+    let mut statx = MaybeUninit::<libc::statx>::uninit();
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_statx,
+            file.as_raw_fd(),
+            b"\0".as_ptr(),
+            libc::AT_EMPTY_PATH | sync,
+            fields.bits(),
+            statx.as_mut_ptr(),
+        )
+    };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(MetadataExt::from_libc_statx(unsafe { statx.assume_init() }))
+}
+
+/// Like `Metadata::from_file`, but uses `statx` so that the creation time and
+/// other `statx`-only fields are filled in. Returns `None` if `statx` isn't
+/// available.
+pub(crate) fn file_metadata_statx(file: &fs::File) -> io::Result<Option<Metadata>> {
+    // Record whether we've seen `statx` fail in a way that means it's not
+    // available, so that we don't keep trying it.
+    static STATX_UNAVAILABLE: AtomicBool = AtomicBool::new(false);
+
+    if STATX_UNAVAILABLE.load(Relaxed) {
+        return Ok(None);
+    }
+
+    match fstatx_impl(file, StatxFields::ALL, StatxSync::AsStat) {
+        Ok(metadata) => Ok(Some(metadata)),
+        Err(err) => match err.raw_os_error() {
+            // `ENOSYS` means the kernel doesn't have `statx`, and `EPERM`
+            // means a seccomp filter doesn't allow it.
+            Some(libc::ENOSYS) | Some(libc::EPERM) => {
+                STATX_UNAVAILABLE.store(true, Relaxed);
+                Ok(None)
+            }
+            _ => Err(err),
+        },
+    }
+}
@@ -0,0 +1,57 @@
+use super::procfs::proc_self_fd;
+use crate::fs::errors;
+use posish::{
+    fs::{linkat, openat, AtFlags, Mode, OFlags},
+    path::DecInt,
+};
+use std::{ffi::OsStr, fs, io, os::unix::fs::MetadataExt};
+
+/// Open an unnamed temporary file in `dir` with `O_TMPFILE`.
+///
+/// Returns `None` if the kernel or filesystem doesn't support `O_TMPFILE`,
+/// or if "/proc/self/fd", which `link_tmpfile_impl` needs to give the file a
+/// name, isn't usable.
+pub(crate) fn open_tmpfile_impl(dir: &fs::File) -> io::Result<Option<fs::File>> {
+    if proc_self_fd().is_err() {
+        return Ok(None);
+    }
+
+    match openat(
+        dir,
+        ".",
+        OFlags::TMPFILE | OFlags::RDWR | OFlags::CLOEXEC,
+        Mode::from_bits(0o666).unwrap(),
+    ) {
+        Ok(file) => Ok(Some(file)),
+        Err(e) => match e.raw_os_error() {
+            // Older kernels report an unknown `O_TMPFILE` as `EISDIR` or
+            // `ENOENT`, and filesystems without support as `EOPNOTSUPP`.
+            Some(libc::EOPNOTSUPP)
+            | Some(libc::EISDIR)
+            | Some(libc::ENOENT)
+            | Some(libc::EINVAL) => Ok(None),
+            _ => Err(e),
+        },
+    }
+}
+
+/// Give a file opened by `open_tmpfile_impl` the name `name` in `dir`.
+///
+/// `linkat` with `AT_EMPTY_PATH` requires `CAP_DAC_READ_SEARCH`, so link
+/// the file's "/proc/self/fd" entry instead.
+///
+/// That would give any file another name, so files which already have a
+/// name are rejected.
+pub(crate) fn link_tmpfile_impl(file: &fs::File, dir: &fs::File, name: &OsStr) -> io::Result<()> {
+    if file.metadata()?.nlink() != 0 {
+        return Err(errors::not_anonymous());
+    }
+
+    linkat(
+        proc_self_fd()?,
+        DecInt::from_fd(file),
+        dir,
+        name,
+        AtFlags::SYMLINK_FOLLOW,
+    )
+}
@@ -0,0 +1,204 @@
+use super::procfs::proc_self_fd;
+use crate::fs::{open, DirEvent, DirEventKind, FollowSymlinks, OpenOptions};
+use std::{
+    collections::HashMap,
+    ffi::{CString, OsStr},
+    fmt, fs,
+    io::{self, Read},
+    mem::size_of,
+    os::unix::{
+        ffi::OsStrExt,
+        fs::OpenOptionsExt,
+        io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, RawFd},
+    },
+    path::{Component, Path, PathBuf},
+    ptr,
+};
+
+/// The changes we ask inotify to report.
+const WATCH_MASK: u32 = libc::IN_CREATE
+    | libc::IN_MODIFY
+    | libc::IN_DELETE
+    | libc::IN_DELETE_SELF
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO
+    | libc::IN_ATTRIB;
+
+/// Big enough to hold at least one event with a maximum-length name.
+const BUFFER_SIZE: usize = 4096;
+
+pub(crate) struct WatcherImpl {
+    inotify: fs::File,
+
+    /// The paths the watches were added with, relative to the directories
+    /// they were added through, indexed by watch descriptor.
+    watches: HashMap<libc::c_int, PathBuf>,
+}
+
+impl WatcherImpl {
+    pub(crate) fn new(nonblocking: bool) -> io::Result<Self> {
+        let mut flags = libc::IN_CLOEXEC;
+        if nonblocking {
+            flags |= libc::IN_NONBLOCK;
+        }
+        let fd = cvt(unsafe { libc::inotify_init1(flags) })?;
+        Ok(Self {
+            inotify: unsafe { fs::File::from_raw_fd(fd) },
+            watches: HashMap::new(),
+        })
+    }
+
+    pub(crate) fn add(&mut self, start: &fs::File, path: &Path) -> io::Result<()> {
+        // Use `read(true)` even though we don't need `read` permissions,
+        // because Rust's libstd requires an access mode, and Linux ignores
+        // `O_RDONLY` with `O_PATH`.
+        let opath = open(
+            start,
+            path,
+            OpenOptions::new()
+                .read(true)
+                .follow(FollowSymlinks::Yes)
+                .custom_flags(libc::O_PATH),
+        )?;
+
+        // There's no `*at` form of `inotify_add_watch`, so use the absolute
+        // path of the file descriptor in "/proc/self/fd", once we've checked
+        // that it's really procfs. The "/proc/self/fd" entry is a magic link
+        // which resolves to exactly the file we opened, so nothing can
+        // redirect the watch to somewhere else.
+        proc_self_fd()?;
+        let proc_path = CString::new(format!("/proc/self/fd/{}", opath.as_raw_fd())).unwrap();
+        let wd = cvt(unsafe {
+            libc::inotify_add_watch(self.inotify.as_raw_fd(), proc_path.as_ptr(), WATCH_MASK)
+        })?;
+
+        // Paths in events are relative to the directory the watch was added
+        // through, so drop any leading `.`.
+        let path = path
+            .components()
+            .filter(|component| *component != Component::CurDir)
+            .collect();
+        self.watches.insert(wd, path);
+        Ok(())
+    }
+
+    pub(crate) fn read_events(&mut self) -> io::Result<Vec<DirEvent>> {
+        let mut buf = [0_u8; BUFFER_SIZE];
+        let len = self.inotify.read(&mut buf)?;
+
+        let mut events: Vec<DirEvent> = Vec::new();
+
+        // The `IN_MOVED_FROM` half of a rename which hasn't been matched
+        // with an `IN_MOVED_TO` yet, with its cookie and index in `events`.
+        let mut moved_from: Vec<(u32, usize)> = Vec::new();
+
+        let mut offset = 0;
+        while offset + size_of::<libc::inotify_event>() <= len {
+            let raw = unsafe {
+                ptr::read_unaligned(buf[offset..].as_ptr().cast::<libc::inotify_event>())
+            };
+            let name_start = offset + size_of::<libc::inotify_event>();
+            let name_end = name_start + raw.len as usize;
+            offset = name_end;
+
+            if raw.mask & libc::IN_Q_OVERFLOW != 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "inotify event queue overflowed",
+                ));
+            }
+
+            // The watched object is gone, so the kernel has removed the
+            // watch.
+            if raw.mask & libc::IN_IGNORED != 0 {
+                self.watches.remove(&raw.wd);
+                continue;
+            }
+
+            let base = match self.watches.get(&raw.wd) {
+                Some(base) => base,
+                None => continue,
+            };
+
+            // The name is padded with NULs.
+            let name = &buf[name_start..name_end];
+            let name = &name[..name.iter().position(|b| *b == 0).unwrap_or(name.len())];
+            let path = if name.is_empty() {
+                base.clone()
+            } else {
+                base.join(OsStr::from_bytes(name))
+            };
+            let is_dir = raw.mask & libc::IN_ISDIR != 0;
+
+            let kind = if raw.mask & libc::IN_MOVED_TO != 0 {
+                match moved_from.iter().position(|(cookie, _)| *cookie == raw.cookie) {
+                    Some(i) => {
+                        // Replace the `Removed` event we recorded for the
+                        // `IN_MOVED_FROM` with a `Renamed` event.
+                        let (_, index) = moved_from.remove(i);
+                        let from = events.remove(index);
+                        for (_, later) in &mut moved_from {
+                            if *later > index {
+                                *later -= 1;
+                            }
+                        }
+                        DirEventKind::Renamed {
+                            from: from.path().to_path_buf(),
+                        }
+                    }
+                    None => DirEventKind::Created,
+                }
+            } else if raw.mask & libc::IN_MOVED_FROM != 0 {
+                // Until we see a matching `IN_MOVED_TO`, this looks like the
+                // object was moved out of the watched directories.
+                moved_from.push((raw.cookie, events.len()));
+                DirEventKind::Removed
+            } else if raw.mask & libc::IN_CREATE != 0 {
+                DirEventKind::Created
+            } else if raw.mask & (libc::IN_DELETE | libc::IN_DELETE_SELF) != 0 {
+                DirEventKind::Removed
+            } else if raw.mask & libc::IN_MODIFY != 0 {
+                DirEventKind::Modified
+            } else if raw.mask & libc::IN_ATTRIB != 0 {
+                DirEventKind::AttributesChanged
+            } else {
+                continue;
+            };
+
+            events.push(DirEvent::new(kind, path, is_dir));
+        }
+
+        Ok(events)
+    }
+}
+
+impl AsRawFd for WatcherImpl {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inotify.as_raw_fd()
+    }
+}
+
+impl AsFd for WatcherImpl {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inotify.as_fd()
+    }
+}
+
+impl fmt::Debug for WatcherImpl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Watcher")
+            .field("inotify", &self.inotify)
+            .field("watches", &self.watches)
+            .finish()
+    }
+}
+
+fn cvt(result: libc::c_int) -> io::Result<libc::c_int> {
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result)
+    }
+}
@@ -0,0 +1,196 @@
+use super::procfs::proc_self_fd;
+use crate::fs::{open, FollowSymlinks, OpenOptions};
+use std::{
+    ffi::{CString, OsStr, OsString},
+    fs, io,
+    os::unix::{
+        ffi::{OsStrExt, OsStringExt},
+        fs::OpenOptionsExt,
+        io::AsRawFd,
+    },
+    path::Path,
+    ptr,
+    sync::atomic::{AtomicBool, Ordering::Relaxed},
+};
+
+/// A file to operate on, either as an open file, or as a path in
+/// "/proc/self/fd" which refers to an open file.
+enum Target<'a> {
+    File(&'a fs::File),
+    ProcPath(CString),
+}
+
+pub(crate) fn getxattr_impl(
+    start: &fs::File,
+    path: &Path,
+    name: &OsStr,
+    follow: FollowSymlinks,
+) -> io::Result<Vec<u8>> {
+    let name = c_name(name)?;
+    with_target(start, path, follow, |target| get(target, &name))
+}
+
+pub(crate) fn setxattr_impl(
+    start: &fs::File,
+    path: &Path,
+    name: &OsStr,
+    value: &[u8],
+    follow: FollowSymlinks,
+) -> io::Result<()> {
+    let name = c_name(name)?;
+    with_target(start, path, follow, |target| set(target, &name, value))
+}
+
+pub(crate) fn listxattr_impl(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+) -> io::Result<Vec<OsString>> {
+    with_target(start, path, follow, list)
+}
+
+pub(crate) fn removexattr_impl(
+    start: &fs::File,
+    path: &Path,
+    name: &OsStr,
+    follow: FollowSymlinks,
+) -> io::Result<()> {
+    let name = c_name(name)?;
+    with_target(start, path, follow, |target| remove(target, &name))
+}
+
+pub(crate) fn fgetxattr_impl(file: &fs::File, name: &OsStr) -> io::Result<Vec<u8>> {
+    get(&Target::File(file), &c_name(name)?)
+}
+
+pub(crate) fn fsetxattr_impl(file: &fs::File, name: &OsStr, value: &[u8]) -> io::Result<()> {
+    set(&Target::File(file), &c_name(name)?, value)
+}
+
+pub(crate) fn flistxattr_impl(file: &fs::File) -> io::Result<Vec<OsString>> {
+    list(&Target::File(file))
+}
+
+pub(crate) fn fremovexattr_impl(file: &fs::File, name: &OsStr) -> io::Result<()> {
+    remove(&Target::File(file), &c_name(name)?)
+}
+
+/// Open `path` with `O_PATH`, using the same sandboxed resolution as `open`,
+/// and call `f` on the result.
+fn with_target<T>(
+    start: &fs::File,
+    path: &Path,
+    follow: FollowSymlinks,
+    f: impl Fn(&Target) -> io::Result<T>,
+) -> io::Result<T> {
+    // Record whether we've seen an `EBADF` from an xattr call on an `O_PATH`
+    // file descriptor, meaning we're on a Linux that doesn't support it.
+    static FXATTR_PATH_BADF: AtomicBool = AtomicBool::new(false);
+
+    // Use `read(true)` even though we don't need `read` permissions, because
+    // Rust's libstd requires an access mode, and Linux ignores `O_RDONLY`
+    // with `O_PATH`.
+    let opath = open(
+        start,
+        path,
+        OpenOptions::new()
+            .read(true)
+            .follow(follow)
+            .custom_flags(libc::O_PATH),
+    )?;
+
+    if !FXATTR_PATH_BADF.load(Relaxed) {
+        match f(&Target::File(&opath)) {
+            Err(err) if err.raw_os_error() == Some(libc::EBADF) => {
+                FXATTR_PATH_BADF.store(true, Relaxed)
+            }
+            result => return result,
+        }
+    }
+
+    // There are no `*at` forms of the xattr functions, so use the absolute
+    // path of the file descriptor in "/proc/self/fd", once we've checked that
+    // it's really procfs. The "/proc/self/fd" entry is a magic link which
+    // resolves to exactly the file we opened, even if it's a symlink.
+    proc_self_fd()?;
+    let proc_path = CString::new(format!("/proc/self/fd/{}", opath.as_raw_fd())).unwrap();
+    f(&Target::ProcPath(proc_path))
+}
+
+fn c_name(name: &OsStr) -> io::Result<CString> {
+    CString::new(name.as_bytes()).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "extended attribute names may not contain NUL",
+        )
+    })
+}
+
+fn cvt(result: libc::ssize_t) -> io::Result<usize> {
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result as usize)
+    }
+}
+
+/// Call `f` with a buffer big enough for its result, retrying if the result
+/// grows between calls.
+fn with_buffer(f: impl Fn(*mut libc::c_void, usize) -> libc::ssize_t) -> io::Result<Vec<u8>> {
+    loop {
+        let size = cvt(f(ptr::null_mut(), 0))?;
+        let mut buf = vec![0_u8; size];
+        match cvt(f(buf.as_mut_ptr().cast(), buf.len())) {
+            Ok(len) => {
+                buf.truncate(len);
+                return Ok(buf);
+            }
+            Err(err) if err.raw_os_error() == Some(libc::ERANGE) => continue,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+fn get(target: &Target, name: &CString) -> io::Result<Vec<u8>> {
+    with_buffer(|buf, len| unsafe {
+        match target {
+            Target::File(file) => libc::fgetxattr(file.as_raw_fd(), name.as_ptr(), buf, len),
+            Target::ProcPath(path) => libc::getxattr(path.as_ptr(), name.as_ptr(), buf, len),
+        }
+    })
+}
+
+fn set(target: &Target, name: &CString, value: &[u8]) -> io::Result<()> {
+    let (value, len) = (value.as_ptr().cast(), value.len());
+    let result = unsafe {
+        match target {
+            Target::File(file) => libc::fsetxattr(file.as_raw_fd(), name.as_ptr(), value, len, 0),
+            Target::ProcPath(path) => libc::setxattr(path.as_ptr(), name.as_ptr(), value, len, 0),
+        }
+    };
+    cvt(result as libc::ssize_t).map(drop)
+}
+
+fn list(target: &Target) -> io::Result<Vec<OsString>> {
+    let names = with_buffer(|buf, len| unsafe {
+        match target {
+            Target::File(file) => libc::flistxattr(file.as_raw_fd(), buf.cast(), len),
+            Target::ProcPath(path) => libc::listxattr(path.as_ptr(), buf.cast(), len),
+        }
+    })?;
+    Ok(names
+        .split(|b| *b == 0)
+        .filter(|name| !name.is_empty())
+        .map(|name| OsString::from_vec(name.to_vec()))
+        .collect())
+}
+
+fn remove(target: &Target, name: &CString) -> io::Result<()> {
+    let result = unsafe {
+        match target {
+            Target::File(file) => libc::fremovexattr(file.as_raw_fd(), name.as_ptr()),
+            Target::ProcPath(path) => libc::removexattr(path.as_ptr(), name.as_ptr()),
+        }
+    };
+    cvt(result as libc::ssize_t).map(drop)
+}
@@ -2,6 +2,7 @@
 //! supported by the `posish` crate.
 
 pub(crate) mod fs;
+pub(crate) mod net;
 
 #[cfg(any(target_os = "macos", target_os = "ios"))]
 mod darwin;
@@ -0,0 +1,44 @@
+use socket2::Socket;
+use std::{convert::TryInto, io, os::unix::io::AsRawFd, time::Duration};
+
+/// Test whether `err` is the error `connect` fails with when a non-blocking
+/// connection has been started but hasn't completed yet.
+pub(crate) fn connect_in_progress(err: &io::Error) -> bool {
+    err.raw_os_error() == Some(libc::EINPROGRESS)
+}
+
+/// Wait up to `timeout` for any of the non-blocking connection attempts in
+/// `sockets` to complete, successfully or not, and return which ones have.
+pub(crate) fn poll_connect_impl(sockets: &[Socket], timeout: Duration) -> io::Result<Vec<bool>> {
+    let mut fds = sockets
+        .iter()
+        .map(|socket| libc::pollfd {
+            fd: socket.as_raw_fd(),
+            events: libc::POLLOUT,
+            revents: 0,
+        })
+        .collect::<Vec<_>>();
+    let nfds = fds
+        .len()
+        .try_into()
+        .map_err(|_| io::Error::from_raw_os_error(libc::EINVAL))?;
+
+    if unsafe { libc::poll(fds.as_mut_ptr(), nfds, timeout_millis(timeout)) } == -1 {
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+
+    Ok(fds.iter().map(|fd| fd.revents != 0).collect())
+}
+
+/// Convert `timeout` to milliseconds, rounding up so that we don't wake up
+/// before it has elapsed.
+fn timeout_millis(timeout: Duration) -> libc::c_int {
+    let mut millis = timeout.as_millis();
+    if timeout.subsec_nanos() > timeout.subsec_millis() * 1_000_000 {
+        millis += 1;
+    }
+    millis.try_into().unwrap_or(libc::c_int::MAX)
+}
@@ -0,0 +1,27 @@
+#[cfg(not(target_os = "wasi"))]
+mod connect_impl;
+
+#[cfg(not(target_os = "wasi"))]
+pub(crate) use connect_impl::*;
+
+#[cfg(target_os = "wasi")]
+pub(crate) fn bind_tcp_listener_impl(
+    _addr: &std::net::SocketAddr,
+    _options: &crate::net::TcpListenerOptions,
+) -> std::io::Result<std::net::TcpListener> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Other,
+        "binding sockets isn't supported on WASI",
+    ))
+}
+
+#[cfg(target_os = "wasi")]
+pub(crate) fn bind_udp_socket_impl(
+    _addr: &std::net::SocketAddr,
+    _options: &crate::net::UdpSocketOptions,
+) -> std::io::Result<std::net::UdpSocket> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Other,
+        "binding sockets isn't supported on WASI",
+    ))
+}
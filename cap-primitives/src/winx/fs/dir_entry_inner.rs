@@ -54,7 +54,10 @@ impl DirEntryInner {
     pub(crate) fn read_dir(&self) -> io::Result<ReadDir> {
         let std = fs::read_dir(self.std.path())?;
         let inner = ReadDirInner::from_std(std);
-        Ok(ReadDir { inner })
+        Ok(ReadDir {
+            inner,
+            no_xdev: false,
+        })
     }
 
     #[inline]
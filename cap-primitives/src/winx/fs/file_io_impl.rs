@@ -0,0 +1,51 @@
+use std::{
+    fs,
+    io::{self, IoSlice, IoSliceMut, Seek, SeekFrom},
+    os::windows::fs::FileExt,
+};
+
+// `seek_read` and `seek_write` move the current position, so save it and
+// restore it afterward. This isn't atomic with respect to other users of the
+// same handle, which must not depend on the position concurrently.
+
+pub(crate) fn read_at_impl(file: &fs::File, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+    preserving_position(file, |file| file.seek_read(buf, offset))
+}
+
+pub(crate) fn write_at_impl(file: &fs::File, buf: &[u8], offset: u64) -> io::Result<usize> {
+    preserving_position(file, |file| file.seek_write(buf, offset))
+}
+
+pub(crate) fn read_vectored_at_impl(
+    file: &fs::File,
+    bufs: &mut [IoSliceMut],
+    offset: u64,
+) -> io::Result<usize> {
+    let buf = bufs
+        .iter_mut()
+        .find(|buf| !buf.is_empty())
+        .map_or(&mut [][..], |buf| &mut **buf);
+    read_at_impl(file, buf, offset)
+}
+
+pub(crate) fn write_vectored_at_impl(
+    file: &fs::File,
+    bufs: &[IoSlice],
+    offset: u64,
+) -> io::Result<usize> {
+    let buf = bufs
+        .iter()
+        .find(|buf| !buf.is_empty())
+        .map_or(&[][..], |buf| &**buf);
+    write_at_impl(file, buf, offset)
+}
+
+fn preserving_position<T>(
+    mut file: &fs::File,
+    f: impl FnOnce(&fs::File) -> io::Result<T>,
+) -> io::Result<T> {
+    let position = file.seek(SeekFrom::Current(0))?;
+    let result = f(file);
+    file.seek(SeekFrom::Start(position))?;
+    result
+}
@@ -0,0 +1,47 @@
+use crate::fs::FileLockKind;
+use std::{fs, io, mem, os::windows::io::AsRawHandle};
+use winapi::{
+    shared::{minwindef::DWORD, winerror::ERROR_LOCK_VIOLATION},
+    um::{
+        fileapi::{LockFileEx, UnlockFile},
+        minwinbase::{LOCKFILE_EXCLUSIVE_LOCK, LOCKFILE_FAIL_IMMEDIATELY, OVERLAPPED},
+    },
+};
+
+pub(crate) fn lock_impl(file: &fs::File, kind: FileLockKind) -> io::Result<()> {
+    lock_file_ex(file, flags(kind))
+}
+
+pub(crate) fn try_lock_impl(file: &fs::File, kind: FileLockKind) -> io::Result<bool> {
+    match lock_file_ex(file, flags(kind) | LOCKFILE_FAIL_IMMEDIATELY) {
+        Ok(()) => Ok(true),
+        Err(e) if e.raw_os_error() == Some(ERROR_LOCK_VIOLATION as i32) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+pub(crate) fn unlock_impl(file: &fs::File) -> io::Result<()> {
+    // Lock and unlock the whole file, which is the range of all possible
+    // offsets.
+    let ok = unsafe { UnlockFile(file.as_raw_handle(), 0, 0, !0, !0) };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn flags(kind: FileLockKind) -> DWORD {
+    match kind {
+        FileLockKind::Shared => 0,
+        FileLockKind::Exclusive => LOCKFILE_EXCLUSIVE_LOCK,
+    }
+}
+
+fn lock_file_ex(file: &fs::File, flags: DWORD) -> io::Result<()> {
+    let mut overlapped: OVERLAPPED = unsafe { mem::zeroed() };
+    let ok = unsafe { LockFileEx(file.as_raw_handle(), flags, 0, !0, !0, &mut overlapped) };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
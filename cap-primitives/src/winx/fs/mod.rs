@@ -5,12 +5,14 @@ mod create_dir_unchecked;
 mod dir_entry_inner;
 mod dir_options_ext;
 mod dir_utils;
+mod file_io_impl;
 mod file_type_ext;
 mod get_path;
 mod hard_link_unchecked;
 mod is_read_write_impl;
 #[cfg(racy_asserts)]
 mod is_same_file;
+mod lock_impl;
 mod metadata_ext;
 mod oflags;
 mod open_options_ext;
@@ -50,11 +52,13 @@ pub(crate) use create_dir_unchecked::*;
 pub(crate) use dir_entry_inner::*;
 pub(crate) use dir_options_ext::*;
 pub(crate) use dir_utils::*;
+pub(crate) use file_io_impl::*;
 pub(crate) use file_type_ext::*;
 pub(crate) use hard_link_unchecked::*;
 pub(crate) use is_read_write_impl::*;
 #[cfg(racy_asserts)]
 pub(crate) use is_same_file::*;
+pub(crate) use lock_impl::*;
 pub(crate) use manually::open as open_impl;
 pub(crate) use metadata_ext::*;
 pub(crate) use open_options_ext::*;
@@ -19,6 +19,13 @@ impl OpenOptionsExt {
             security_qos_flags: 0,
         }
     }
+
+    /// Does the access mode ask for anything other than reading?
+    pub(crate) fn is_modifying(&self) -> bool {
+        self.access_mode.map_or(false, |access_mode| {
+            access_mode & !(winnt::GENERIC_READ | winnt::FILE_GENERIC_READ) != 0
+        })
+    }
 }
 
 impl std::os::windows::fs::OpenOptionsExt for OpenOptionsExt {
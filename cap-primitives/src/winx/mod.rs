@@ -2,3 +2,4 @@
 //! crate.
 
 pub(crate) mod fs;
+pub(crate) mod net;
@@ -0,0 +1,45 @@
+use socket2::Socket;
+use std::{convert::TryInto, io, os::windows::io::AsRawSocket, time::Duration};
+use winapi::{
+    shared::minwindef::{INT, ULONG},
+    um::winsock2::{WSAGetLastError, WSAPoll, POLLWRNORM, SOCKET, SOCKET_ERROR, WSAPOLLFD},
+};
+
+/// Test whether `err` is the error `connect` fails with when a non-blocking
+/// connection has been started but hasn't completed yet.
+pub(crate) fn connect_in_progress(err: &io::Error) -> bool {
+    err.kind() == io::ErrorKind::WouldBlock
+}
+
+/// Wait up to `timeout` for any of the non-blocking connection attempts in
+/// `sockets` to complete, successfully or not, and return which ones have.
+pub(crate) fn poll_connect_impl(sockets: &[Socket], timeout: Duration) -> io::Result<Vec<bool>> {
+    let mut fds = sockets
+        .iter()
+        .map(|socket| WSAPOLLFD {
+            fd: socket.as_raw_socket() as SOCKET,
+            events: POLLWRNORM,
+            revents: 0,
+        })
+        .collect::<Vec<_>>();
+    let nfds: ULONG = fds
+        .len()
+        .try_into()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "too many sockets"))?;
+
+    if unsafe { WSAPoll(fds.as_mut_ptr(), nfds, timeout_millis(timeout)) } == SOCKET_ERROR {
+        return Err(io::Error::from_raw_os_error(unsafe { WSAGetLastError() }));
+    }
+
+    Ok(fds.iter().map(|fd| fd.revents != 0).collect())
+}
+
+/// Convert `timeout` to milliseconds, rounding up so that we don't wake up
+/// before it has elapsed.
+fn timeout_millis(timeout: Duration) -> INT {
+    let mut millis = timeout.as_millis();
+    if timeout.subsec_nanos() > timeout.subsec_millis() * 1_000_000 {
+        millis += 1;
+    }
+    millis.try_into().unwrap_or(INT::MAX)
+}
@@ -0,0 +1,3 @@
+mod connect_impl;
+
+pub(crate) use connect_impl::*;
@@ -0,0 +1,56 @@
+use std::{
+    fmt,
+    io::{self, IoSlice, Seek, SeekFrom, Write},
+};
+
+/// A file which atomically replaces the file at its path when it's
+/// committed, returned by [`Dir::create_atomic`].
+///
+/// Writes go to a temporary file in the same directory as the target, which
+/// is synced and renamed over the target by [`commit`]. If an `AtomicFile` is
+/// dropped without being committed, the target is left unchanged.
+///
+/// [`Dir::create_atomic`]: struct.Dir.html#method.create_atomic
+/// [`commit`]: #method.commit
+pub struct AtomicFile {
+    pub(crate) inner: cap_primitives::fs::AtomicFile,
+}
+
+impl AtomicFile {
+    /// Syncs the written contents and renames them over the target, and then
+    /// syncs the directory containing the target.
+    #[inline]
+    pub fn commit(self) -> io::Result<()> {
+        self.inner.commit()
+    }
+}
+
+impl Write for AtomicFile {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.inner.write_vectored(bufs)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl Seek for AtomicFile {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl fmt::Debug for AtomicFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
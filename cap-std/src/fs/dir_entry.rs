@@ -1,4 +1,5 @@
 use crate::fs::{Dir, File, FileType, Metadata, OpenOptions};
+use cap_primitives::fs::DirAccess;
 #[cfg(unix)]
 use std::os::unix::fs::DirEntryExt;
 #[cfg(target_os = "wasi")]
@@ -24,6 +25,8 @@ use std::{ffi::OsString, fmt, io};
 /// [`std::fs::DirEntry`]: https://doc.rust-lang.org/std/fs/struct.DirEntry.html
 pub struct DirEntry {
     pub(crate) inner: cap_primitives::fs::DirEntry,
+    pub(crate) access: DirAccess,
+    pub(crate) no_xdev: bool,
 }
 
 impl DirEntry {
@@ -31,32 +34,35 @@ impl DirEntry {
     #[inline]
     pub fn open(&self) -> io::Result<File> {
         let file = self.inner.open()?;
-        Ok(unsafe { File::from_std(file) })
+        Ok(unsafe { File::from_std_with_access(file, self.access) })
     }
 
     /// Open the file with the given options.
     #[inline]
     pub fn open_with(&self, options: &OpenOptions) -> io::Result<File> {
+        self.access.check_open(options)?;
         let file = self.inner.open_with(options)?;
-        Ok(unsafe { File::from_std(file) })
+        Ok(unsafe { File::from_std_with_access(file, self.access) })
     }
 
     /// Open the entry as a directory.
     #[inline]
     pub fn open_dir(&self) -> io::Result<Dir> {
         let dir = self.inner.open_dir()?;
-        Ok(unsafe { Dir::from_std_file(dir) })
+        Ok(unsafe { Dir::from_std_file_with_policy(dir, self.access, self.no_xdev) })
     }
 
     /// Removes the file from its filesystem.
     #[inline]
     pub fn remove_file(&self) -> io::Result<()> {
+        self.access.check_modify()?;
         self.inner.remove_file()
     }
 
     /// Removes the directory from its filesystem.
     #[inline]
     pub fn remove_dir(&self) -> io::Result<()> {
+        self.access.check_modify()?;
         self.inner.remove_dir()
     }
 
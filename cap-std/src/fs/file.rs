@@ -1,7 +1,7 @@
 #[cfg(with_options)]
 use crate::fs::OpenOptions;
-use crate::fs::{Metadata, Permissions};
-use cap_primitives::fs::is_read_write;
+use crate::fs::{DirAccess, Metadata, Permissions};
+use cap_primitives::fs::{is_read_write, lock, try_lock, unlock, FileLockKind};
 #[cfg(read_initializer)]
 use std::io::Initializer;
 #[cfg(unix)]
@@ -32,6 +32,7 @@ use std::{
 /// [`Dir::create`]: struct.Dir.html#method.create
 pub struct File {
     pub(crate) std: fs::File,
+    pub(crate) access: DirAccess,
 }
 
 impl File {
@@ -42,7 +43,11 @@ impl File {
     /// `std::fs::File` is not sandboxed and may access any path that the host
     #[inline]
     pub unsafe fn from_std(std: fs::File) -> Self {
-        Self { std }
+        Self::from_std_with_access(std, DirAccess::ReadWrite)
+    }
+
+    pub(crate) unsafe fn from_std_with_access(std: fs::File, access: DirAccess) -> Self {
+        Self { std, access }
     }
 
     /// Consumes `self` and returns a `std::fs::File`.
@@ -51,6 +56,17 @@ impl File {
         self.std
     }
 
+    /// Returns the [`DirAccess`] level of the `Dir` this file was opened
+    /// from. Methods which change the file's metadata, such as
+    /// `set_permissions`, fail with `io::ErrorKind::PermissionDenied` unless
+    /// it's `DirAccess::ReadWrite`.
+    ///
+    /// [`DirAccess`]: enum.DirAccess.html
+    #[inline]
+    pub fn access(&self) -> DirAccess {
+        self.access
+    }
+
     /// Returns a new `OpenOptions` object.
     ///
     /// This corresponds to [`std::fs::File::with_options`].
@@ -113,7 +129,7 @@ impl File {
     #[inline]
     pub fn try_clone(&self) -> io::Result<Self> {
         let file = self.std.try_clone()?;
-        Ok(unsafe { Self::from_std(file) })
+        Ok(unsafe { Self::from_std_with_access(file, self.access) })
     }
 
     /// Changes the permissions on the underlying file.
@@ -123,9 +139,68 @@ impl File {
     /// [`std::fs::File::set_permissions`]: https://doc.rust-lang.org/std/fs/struct.File.html#method.set_permissions
     #[inline]
     pub fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
+        self.access.check_modify()?;
         self.std
             .set_permissions(permissions_into_std(&self.std, perm)?)
     }
+
+    /// Changes the owner and group of the underlying file. A `None` `uid` or
+    /// `gid` leaves the corresponding owner unchanged.
+    ///
+    /// This corresponds to [`std::os::unix::fs::fchown`].
+    ///
+    /// [`std::os::unix::fs::fchown`]: https://doc.rust-lang.org/std/os/unix/fs/fn.fchown.html
+    #[cfg(unix)]
+    #[inline]
+    pub fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+        self.access.check_modify()?;
+        cap_primitives::fs::set_file_owner(&self.std, uid, gid)
+    }
+
+    /// Acquires a shared advisory lock on the whole file, blocking until it's
+    /// available.
+    ///
+    /// Any number of shared locks may be held at once, as long as nobody
+    /// holds an exclusive lock. If this file already holds a lock, it's
+    /// converted to a shared lock. On Unix, this uses `flock`, so the lock
+    /// is shared by clones of this `File` and is released when the last of
+    /// them is closed.
+    #[inline]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        lock(&self.std, FileLockKind::Shared)
+    }
+
+    /// Acquires an exclusive advisory lock on the whole file, blocking until
+    /// it's available.
+    ///
+    /// See [`lock_shared`] for details.
+    ///
+    /// [`lock_shared`]: #method.lock_shared
+    #[inline]
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        lock(&self.std, FileLockKind::Exclusive)
+    }
+
+    /// Acquires a shared advisory lock on the whole file if it's available,
+    /// returning `Ok(false)` instead of blocking if it isn't.
+    #[inline]
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        try_lock(&self.std, FileLockKind::Shared)
+    }
+
+    /// Acquires an exclusive advisory lock on the whole file if it's
+    /// available, returning `Ok(false)` instead of blocking if it isn't.
+    #[inline]
+    pub fn try_lock_exclusive(&self) -> io::Result<bool> {
+        try_lock(&self.std, FileLockKind::Exclusive)
+    }
+
+    /// Releases an advisory lock acquired by one of the `lock_*` or
+    /// `try_lock_*` methods.
+    #[inline]
+    pub fn unlock(&self) -> io::Result<()> {
+        unlock(&self.std)
+    }
 }
 
 #[cfg(not(target_os = "wasi"))]
@@ -0,0 +1,37 @@
+use std::{fmt, io, path::PathBuf};
+
+/// An iterator over the paths matching a glob pattern, returned by
+/// [`Dir::glob`].
+///
+/// Paths are relative to the `Dir`. Patterns may contain `?`, `*`, `**` and
+/// character classes such as `[a-z]` or `[!0-9]`; `..` components are
+/// resolved lexically and never refer to anything outside the `Dir`.
+///
+/// [`Dir::glob`]: struct.Dir.html#method.glob
+pub struct Glob {
+    pub(crate) inner: cap_primitives::fs::Glob,
+}
+
+impl Glob {
+    /// Sets the option to match names without regard to case.
+    #[inline]
+    pub fn case_insensitive(&mut self, case_insensitive: bool) -> &mut Self {
+        self.inner.case_insensitive(case_insensitive);
+        self
+    }
+}
+
+impl Iterator for Glob {
+    type Item = io::Result<PathBuf>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl fmt::Debug for Glob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
@@ -26,20 +26,30 @@
 //! [`std::fs::File`]: https://doc.rust-lang.org/std/fs/struct.File.html
 //! [`Dir`]: struct.Dir.html
 
+mod atomic_file;
 mod dir;
 mod dir_entry;
 mod file;
+mod file_lock;
+mod glob;
 mod read_dir;
+mod walk;
 
+pub use atomic_file::*;
 pub use dir::*;
 pub use dir_entry::*;
 pub use file::*;
+pub use file_lock::*;
+pub use glob::*;
 pub use read_dir::*;
+pub use walk::*;
 
 // Re-export things from `cap_primitives` that we can use as-is.
 #[cfg(not(target_os = "wasi"))]
 pub use cap_primitives::fs::{DirBuilder, FileType, Metadata, OpenOptions, Permissions};
 
+pub use cap_primitives::fs::{CopyDirOptions, CopyProgress, DirAccess};
+
 // Re-export things from `std` that we can use as-is.
 #[cfg(target_os = "wasi")]
 pub use std::fs::{DirBuilder, FileType, Metadata, OpenOptions, Permissions};
@@ -1,4 +1,5 @@
 use crate::fs::DirEntry;
+use cap_primitives::fs::DirAccess;
 use std::{fmt, io};
 
 /// Iterator over the entries in a directory.
@@ -12,6 +13,8 @@ use std::{fmt, io};
 /// [`std::fs::ReadDir`]: https://doc.rust-lang.org/std/fs/struct.ReadDir.html
 pub struct ReadDir {
     pub(crate) inner: cap_primitives::fs::ReadDir,
+    pub(crate) access: DirAccess,
+    pub(crate) no_xdev: bool,
 }
 
 impl Iterator for ReadDir {
@@ -19,9 +22,14 @@ impl Iterator for ReadDir {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
+        let (access, no_xdev) = (self.access, self.no_xdev);
         self.inner
             .next()
-            .map(|inner| inner.map(|inner| DirEntry { inner }))
+            .map(|inner| inner.map(|inner| DirEntry {
+                    inner,
+                    access,
+                    no_xdev,
+                }))
     }
 }
 
@@ -1,6 +1,6 @@
 use crate::{
-    fs::{OpenOptions, Permissions},
-    fs_utf8::{from_utf8, to_utf8, DirBuilder, File, Metadata, ReadDir},
+    fs::{AtomicFile, CopyDirOptions, DirAccess, OpenOptions, Permissions},
+    fs_utf8::{from_utf8, to_utf8, DirBuilder, File, FileLock, Glob, Metadata, ReadDir, Walk},
 };
 #[cfg(windows)]
 use std::os::windows::io::{AsRawHandle, FromRawHandle, IntoRawHandle, RawHandle};
@@ -47,6 +47,74 @@ impl Dir {
         Self { cap_std }
     }
 
+    /// Returns the [`DirAccess`] level that this `Dir` is restricted to.
+    ///
+    /// [`DirAccess`]: ../fs/enum.DirAccess.html
+    #[inline]
+    pub fn access(&self) -> DirAccess {
+        self.cap_std.access()
+    }
+
+    /// Returns a new `Dir` for the same directory which can't create, write,
+    /// remove, or rename anything, or change permissions.
+    ///
+    /// The `DirExtUtf8` extension trait in `cap-fs-ext` honors the
+    /// restriction too, and `File`s opened from the returned `Dir` can't
+    /// change their permissions or owner. Only the raw handle bypasses it.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::read_only`].
+    ///
+    /// [`cap_std::fs::Dir::read_only`]: ../fs/struct.Dir.html#method.read_only
+    #[inline]
+    pub fn read_only(&self) -> io::Result<Self> {
+        self.cap_std.read_only().map(Self::from_cap_std)
+    }
+
+    /// Returns a new `Dir` for the same directory which can create new files
+    /// and directories and append to files, but can't otherwise modify
+    /// anything.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::append_only`].
+    ///
+    /// [`cap_std::fs::Dir::append_only`]: ../fs/struct.Dir.html#method.append_only
+    #[inline]
+    pub fn append_only(&self) -> io::Result<Self> {
+        self.cap_std.append_only().map(Self::from_cap_std)
+    }
+
+    /// Returns a new `Dir` for the same directory which can create new files
+    /// and directories, but can't otherwise modify anything.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::create_only`].
+    ///
+    /// [`cap_std::fs::Dir::create_only`]: ../fs/struct.Dir.html#method.create_only
+    #[inline]
+    pub fn create_only(&self) -> io::Result<Self> {
+        self.cap_std.create_only().map(Self::from_cap_std)
+    }
+
+    /// Returns `true` if this `Dir` is pinned to the filesystem it's on.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::is_no_xdev`].
+    ///
+    /// [`cap_std::fs::Dir::is_no_xdev`]: ../fs/struct.Dir.html#method.is_no_xdev
+    #[inline]
+    pub fn is_no_xdev(&self) -> bool {
+        self.cap_std.is_no_xdev()
+    }
+
+    /// Returns a new `Dir` for the same directory which fails rather than
+    /// resolve a path through a mount point.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::no_xdev`].
+    ///
+    /// [`cap_std::fs::Dir::no_xdev`]: ../fs/struct.Dir.html#method.no_xdev
+    #[cfg(unix)]
+    #[inline]
+    pub fn no_xdev(&self) -> io::Result<Self> {
+        self.cap_std.no_xdev().map(Self::from_cap_std)
+    }
+
     /// Attempts to open a file in read-only mode.
     ///
     /// This corresponds to [`std::fs::File::open`], but only accesses paths
@@ -133,6 +201,29 @@ impl Dir {
         self.cap_std.create(path).map(File::from_cap_std)
     }
 
+    /// Creates a new file in `self` which has no name, opened for reading and
+    /// writing.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::create_anonymous_file`].
+    ///
+    /// [`cap_std::fs::Dir::create_anonymous_file`]: ../fs/struct.Dir.html#method.create_anonymous_file
+    #[inline]
+    pub fn create_anonymous_file(&self) -> io::Result<File> {
+        self.cap_std.create_anonymous_file().map(File::from_cap_std)
+    }
+
+    /// Gives a file created by [`create_anonymous_file`] the name `path`.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::link_file_at`].
+    ///
+    /// [`create_anonymous_file`]: #method.create_anonymous_file
+    /// [`cap_std::fs::Dir::link_file_at`]: ../fs/struct.Dir.html#method.link_file_at
+    #[inline]
+    pub fn link_file_at<P: AsRef<str>>(&self, file: &File, path: P) -> io::Result<()> {
+        let path = from_utf8(path)?;
+        self.cap_std.link_file_at(&file.cap_std, path)
+    }
+
     /// Returns the canonical form of a path with all intermediate components normalized
     /// and symbolic links resolved.
     ///
@@ -165,6 +256,45 @@ impl Dir {
         self.cap_std.copy(from, &to_dir.cap_std, to)
     }
 
+    /// Recursively copies a directory and all of its contents to a new
+    /// directory, which may be in a different `Dir`.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::copy_dir_all`].
+    ///
+    /// [`cap_std::fs::Dir::copy_dir_all`]: ../fs/struct.Dir.html#method.copy_dir_all
+    #[inline]
+    pub fn copy_dir_all<P: AsRef<str>, Q: AsRef<str>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+    ) -> io::Result<u64> {
+        let from = from_utf8(from)?;
+        let to = from_utf8(to)?;
+        self.cap_std.copy_dir_all(from, &to_dir.cap_std, to)
+    }
+
+    /// Recursively copies a directory and all of its contents to a new
+    /// directory, with the options specified by `options`.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::copy_dir_all_with`]. Paths
+    /// passed to the progress callback aren't converted to UTF-8.
+    ///
+    /// [`cap_std::fs::Dir::copy_dir_all_with`]: ../fs/struct.Dir.html#method.copy_dir_all_with
+    #[inline]
+    pub fn copy_dir_all_with<P: AsRef<str>, Q: AsRef<str>>(
+        &self,
+        from: P,
+        to_dir: &Self,
+        to: Q,
+        options: &CopyDirOptions,
+    ) -> io::Result<u64> {
+        let from = from_utf8(from)?;
+        let to = from_utf8(to)?;
+        self.cap_std
+            .copy_dir_all_with(from, &to_dir.cap_std, to, options)
+    }
+
     /// Creates a new hard link on a filesystem.
     ///
     /// This corresponds to [`std::fs::hard_link`], but only accesses paths
@@ -213,6 +343,29 @@ impl Dir {
         self.cap_std.read_dir(path).map(ReadDir::from_cap_std)
     }
 
+    /// Returns an iterator over the entries within `self` and, recursively,
+    /// its subdirectories.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::walk`].
+    ///
+    /// [`cap_std::fs::Dir::walk`]: ../fs/struct.Dir.html#method.walk
+    #[inline]
+    pub fn walk(&self) -> io::Result<Walk> {
+        let file = self.cap_std.try_clone()?.into_std_file();
+        Ok(Walk::new(file, self.access(), self.is_no_xdev()))
+    }
+
+    /// Returns an iterator over the paths within `self` which match the glob
+    /// `pattern`, relative to `self`.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::glob`].
+    ///
+    /// [`cap_std::fs::Dir::glob`]: ../fs/struct.Dir.html#method.glob
+    #[inline]
+    pub fn glob(&self, pattern: &str) -> io::Result<Glob> {
+        self.cap_std.glob(pattern).map(Glob::from_cap_std)
+    }
+
     /// Read the entire contents of a file into a bytes vector.
     ///
     /// This corresponds to [`std::fs::read`], but only accesses paths
@@ -360,6 +513,66 @@ impl Dir {
         self.cap_std.write(path, contents)
     }
 
+    /// Writes a slice as the entire contents of a file, atomically replacing
+    /// any existing file at `path`.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::atomic_write`].
+    ///
+    /// [`cap_std::fs::Dir::atomic_write`]: ../fs/struct.Dir.html#method.atomic_write
+    #[inline]
+    pub fn atomic_write<P: AsRef<str>, C: AsRef<[u8]>>(
+        &self,
+        path: P,
+        contents: C,
+    ) -> io::Result<()> {
+        let path = from_utf8(path)?;
+        self.cap_std.atomic_write(path, contents)
+    }
+
+    /// Creates a file which replaces the file at `path` atomically when it's
+    /// committed.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::create_atomic`].
+    ///
+    /// [`cap_std::fs::Dir::create_atomic`]: ../fs/struct.Dir.html#method.create_atomic
+    #[inline]
+    pub fn create_atomic<P: AsRef<str>>(&self, path: P) -> io::Result<AtomicFile> {
+        let path = from_utf8(path)?;
+        self.cap_std.create_atomic(path)
+    }
+
+    /// Opens the lock file at `path`, creating it if it doesn't exist, and
+    /// acquires an exclusive advisory lock on it, blocking until it's
+    /// available.
+    ///
+    /// This corresponds to [`cap_std::fs::Dir::lock_file`].
+    ///
+    /// [`cap_std::fs::Dir::lock_file`]: ../fs/struct.Dir.html#method.lock_file
+    #[inline]
+    pub fn lock_file<P: AsRef<str>>(&self, path: P) -> io::Result<FileLock> {
+        let file = self.open_lock_file(path)?;
+        file.lock_exclusive()?;
+        Ok(FileLock::new(file))
+    }
+
+    /// Like [`lock_file`], but returns `Ok(None)` instead of blocking if the
+    /// lock is held by someone else.
+    ///
+    /// [`lock_file`]: #method.lock_file
+    #[inline]
+    pub fn try_lock_file<P: AsRef<str>>(&self, path: P) -> io::Result<Option<FileLock>> {
+        let file = self.open_lock_file(path)?;
+        Ok(if file.try_lock_exclusive()? {
+            Some(FileLock::new(file))
+        } else {
+            None
+        })
+    }
+
+    fn open_lock_file<P: AsRef<str>>(&self, path: P) -> io::Result<File> {
+        self.open_with(path, OpenOptions::new().read(true).write(true).create(true))
+    }
+
     /// Creates a new symbolic link on a filesystem.
     ///
     /// This corresponds to [`std::os::unix::fs::symlink`], but only accesses paths
@@ -26,7 +26,7 @@ use std::{fmt, io};
 ///
 /// [`std::fs::DirEntry`]: https://doc.rust-lang.org/std/fs/struct.DirEntry.html
 pub struct DirEntry {
-    cap_std: crate::fs::DirEntry,
+    pub(crate) cap_std: crate::fs::DirEntry,
 }
 
 impl DirEntry {
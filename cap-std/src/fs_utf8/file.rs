@@ -1,6 +1,6 @@
 #[cfg(with_options)]
 use crate::fs::OpenOptions;
-use crate::fs::{Metadata, Permissions};
+use crate::fs::{DirAccess, Metadata, Permissions};
 #[cfg(read_initializer)]
 use std::io::Initializer;
 #[cfg(unix)]
@@ -30,7 +30,7 @@ use std::{
 /// [`Dir::open`]: struct.Dir.html#method.open
 /// [`Dir::create`]: struct.Dir.html#method.create
 pub struct File {
-    cap_std: crate::fs::File,
+    pub(crate) cap_std: crate::fs::File,
 }
 
 impl File {
@@ -67,6 +67,18 @@ impl File {
         crate::fs::File::with_options()
     }
 
+    /// Returns the [`DirAccess`] level of the `Dir` this file was opened
+    /// from.
+    ///
+    /// This corresponds to [`cap_std::fs::File::access`].
+    ///
+    /// [`DirAccess`]: ../fs/enum.DirAccess.html
+    /// [`cap_std::fs::File::access`]: ../fs/struct.File.html#method.access
+    #[inline]
+    pub fn access(&self) -> DirAccess {
+        self.cap_std.access()
+    }
+
     /// Attempts to sync all OS-internal metadata to disk.
     ///
     /// This corresponds to [`std::fs::File::sync_all`].
@@ -129,6 +141,61 @@ impl File {
     pub fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
         self.cap_std.set_permissions(perm)
     }
+
+    /// Changes the owner and group of the underlying file. A `None` `uid` or
+    /// `gid` leaves the corresponding owner unchanged.
+    ///
+    /// This corresponds to [`std::os::unix::fs::fchown`].
+    ///
+    /// [`std::os::unix::fs::fchown`]: https://doc.rust-lang.org/std/os/unix/fs/fn.fchown.html
+    #[cfg(unix)]
+    #[inline]
+    pub fn set_owner(&self, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+        self.cap_std.set_owner(uid, gid)
+    }
+
+    /// Acquires a shared advisory lock on the whole file, blocking until it's
+    /// available.
+    ///
+    /// This corresponds to [`cap_std::fs::File::lock_shared`].
+    ///
+    /// [`cap_std::fs::File::lock_shared`]: ../fs/struct.File.html#method.lock_shared
+    #[inline]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.cap_std.lock_shared()
+    }
+
+    /// Acquires an exclusive advisory lock on the whole file, blocking until
+    /// it's available.
+    ///
+    /// This corresponds to [`cap_std::fs::File::lock_exclusive`].
+    ///
+    /// [`cap_std::fs::File::lock_exclusive`]: ../fs/struct.File.html#method.lock_exclusive
+    #[inline]
+    pub fn lock_exclusive(&self) -> io::Result<()> {
+        self.cap_std.lock_exclusive()
+    }
+
+    /// Acquires a shared advisory lock on the whole file if it's available,
+    /// returning `Ok(false)` instead of blocking if it isn't.
+    #[inline]
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.cap_std.try_lock_shared()
+    }
+
+    /// Acquires an exclusive advisory lock on the whole file if it's
+    /// available, returning `Ok(false)` instead of blocking if it isn't.
+    #[inline]
+    pub fn try_lock_exclusive(&self) -> io::Result<bool> {
+        self.cap_std.try_lock_exclusive()
+    }
+
+    /// Releases an advisory lock acquired by one of the `lock_*` or
+    /// `try_lock_*` methods.
+    #[inline]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.cap_std.unlock()
+    }
 }
 
 #[cfg(not(windows))]
@@ -0,0 +1,45 @@
+use crate::fs_utf8::File;
+use std::{fmt, io};
+
+/// An exclusive advisory lock on a lock file, returned by [`Dir::lock_file`].
+///
+/// This corresponds to [`cap_std::fs::FileLock`].
+///
+/// [`Dir::lock_file`]: struct.Dir.html#method.lock_file
+/// [`cap_std::fs::FileLock`]: ../fs/struct.FileLock.html
+pub struct FileLock {
+    file: File,
+}
+
+impl FileLock {
+    pub(crate) fn new(file: File) -> Self {
+        Self { file }
+    }
+
+    /// Returns the locked file.
+    #[inline]
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    /// Releases the lock, reporting any error which occurs.
+    #[inline]
+    pub fn unlock(self) -> io::Result<()> {
+        self.file.unlock()
+    }
+}
+
+impl Drop for FileLock {
+    #[inline]
+    fn drop(&mut self) {
+        self.file.unlock().ok();
+    }
+}
+
+impl fmt::Debug for FileLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut b = f.debug_struct("FileLock");
+        b.field("file", &self.file);
+        b.finish()
+    }
+}
@@ -0,0 +1,44 @@
+use crate::fs_utf8::to_utf8;
+use std::{fmt, io};
+
+/// An iterator over the paths matching a glob pattern, returned by
+/// [`Dir::glob`].
+///
+/// This corresponds to [`cap_std::fs::Glob`], but yields paths as
+/// `String`s.
+///
+/// [`Dir::glob`]: struct.Dir.html#method.glob
+/// [`cap_std::fs::Glob`]: ../fs/struct.Glob.html
+pub struct Glob {
+    cap_std: crate::fs::Glob,
+}
+
+impl Glob {
+    /// Constructs a new instance of `Self` from the given `cap_std::fs::Glob`.
+    #[inline]
+    pub fn from_cap_std(cap_std: crate::fs::Glob) -> Self {
+        Self { cap_std }
+    }
+
+    /// Sets the option to match names without regard to case.
+    #[inline]
+    pub fn case_insensitive(&mut self, case_insensitive: bool) -> &mut Self {
+        self.cap_std.case_insensitive(case_insensitive);
+        self
+    }
+}
+
+impl Iterator for Glob {
+    type Item = io::Result<String>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cap_std.next().map(|result| result.and_then(to_utf8))
+    }
+}
+
+impl fmt::Debug for Glob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.cap_std.fmt(f)
+    }
+}
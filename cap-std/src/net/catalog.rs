@@ -1,23 +1,345 @@
-#![allow(missing_docs)] // TODO: add docs
-
-use crate::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use crate::net::{
+    IpNet, Operations, PeerFilter, PolicyError, Resolver, SocketAddr, TcpListener,
+    TcpListenerOptions, TcpStream, ToSocketAddrs, UdpSocket, UdpSocketOptions,
+};
 use cap_primitives::net::NO_SOCKET_ADDRS;
-use std::{io, net, time::Duration};
-
-// FIXME: lots more to do here
+use std::{io, net, sync::Arc, time::Duration};
 
+/// A set of network addresses that may be accessed.
+///
+/// Instead of [`std::net`]'s constructor methods which take an address to
+/// bind or connect to, this type has methods which operate on addresses
+/// which must be present in the catalog. Addresses outside the catalog are
+/// rejected with [`io::ErrorKind::PermissionDenied`].
+///
+/// [`std::net`]: https://doc.rust-lang.org/std/net/
+/// [`io::ErrorKind::PermissionDenied`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.PermissionDenied
+#[derive(Clone, Default)]
 pub struct Catalog {
-    cap: cap_primitives::net::Catalog,
+    cap: Arc<cap_primitives::net::Catalog>,
 }
 
 impl Catalog {
+    /// Construct a new empty catalog, which permits no addresses.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            cap: Arc::new(cap_primitives::net::Catalog::new()),
+        }
+    }
+
+    /// Add a range of network addresses, accepting any port, to the catalog.
+    ///
+    /// The addresses may be used for all [`Operations`].
+    ///
+    /// # Safety
+    ///
+    /// This grants access to any address in `ip_net`, which may be any
+    /// address that the host process has access to.
+    ///
+    /// [`Operations`]: struct.Operations.html
+    #[inline]
+    pub unsafe fn insert_ip_net_port_any(&mut self, ip_net: IpNet) -> &mut Self {
+        Arc::make_mut(&mut self.cap).insert_ip_net_port_any(ip_net);
+        self
+    }
+
+    /// Add a range of network addresses, accepting a range of ports, to the
+    /// catalog.
+    ///
+    /// The range of ports is inclusive of both `ports_start` and `ports_end`.
+    /// The addresses may be used for all [`Operations`].
+    ///
+    /// # Safety
+    ///
+    /// This grants access to any address in `ip_net`, which may be any
+    /// address that the host process has access to.
+    ///
+    /// [`Operations`]: struct.Operations.html
+    #[inline]
+    pub unsafe fn insert_ip_net_port_range(
+        &mut self,
+        ip_net: IpNet,
+        ports_start: u16,
+        ports_end: u16,
+    ) -> &mut Self {
+        Arc::make_mut(&mut self.cap).insert_ip_net_port_range(ip_net, ports_start, ports_end);
+        self
+    }
+
+    /// Add a range of network addresses, accepting a range of ports, to the
+    /// catalog, permitting only the given `operations` on them.
+    ///
+    /// For example, a grant with only [`Operations::CONNECT`] may be used to
+    /// connect TCP streams, but not to bind listeners.
+    ///
+    /// The range of ports is inclusive of both `ports_start` and `ports_end`.
+    ///
+    /// # Safety
+    ///
+    /// This grants access to any address in `ip_net`, which may be any
+    /// address that the host process has access to.
+    ///
+    /// [`Operations::CONNECT`]: struct.Operations.html#associatedconstant.CONNECT
+    #[inline]
+    pub unsafe fn insert_ip_net_operations(
+        &mut self,
+        ip_net: IpNet,
+        ports_start: u16,
+        ports_end: u16,
+        operations: Operations,
+    ) -> &mut Self {
+        Arc::make_mut(&mut self.cap).insert_ip_net_operations(
+            ip_net,
+            ports_start,
+            ports_end,
+            operations,
+        );
+        self
+    }
+
+    /// Add a range of network addresses with a specific port to the catalog.
+    ///
+    /// The addresses may be used for all [`Operations`].
+    ///
+    /// # Safety
+    ///
+    /// This grants access to any address in `ip_net`, which may be any
+    /// address that the host process has access to.
+    ///
+    /// [`Operations`]: struct.Operations.html
+    #[inline]
+    pub unsafe fn insert_ip_net(&mut self, ip_net: IpNet, port: u16) -> &mut Self {
+        Arc::make_mut(&mut self.cap).insert_ip_net(ip_net, port);
+        self
+    }
+
+    /// Add a host name pattern with a specific port to the catalog.
+    ///
+    /// The pattern is either a host name, such as `db.internal.example`, or
+    /// a wildcard, such as `*.internal.example`, which matches any name with
+    /// one or more labels in front of `.internal.example`. Names are compared
+    /// case-insensitively. The names may be used for all [`Operations`].
+    ///
+    /// Host name grants are used by [`connect_tcp_stream_by_name`] and
+    /// [`lookup_host`], which look up names with the catalog's [`Resolver`].
+    /// Other methods only accept addresses for these grants once
+    /// [`lookup_host`] has inserted grants for them.
+    ///
+    /// # Safety
+    ///
+    /// This grants access to any address that the catalog's resolver
+    /// produces for a matching name, which may be any address that the host
+    /// process has access to.
+    ///
+    /// [`Operations`]: struct.Operations.html
+    /// [`connect_tcp_stream_by_name`]: #method.connect_tcp_stream_by_name
+    /// [`lookup_host`]: #method.lookup_host
+    /// [`Resolver`]: trait.Resolver.html
+    #[inline]
+    pub unsafe fn insert_name(&mut self, pattern: &str, port: u16) -> &mut Self {
+        Arc::make_mut(&mut self.cap).insert_name(pattern, port);
+        self
+    }
+
+    /// Add a host name pattern, accepting a range of ports, to the catalog,
+    /// permitting only the given `operations` on it.
+    ///
+    /// The range of ports is inclusive of both `ports_start` and `ports_end`.
+    /// See [`insert_name`] for the syntax of `pattern`.
+    ///
+    /// # Safety
+    ///
+    /// This grants access to any address that the catalog's resolver
+    /// produces for a matching name, which may be any address that the host
+    /// process has access to.
+    ///
+    /// [`insert_name`]: #method.insert_name
+    #[inline]
+    pub unsafe fn insert_name_operations(
+        &mut self,
+        pattern: &str,
+        ports_start: u16,
+        ports_end: u16,
+        operations: Operations,
+    ) -> &mut Self {
+        Arc::make_mut(&mut self.cap).insert_name_operations(
+            pattern,
+            ports_start,
+            ports_end,
+            operations,
+        );
+        self
+    }
+
+    /// Set the [`Resolver`] used to look up host names permitted by this
+    /// catalog. By default, the [`SystemResolver`] is used.
+    ///
+    /// Catalogs derived from this one with [`restrict`] or [`attenuate`]
+    /// share its resolver.
+    ///
+    /// # Safety
+    ///
+    /// The resolver decides which addresses the host names in this catalog
+    /// refer to, which may be any address that the host process has access
+    /// to.
+    ///
+    /// [`Resolver`]: trait.Resolver.html
+    /// [`SystemResolver`]: struct.SystemResolver.html
+    /// [`restrict`]: #method.restrict
+    /// [`attenuate`]: #method.attenuate
+    #[inline]
+    pub unsafe fn set_resolver(&mut self, resolver: Arc<dyn Resolver>) -> &mut Self {
+        Arc::make_mut(&mut self.cap).set_resolver(resolver);
+        self
+    }
+
+    /// Add a specific `SocketAddr` to the catalog.
+    ///
+    /// The address may be used for all [`Operations`].
+    ///
+    /// # Safety
+    ///
+    /// This grants access to `addr`, which may be any address that the host
+    /// process has access to.
+    ///
+    /// [`Operations`]: struct.Operations.html
+    #[inline]
+    pub unsafe fn insert_socket_addr(&mut self, addr: SocketAddr) -> &mut Self {
+        Arc::make_mut(&mut self.cap).insert_socket_addr(addr);
+        self
+    }
+
+    /// Construct a new catalog which permits only the addresses in `ip_net`
+    /// with ports in the inclusive range from `ports_start` to `ports_end`
+    /// which are also permitted by `self`.
+    ///
+    /// This is similar to [`Dir::open_dir`] in that it produces a capability
+    /// for a subset of the resources of `self`; it never widens access.
+    ///
+    /// [`Dir::open_dir`]: ../fs/struct.Dir.html#method.open_dir
+    #[inline]
+    pub fn restrict(&self, ip_net: IpNet, ports_start: u16, ports_end: u16) -> Self {
+        Self {
+            cap: Arc::new(self.cap.restrict(ip_net, ports_start, ports_end)),
+        }
+    }
+
+    /// Construct a new catalog which permits only the addresses permitted by
+    /// both `self` and `other`.
+    ///
+    /// Like [`restrict`], this never widens access.
+    ///
+    /// [`restrict`]: #method.restrict
+    #[inline]
+    pub fn attenuate(&self, other: &Self) -> Self {
+        Self {
+            cap: Arc::new(self.cap.attenuate(&other.cap)),
+        }
+    }
+
+    /// Construct a new catalog which permits the same addresses as `self`,
+    /// but only for the given `operations`.
+    ///
+    /// Like [`restrict`], this never widens access.
+    ///
+    /// [`restrict`]: #method.restrict
+    #[inline]
+    pub fn restrict_operations(&self, operations: Operations) -> Self {
+        Self {
+            cap: Arc::new(self.cap.restrict_operations(operations)),
+        }
+    }
+
+    /// Construct a new catalog from the grants in a textual policy, as
+    /// described in [`cap_primitives::net::Catalog::from_policy`].
+    ///
+    /// Errors report the line and column at which parsing failed.
+    ///
+    /// # Safety
+    ///
+    /// This grants access to all of the addresses in `policy`, which may be
+    /// any addresses that the host process has access to.
+    ///
+    /// [`cap_primitives::net::Catalog::from_policy`]: https://docs.rs/cap-primitives/latest/cap_primitives/net/struct.Catalog.html#method.from_policy
+    #[inline]
+    pub unsafe fn from_policy(policy: &str) -> Result<Self, PolicyError> {
+        Ok(Self {
+            cap: Arc::new(cap_primitives::net::Catalog::from_policy(policy)?),
+        })
+    }
+
+    /// Return a textual policy describing the grants in this catalog, which
+    /// may be parsed with [`from_policy`].
+    ///
+    /// The catalog's resolver isn't included. Grants inserted by
+    /// [`lookup_host`] are included, as IP address grants.
+    ///
+    /// [`from_policy`]: #method.from_policy
+    /// [`lookup_host`]: #method.lookup_host
+    #[inline]
+    pub fn to_policy(&self) -> String {
+        self.cap.to_policy()
+    }
+
+    /// Check whether the given address is permitted by this catalog for all
+    /// of the given `operations`, and fail with `PermissionDenied` if it is
+    /// not.
+    #[inline]
+    pub fn check_addr(&self, addr: &SocketAddr, operations: Operations) -> io::Result<()> {
+        self.cap.check_addr(addr, operations)
+    }
+
+    /// Look up the given host name with the catalog's [`Resolver`], if the
+    /// name and port are permitted by a host name grant in this catalog.
+    ///
+    /// This is a capability-oriented alternative to passing a host name to
+    /// [`ToSocketAddrs`], which uses the system resolver. Grants for the
+    /// returned addresses, at `port` only, are inserted into this catalog,
+    /// for the operations that the name is permitted for, so they may be
+    /// passed to its other methods. Clones of this catalog, and catalogs
+    /// derived from it, taken before the lookup aren't affected.
+    ///
+    /// [`Resolver`]: trait.Resolver.html
+    /// [`ToSocketAddrs`]: https://doc.rust-lang.org/std/net/trait.ToSocketAddrs.html
+    #[inline]
+    pub fn lookup_host(&mut self, name: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        Arc::make_mut(&mut self.cap).lookup_host(name, port)
+    }
+
+    /// Check whether the given host name and port are permitted by this
+    /// catalog for all of the given `operations`, and fail with
+    /// `PermissionDenied` if they are not.
+    #[inline]
+    pub fn check_name(&self, name: &str, port: u16, operations: Operations) -> io::Result<()> {
+        self.cap.check_name(name, port, operations)
+    }
+
+    /// Construct a [`PeerFilter`] which accepts connections from addresses
+    /// that this catalog permits for `Operations::ACCEPT`, for use with
+    /// [`TcpListener::set_peer_filter`].
+    ///
+    /// [`PeerFilter`]: struct.PeerFilter.html
+    /// [`TcpListener::set_peer_filter`]: struct.TcpListener.html#method.set_peer_filter
+    #[inline]
+    pub fn peer_filter(&self) -> PeerFilter {
+        PeerFilter::from_catalog(Arc::clone(&self.cap))
+    }
+
+    /// Creates a new `TcpListener` which will be bound to the specified
+    /// address.
+    ///
+    /// This corresponds to [`std::net::TcpListener::bind`], but only
+    /// accepts addresses present in the catalog for `Operations::BIND`.
+    ///
+    /// [`std::net::TcpListener::bind`]: https://doc.rust-lang.org/std/net/struct.TcpListener.html#method.bind
     #[inline]
     pub fn bind_tcp_listener<A: ToSocketAddrs>(&self, addr: A) -> io::Result<TcpListener> {
         let addrs = addr.to_socket_addrs()?;
 
         let mut last_err = None;
         for addr in addrs {
-            self.cap.check_addr(&addr)?;
+            self.cap.check_addr(&addr, Operations::BIND)?;
             // TODO: when compiling for WASI, use WASI-specific methods instead
             match net::TcpListener::bind(addr) {
                 Ok(tcp_listener) => return Ok(unsafe { TcpListener::from_std(tcp_listener) }),
@@ -30,13 +352,72 @@ impl Catalog {
         }
     }
 
+    /// Creates a new `TcpListener` which will be bound to the specified
+    /// address, with the given options.
+    ///
+    /// This is like [`bind_tcp_listener`], but applies `options` to the
+    /// socket before it's bound.
+    ///
+    /// [`bind_tcp_listener`]: #method.bind_tcp_listener
+    #[inline]
+    pub fn bind_tcp_listener_with<A: ToSocketAddrs>(
+        &self,
+        addr: A,
+        options: &TcpListenerOptions,
+    ) -> io::Result<TcpListener> {
+        let addrs = addr.to_socket_addrs()?;
+
+        let mut last_err = None;
+        for addr in addrs {
+            self.cap.check_addr(&addr, Operations::BIND)?;
+            match self.cap.bind_tcp_listener_with(&addr, options) {
+                Ok(tcp_listener) => return Ok(unsafe { TcpListener::from_std(tcp_listener) }),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) => Err(e),
+            None => Err(net::TcpListener::bind(NO_SOCKET_ADDRS).unwrap_err()),
+        }
+    }
+
+    /// Opens a TCP connection to a remote host.
+    ///
+    /// This corresponds to [`std::net::TcpStream::connect`], but only
+    /// accepts addresses present in the catalog for `Operations::CONNECT`.
+    ///
+    /// [`std::net::TcpStream::connect`]: https://doc.rust-lang.org/std/net/struct.TcpStream.html#method.connect
     #[inline]
     pub fn connect_tcp_stream<A: ToSocketAddrs>(&self, addr: A) -> io::Result<TcpStream> {
         let addrs = addr.to_socket_addrs()?;
 
         let mut last_err = None;
         for addr in addrs {
-            self.cap.check_addr(&addr)?;
+            self.cap.check_addr(&addr, Operations::CONNECT)?;
+            // TODO: when compiling for WASI, use WASI-specific methods instead
+            match net::TcpStream::connect(addr) {
+                Ok(tcp_stream) => return Ok(unsafe { TcpStream::from_std(tcp_stream) }),
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) => Err(e),
+            None => Err(net::TcpStream::connect(NO_SOCKET_ADDRS).unwrap_err()),
+        }
+    }
+
+    /// Opens a TCP connection to a remote host, given by name.
+    ///
+    /// `name` must be permitted by a host name grant in the catalog for
+    /// `Operations::CONNECT`. It's looked up with the catalog's resolver
+    /// rather than the system resolver, and each of the resulting
+    /// addresses is tried in turn.
+    #[inline]
+    pub fn connect_tcp_stream_by_name(&self, name: &str, port: u16) -> io::Result<TcpStream> {
+        let addrs = self.cap.resolve_name(name, port, Operations::CONNECT)?;
+
+        let mut last_err = None;
+        for addr in addrs {
             // TODO: when compiling for WASI, use WASI-specific methods instead
             match net::TcpStream::connect(addr) {
                 Ok(tcp_stream) => return Ok(unsafe { TcpStream::from_std(tcp_stream) }),
@@ -49,26 +430,89 @@ impl Catalog {
         }
     }
 
+    /// Opens a TCP connection to a remote host with a timeout.
+    ///
+    /// This corresponds to [`std::net::TcpStream::connect_timeout`], but only
+    /// accepts addresses present in the catalog for `Operations::CONNECT`.
+    ///
+    /// [`std::net::TcpStream::connect_timeout`]: https://doc.rust-lang.org/std/net/struct.TcpStream.html#method.connect_timeout
     #[inline]
     pub fn connect_timeout_tcp_stream(
         &self,
         addr: &SocketAddr,
         timeout: Duration,
     ) -> io::Result<TcpStream> {
-        self.cap.check_addr(addr)?;
+        self.cap.check_addr(addr, Operations::CONNECT)?;
         let tcp_stream = net::TcpStream::connect_timeout(addr, timeout)?;
         Ok(unsafe { TcpStream::from_std(tcp_stream) })
     }
 
+    /// Opens a TCP connection to whichever of the given addresses responds
+    /// first, racing IPv6 and IPv4 candidates as described in [RFC 8305].
+    ///
+    /// Addresses not present in the catalog for `Operations::CONNECT` are
+    /// skipped, and the call fails with `PermissionDenied` if none remain.
+    /// `timeout` applies to the whole attempt, rather than to each address.
+    ///
+    /// [RFC 8305]: https://tools.ietf.org/html/rfc8305
+    #[inline]
+    pub fn connect_happy_eyeballs_tcp_stream<A: ToSocketAddrs>(
+        &self,
+        addr: A,
+        timeout: Duration,
+    ) -> io::Result<TcpStream> {
+        let addrs = addr.to_socket_addrs()?.collect::<Vec<_>>();
+        let tcp_stream = self.cap.connect_happy_eyeballs(&addrs, timeout)?;
+        Ok(unsafe { TcpStream::from_std(tcp_stream) })
+    }
+
+    /// Creates a UDP socket from the given address.
+    ///
+    /// This corresponds to [`std::net::UdpSocket::bind`], but only
+    /// accepts addresses present in the catalog for `Operations::BIND`.
+    ///
+    /// [`std::net::UdpSocket::bind`]: https://doc.rust-lang.org/std/net/struct.UdpSocket.html#method.bind
     #[inline]
     pub fn bind_udp_socket<A: ToSocketAddrs>(&self, addr: A) -> io::Result<UdpSocket> {
         let addrs = addr.to_socket_addrs()?;
 
         let mut last_err = None;
         for addr in addrs {
-            self.cap.check_addr(&addr)?;
+            self.cap.check_addr(&addr, Operations::BIND)?;
             match net::UdpSocket::bind(addr) {
-                Ok(udp_socket) => return Ok(unsafe { UdpSocket::from_std(udp_socket) }),
+                Ok(udp_socket) => {
+                    return Ok(unsafe { UdpSocket::from_std_in(udp_socket, Arc::clone(&self.cap)) })
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) => Err(e),
+            None => Err(net::UdpSocket::bind(NO_SOCKET_ADDRS).unwrap_err()),
+        }
+    }
+
+    /// Creates a UDP socket from the given address, with the given options.
+    ///
+    /// This is like [`bind_udp_socket`], but applies `options` to the socket
+    /// before it's bound.
+    ///
+    /// [`bind_udp_socket`]: #method.bind_udp_socket
+    #[inline]
+    pub fn bind_udp_socket_with<A: ToSocketAddrs>(
+        &self,
+        addr: A,
+        options: &UdpSocketOptions,
+    ) -> io::Result<UdpSocket> {
+        let addrs = addr.to_socket_addrs()?;
+
+        let mut last_err = None;
+        for addr in addrs {
+            self.cap.check_addr(&addr, Operations::BIND)?;
+            match self.cap.bind_udp_socket_with(&addr, options) {
+                Ok(udp_socket) => {
+                    return Ok(unsafe { UdpSocket::from_std_in(udp_socket, Arc::clone(&self.cap)) })
+                }
                 Err(e) => last_err = Some(e),
             }
         }
@@ -78,6 +522,12 @@ impl Catalog {
         }
     }
 
+    /// Sends data on the socket to the given address.
+    ///
+    /// This corresponds to [`std::net::UdpSocket::send_to`], but only
+    /// accepts addresses present in the catalog for `Operations::UDP_SEND`.
+    ///
+    /// [`std::net::UdpSocket::send_to`]: https://doc.rust-lang.org/std/net/struct.UdpSocket.html#method.send_to
     #[inline]
     pub fn send_to_udp_socket_addr<A: ToSocketAddrs>(
         &self,
@@ -91,10 +541,18 @@ impl Catalog {
         let addr = addrs
             .next()
             .ok_or_else(|| net::UdpSocket::bind(NO_SOCKET_ADDRS).unwrap_err())?;
-        self.cap.check_addr(&addr)?;
+        self.cap.check_addr(&addr, Operations::UDP_SEND)?;
         udp_socket.std.send_to(buf, addr)
     }
 
+    /// Connects the UDP socket to a remote address.
+    ///
+    /// This corresponds to [`std::net::UdpSocket::connect`], but only
+    /// accepts addresses present in the catalog for both
+    /// `Operations::UDP_SEND` and `Operations::UDP_RECEIVE`, since a connected
+    /// socket may both send to and receive from its peer.
+    ///
+    /// [`std::net::UdpSocket::connect`]: https://doc.rust-lang.org/std/net/struct.UdpSocket.html#method.connect
     #[inline]
     pub fn connect_udp_socket<A: ToSocketAddrs>(
         &self,
@@ -105,7 +563,8 @@ impl Catalog {
 
         let mut last_err = None;
         for addr in addrs {
-            self.cap.check_addr(&addr)?;
+            self.cap
+                .check_addr(&addr, Operations::UDP_SEND | Operations::UDP_RECEIVE)?;
             match udp_socket.std.connect(addr) {
                 Ok(()) => return Ok(()),
                 Err(e) => last_err = Some(e),
@@ -1,4 +1,4 @@
-use crate::net::TcpStream;
+use crate::net::{PeerFilter, TcpStream};
 use std::{io, net};
 
 /// An iterator that infinitely `accept`s connections on a [`TcpListener`].
@@ -9,6 +9,7 @@ use std::{io, net};
 /// [`TcpListener`]: struct.TcpListener.html
 pub struct Incoming<'a> {
     std: net::Incoming<'a>,
+    peer_filter: Option<&'a PeerFilter>,
 }
 
 impl<'a> Incoming<'a> {
@@ -20,7 +21,17 @@ impl<'a> Incoming<'a> {
     /// process has access to.
     #[inline]
     pub unsafe fn from_std(std: net::Incoming<'a>) -> Self {
-        Self { std }
+        Self::from_std_filtered(std, None)
+    }
+
+    /// Like `from_std`, but skipping connections from peers rejected by
+    /// `peer_filter`.
+    #[inline]
+    pub(crate) unsafe fn from_std_filtered(
+        std: net::Incoming<'a>,
+        peer_filter: Option<&'a PeerFilter>,
+    ) -> Self {
+        Self { std, peer_filter }
     }
 }
 
@@ -29,10 +40,21 @@ impl<'a> Iterator for Incoming<'a> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        self.std.next().map(|result| {
-            let tcp_stream = result?;
-            Ok(unsafe { TcpStream::from_std(tcp_stream) })
-        })
+        loop {
+            let tcp_stream = match self.std.next()? {
+                Ok(tcp_stream) => tcp_stream,
+                Err(e) => return Some(Err(e)),
+            };
+            if let Some(peer_filter) = self.peer_filter {
+                // If the peer has already gone away, its address can't be
+                // checked, so just drop the connection.
+                match tcp_stream.peer_addr() {
+                    Ok(addr) if peer_filter.check_peer(&addr) => {}
+                    _ => continue,
+                }
+            }
+            return Some(Ok(unsafe { TcpStream::from_std(tcp_stream) }));
+        }
     }
 
     #[inline]
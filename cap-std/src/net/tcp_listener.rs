@@ -1,4 +1,4 @@
-use crate::net::{Incoming, SocketAddr, TcpStream};
+use crate::net::{Incoming, PeerFilter, SocketAddr, TcpStream};
 #[cfg(unix)]
 use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 #[cfg(windows)]
@@ -18,6 +18,7 @@ use std::{fmt, io, net};
 /// [`Catalog::bind_tcp_listener`]: struct.Catalog.html#method.bind_tcp_listener
 pub struct TcpListener {
     std: net::TcpListener,
+    peer_filter: Option<PeerFilter>,
 }
 
 impl TcpListener {
@@ -29,7 +30,10 @@ impl TcpListener {
     /// process has access to.
     #[inline]
     pub unsafe fn from_std(std: net::TcpListener) -> Self {
-        Self { std }
+        Self {
+            std,
+            peer_filter: None,
+        }
     }
 
     /// Returns the local socket address of this listener.
@@ -50,30 +54,61 @@ impl TcpListener {
     #[inline]
     pub fn try_clone(&self) -> io::Result<Self> {
         let tcp_listener = self.std.try_clone()?;
-        Ok(unsafe { Self::from_std(tcp_listener) })
+        let mut clone = unsafe { Self::from_std(tcp_listener) };
+        clone.peer_filter = self.peer_filter.clone();
+        Ok(clone)
+    }
+
+    /// Restrict the peers that this listener accepts connections from.
+    ///
+    /// With a filter set, [`accept`] and [`incoming`] close connections from
+    /// peers that the filter doesn't permit, report them to its rejection
+    /// hook, and wait for the next connection. Passing `None` removes the
+    /// filter.
+    ///
+    /// [`accept`]: #method.accept
+    /// [`incoming`]: #method.incoming
+    #[inline]
+    pub fn set_peer_filter(&mut self, peer_filter: Option<PeerFilter>) {
+        self.peer_filter = peer_filter;
+    }
+
+    /// Returns the filter set with [`set_peer_filter`], if any.
+    ///
+    /// [`set_peer_filter`]: #method.set_peer_filter
+    #[inline]
+    pub fn peer_filter(&self) -> Option<&PeerFilter> {
+        self.peer_filter.as_ref()
     }
 
     /// Accept a new incoming connection from this listener.
     ///
-    /// This corresponds to [`std::net::TcpListener::accept`].
+    /// This corresponds to [`std::net::TcpListener::accept`], except that
+    /// connections from peers rejected by the [`peer_filter`] are skipped.
     ///
     /// [`std::net::TcpListener::accept`]: https://doc.rust-lang.org/std/net/struct.TcpListener.html#method.accept
+    /// [`peer_filter`]: #method.peer_filter
     #[inline]
     pub fn accept(&self) -> io::Result<(TcpStream, SocketAddr)> {
-        self.std
-            .accept()
-            .map(|(tcp_stream, addr)| (unsafe { TcpStream::from_std(tcp_stream) }, addr))
+        loop {
+            let (tcp_stream, addr) = self.std.accept()?;
+            if self.check_peer(&addr) {
+                return Ok((unsafe { TcpStream::from_std(tcp_stream) }, addr));
+            }
+        }
     }
 
     /// Returns an iterator over the connections being received on this listener.
     ///
-    /// This corresponds to [`std::net::TcpListener::incoming`].
+    /// This corresponds to [`std::net::TcpListener::incoming`], except that
+    /// connections from peers rejected by the [`peer_filter`] are skipped.
     ///
     /// [`std::net::TcpListener::incoming`]: https://doc.rust-lang.org/std/net/struct.TcpListener.html#method.incoming
+    /// [`peer_filter`]: #method.peer_filter
     #[inline]
     pub fn incoming(&self) -> Incoming {
         let incoming = self.std.incoming();
-        unsafe { Incoming::from_std(incoming) }
+        unsafe { Incoming::from_std_filtered(incoming, self.peer_filter.as_ref()) }
     }
 
     /// Sets the value for the `IP_TTL` option on this socket.
@@ -115,6 +150,13 @@ impl TcpListener {
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
         self.std.set_nonblocking(nonblocking)
     }
+
+    fn check_peer(&self, addr: &SocketAddr) -> bool {
+        match &self.peer_filter {
+            Some(peer_filter) => peer_filter.check_peer(addr),
+            None => true,
+        }
+    }
 }
 
 #[cfg(unix)]
@@ -1,18 +1,32 @@
-use crate::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use crate::net::{Ipv4Addr, Ipv6Addr, Operations, SocketAddr, ToSocketAddrs};
+use cap_primitives::net::{Catalog, NO_SOCKET_ADDRS};
 #[cfg(unix)]
 use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 #[cfg(windows)]
 use std::os::windows::io::{AsRawSocket, FromRawSocket, IntoRawSocket, RawSocket};
-use std::{io, net, time::Duration};
+use std::{
+    io, net,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 /// A UDP socket.
 ///
 /// This corresponds to [`std::net::UdpSocket`].
 ///
-/// Note that this `UdpSocket` has no `bind`, `connect`, or `send_to` methods. To
-/// create a `UdpSocket` bound to an address or to send a message to an address,
+/// Note that this `UdpSocket` has no `bind` or `connect` methods. To
+/// create a `UdpSocket` bound to an address or to connect it to an address,
 /// you must first obtain a [`Catalog`] permitting the address, and then call
-/// [`Catalog::bind_udp_socket`], or [`Catalog::connect_udp_socket`], or
+/// [`Catalog::bind_udp_socket`], or [`Catalog::connect_udp_socket`].
+///
+/// A `UdpSocket` created by [`Catalog::bind_udp_socket`] remembers the
+/// catalog, and its [`send_to`] method only sends to addresses which that
+/// catalog permits. It can also be told to discard datagrams from peers that
+/// the catalog doesn't permit, with [`set_recv_filter`]. Sockets which weren't
+/// created from a catalog can only send with
 /// [`Catalog::send_to_udp_socket_addr`].
 ///
 /// [`std::net::UdpSocket`]: https://doc.rust-lang.org/std/net/struct.UdpSocket.html
@@ -20,8 +34,12 @@ use std::{io, net, time::Duration};
 /// [`Catalog::bind_udp_socket`]: struct.Catalog.html#method.bind_udp_socket
 /// [`Catalog::connect_udp_socket`]: struct.Catalog.html#method.connect_udp_socket
 /// [`Catalog::send_to_udp_socket_addr`]: struct.Catalog.html#method.send_to_udp_socket_addr
+/// [`send_to`]: #method.send_to
+/// [`set_recv_filter`]: #method.set_recv_filter
 pub struct UdpSocket {
     pub(crate) std: net::UdpSocket,
+    catalog: Option<Arc<Catalog>>,
+    recv_filter: AtomicBool,
 }
 
 impl UdpSocket {
@@ -33,27 +51,129 @@ impl UdpSocket {
     /// process has access to.
     #[inline]
     pub unsafe fn from_std(std: net::UdpSocket) -> Self {
-        Self { std }
+        Self {
+            std,
+            catalog: None,
+            recv_filter: AtomicBool::new(false),
+        }
+    }
+
+    /// Constructs a new instance of `Self` from the given `std::net::UdpSocket`
+    /// which was bound from `catalog`.
+    ///
+    /// # Safety
+    ///
+    /// `std::net::UdpSocket` is not sandboxed and may access any address that the host
+    /// process has access to.
+    #[inline]
+    pub(crate) unsafe fn from_std_in(std: net::UdpSocket, catalog: Arc<Catalog>) -> Self {
+        Self {
+            std,
+            catalog: Some(catalog),
+            recv_filter: AtomicBool::new(false),
+        }
+    }
+
+    /// Sends data on the socket to the given address.
+    ///
+    /// This corresponds to [`std::net::UdpSocket::send_to`], but only sends to
+    /// addresses permitted for `Operations::UDP_SEND` by the catalog this socket
+    /// was bound from. Sockets which weren't bound from a catalog can't send
+    /// with this function.
+    ///
+    /// [`std::net::UdpSocket::send_to`]: https://doc.rust-lang.org/std/net/struct.UdpSocket.html#method.send_to
+    #[inline]
+    pub fn send_to<A: ToSocketAddrs>(&self, buf: &[u8], addr: A) -> io::Result<usize> {
+        let catalog = self.catalog()?;
+        let mut addrs = addr.to_socket_addrs()?;
+
+        // `UdpSocket::send_to` only sends to the first address.
+        let addr = addrs
+            .next()
+            .ok_or_else(|| net::UdpSocket::bind(NO_SOCKET_ADDRS).unwrap_err())?;
+        catalog.check_addr(&addr, Operations::UDP_SEND)?;
+        self.std.send_to(buf, addr)
+    }
+
+    /// Sets whether datagrams from peers which the catalog this socket was
+    /// bound from doesn't permit for `Operations::UDP_RECEIVE` are discarded
+    /// by [`recv_from`], [`peek_from`], [`recv`], and [`peek`].
+    ///
+    /// This fails for sockets which weren't bound from a catalog.
+    ///
+    /// [`recv_from`]: #method.recv_from
+    /// [`peek_from`]: #method.peek_from
+    /// [`recv`]: #method.recv
+    /// [`peek`]: #method.peek
+    #[inline]
+    pub fn set_recv_filter(&self, filter: bool) -> io::Result<()> {
+        self.catalog()?;
+        self.recv_filter.store(filter, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Returns whether datagrams from peers outside the catalog this socket
+    /// was bound from are discarded.
+    #[inline]
+    pub fn recv_filter(&self) -> bool {
+        self.recv_filter.load(Ordering::Relaxed)
     }
 
     /// Receives a single datagram message on the socket.
     ///
-    /// This corresponds to [`std::net::UdpSocket::recv_from`].
+    /// This corresponds to [`std::net::UdpSocket::recv_from`]. If
+    /// [`set_recv_filter`] is enabled, datagrams from peers outside the
+    /// socket's catalog are discarded.
     ///
     /// [`std::net::UdpSocket::recv_from`]: https://doc.rust-lang.org/std/net/struct.UdpSocket.html#method.recv_from
+    /// [`set_recv_filter`]: #method.set_recv_filter
     #[inline]
     pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
-        self.std.recv_from(buf)
+        loop {
+            let (n, addr) = self.std.recv_from(buf)?;
+            if self.permits_peer(&addr) {
+                return Ok((n, addr));
+            }
+        }
     }
 
     /// Receives a single datagram message on the socket, without removing it from the queue.
     ///
-    /// This corresponds to [`std::net::UdpSocket::peek_from`].
+    /// This corresponds to [`std::net::UdpSocket::peek_from`]. If
+    /// [`set_recv_filter`] is enabled, datagrams from peers outside the
+    /// socket's catalog are removed from the queue and discarded.
     ///
     /// [`std::net::UdpSocket::peek_from`]: https://doc.rust-lang.org/std/net/struct.UdpSocket.html#method.peek_from
+    /// [`set_recv_filter`]: #method.set_recv_filter
     #[inline]
     pub fn peek_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
-        self.std.peek_from(buf)
+        loop {
+            let (n, addr) = self.std.peek_from(buf)?;
+            if self.permits_peer(&addr) {
+                return Ok((n, addr));
+            }
+            // Discard the datagram. Some platforms report an error when a
+            // datagram doesn't fit in the buffer, which we can ignore here.
+            let _ = self.std.recv_from(&mut []);
+        }
+    }
+
+    fn catalog(&self) -> io::Result<&Catalog> {
+        self.catalog.as_deref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "UdpSocket was not bound from a catalog",
+            )
+        })
+    }
+
+    fn permits_peer(&self, addr: &SocketAddr) -> bool {
+        match &self.catalog {
+            Some(catalog) if self.recv_filter() => {
+                catalog.check_addr(addr, Operations::UDP_RECEIVE).is_ok()
+            }
+            _ => true,
+        }
     }
 
     /// Returns the socket address of the remote peer this socket was connected to.
@@ -84,7 +204,11 @@ impl UdpSocket {
     #[inline]
     pub fn try_clone(&self) -> io::Result<Self> {
         let udp_socket = self.std.try_clone()?;
-        Ok(unsafe { Self::from_std(udp_socket) })
+        Ok(Self {
+            std: udp_socket,
+            catalog: self.catalog.clone(),
+            recv_filter: AtomicBool::new(self.recv_filter()),
+        })
     }
 
     /// Sets the read timeout to the timeout specified.
@@ -294,23 +418,43 @@ impl UdpSocket {
     /// Receives a single datagram message on the socket from the remote address to which it is
     /// connected.
     ///
-    /// This corresponds to [`std::net::UdpSocket::recv`].
+    /// This corresponds to [`std::net::UdpSocket::recv`]. If
+    /// [`set_recv_filter`] is enabled, datagrams from peers outside the
+    /// socket's catalog are discarded, as with [`recv_from`].
     ///
     /// [`std::net::UdpSocket::recv`]: https://doc.rust-lang.org/std/net/struct.UdpSocket.html#method.recv
+    /// [`set_recv_filter`]: #method.set_recv_filter
+    /// [`recv_from`]: #method.recv_from
     #[inline]
     pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
-        self.std.recv(buf)
+        // An unconnected socket receives from any peer, so when filtering,
+        // go through `recv_from` to learn who sent each datagram.
+        if self.recv_filter() {
+            self.recv_from(buf).map(|(n, _addr)| n)
+        } else {
+            self.std.recv(buf)
+        }
     }
 
     /// Receives single datagram on the socket from the remote address to which it is connected,
     /// without removing the message from input queue.
     ///
-    /// This corresponds to [`std::net::UdpSocket::peek`].
+    /// This corresponds to [`std::net::UdpSocket::peek`]. If
+    /// [`set_recv_filter`] is enabled, datagrams from peers outside the
+    /// socket's catalog are removed from the queue and discarded, as with
+    /// [`peek_from`].
     ///
     /// [`std::net::UdpSocket::peek`]: https://doc.rust-lang.org/std/net/struct.UdpSocket.html#method.peek
+    /// [`set_recv_filter`]: #method.set_recv_filter
+    /// [`peek_from`]: #method.peek_from
     #[inline]
     pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
-        self.std.peek(buf)
+        // Likewise, go through `peek_from` when filtering.
+        if self.recv_filter() {
+            self.peek_from(buf).map(|(n, _addr)| n)
+        } else {
+            self.std.peek(buf)
+        }
     }
 
     /// Moves this UDP socket into or out of nonblocking mode.
@@ -0,0 +1,105 @@
+#[macro_use]
+mod sys_common;
+
+use std::io::{Read, Seek, SeekFrom, Write};
+use sys_common::io::tmpdir;
+
+#[test]
+fn create_anonymous_file() {
+    let tmpdir = tmpdir();
+
+    let mut file = check!(tmpdir.create_anonymous_file());
+    check!(file.write_all(b"hidden"));
+    check!(file.seek(SeekFrom::Start(0)));
+    let mut contents = String::new();
+    check!(file.read_to_string(&mut contents));
+    assert_eq!(contents, "hidden");
+
+    // The file doesn't appear in the directory.
+    assert_eq!(check!(tmpdir.entries()).count(), 0);
+    drop(file);
+    assert_eq!(check!(tmpdir.entries()).count(), 0);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn link_file_at() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+
+    let mut file = check!(tmpdir.create_anonymous_file());
+    check!(file.write_all(b"materialized"));
+    check!(tmpdir.link_file_at(&file, "sub/named"));
+    assert_eq!(check!(tmpdir.read_to_string("sub/named")), "materialized");
+
+    // Writes after linking are visible through the name.
+    check!(file.write_all(b"!"));
+    assert_eq!(check!(tmpdir.read_to_string("sub/named")), "materialized!");
+
+    // Existing names aren't replaced.
+    let other = check!(tmpdir.create_anonymous_file());
+    assert!(tmpdir.link_file_at(&other, "sub/named").is_err());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn link_file_at_named() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("existing", b"named"));
+
+    // Files which already have a name can't be given another one.
+    let file = check!(tmpdir.open("existing"));
+    assert_eq!(
+        tmpdir.link_file_at(&file, "second").unwrap_err().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+    assert!(!tmpdir.exists("second"));
+
+    // Including anonymous files which have already been linked.
+    let file = check!(tmpdir.create_anonymous_file());
+    check!(tmpdir.link_file_at(&file, "first"));
+    assert!(tmpdir.link_file_at(&file, "second").is_err());
+    assert!(!tmpdir.exists("second"));
+}
+
+#[test]
+fn link_file_at_sandboxed() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("inner"));
+    let inner = check!(tmpdir.open_dir("inner"));
+
+    let file = check!(inner.create_anonymous_file());
+    assert!(inner.link_file_at(&file, "../escaped").is_err());
+    assert_eq!(check!(tmpdir.entries()).count(), 1);
+}
+
+#[test]
+#[cfg(all(target_os = "linux", feature = "fs_utf8"))]
+fn link_file_at_utf8() {
+    let tmpdir = tmpdir();
+    let dir = cap_std::fs_utf8::Dir::from_cap_std(check!(tmpdir.try_clone()));
+
+    let mut file = check!(dir.create_anonymous_file());
+    check!(file.write_all(b"utf8"));
+    assert_eq!(check!(dir.entries()).count(), 0);
+    check!(dir.link_file_at(&file, "named"));
+    assert_eq!(check!(dir.read_to_string("named")), "utf8");
+}
+
+#[async_std::test]
+async fn async_create_anonymous_file() {
+    use async_std::prelude::*;
+
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+
+    let mut file = check!(dir.create_anonymous_file());
+    check!(file.write_all(b"async").await);
+    check!(file.flush().await);
+    assert_eq!(check!(dir.entries()).count(), 0);
+
+    if cfg!(target_os = "linux") {
+        check!(dir.link_file_at(&file, "named"));
+        assert_eq!(check!(dir.read_to_string("named").await), "async");
+    }
+}
@@ -0,0 +1,122 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::Dir;
+use std::io::Write;
+use sys_common::io::tmpdir;
+
+fn names(dir: &Dir) -> Vec<String> {
+    let mut names = check!(dir.entries())
+        .map(|entry| check!(entry).file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+#[test]
+fn atomic_write_basic() {
+    let tmpdir = tmpdir();
+
+    check!(tmpdir.atomic_write("config", b"first"));
+    assert_eq!(check!(tmpdir.read_to_string("config")), "first");
+
+    check!(tmpdir.atomic_write("config", b"second"));
+    assert_eq!(check!(tmpdir.read_to_string("config")), "second");
+
+    // No temporary files are left behind.
+    assert_eq!(names(&tmpdir), ["config"]);
+
+    check!(tmpdir.create_dir("sub"));
+    check!(tmpdir.atomic_write("sub/file", b"nested"));
+    assert_eq!(check!(tmpdir.read_to_string("sub/file")), "nested");
+}
+
+#[test]
+fn atomic_file_commit() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("config", b"old"));
+
+    let mut file = check!(tmpdir.create_atomic("config"));
+    check!(file.write_all(b"new "));
+    check!(file.write_all(b"contents"));
+
+    // Nothing changes until the file is committed.
+    assert_eq!(check!(tmpdir.read_to_string("config")), "old");
+    check!(file.commit());
+    assert_eq!(check!(tmpdir.read_to_string("config")), "new contents");
+    assert_eq!(names(&tmpdir), ["config"]);
+}
+
+#[test]
+fn atomic_file_drop() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("config", b"old"));
+
+    let mut file = check!(tmpdir.create_atomic("config"));
+    check!(file.write_all(b"discarded"));
+    drop(file);
+
+    assert_eq!(check!(tmpdir.read_to_string("config")), "old");
+    assert_eq!(names(&tmpdir), ["config"]);
+
+    // Dropping an `AtomicFile` for a new file doesn't create it.
+    drop(check!(tmpdir.create_atomic("new")));
+    assert_eq!(names(&tmpdir), ["config"]);
+}
+
+#[test]
+#[cfg(unix)]
+fn atomic_write_permissions() {
+    use cap_std::fs::Permissions;
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("secret", b"old"));
+    check!(tmpdir.set_permissions("secret", Permissions::from_mode(0o600)));
+
+    check!(tmpdir.atomic_write("secret", b"new"));
+    assert_eq!(
+        check!(tmpdir.metadata("secret")).permissions().mode() & 0o777,
+        0o600
+    );
+}
+
+#[test]
+fn atomic_write_sandboxed() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("inner"));
+    let inner = check!(tmpdir.open_dir("inner"));
+
+    assert!(inner.atomic_write("../outside", b"escape").is_err());
+    assert!(inner.atomic_write("..", b"escape").is_err());
+    assert!(inner.atomic_write("", b"empty").is_err());
+    assert_eq!(names(&tmpdir), ["inner"]);
+    assert!(names(&inner).is_empty());
+}
+
+#[async_std::test]
+async fn async_atomic_write() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+
+    check!(dir.atomic_write("config", b"first").await);
+    check!(dir.atomic_write("config", b"second").await);
+    assert_eq!(check!(dir.read_to_string("config").await), "second");
+    assert_eq!(check!(dir.entries()).count(), 1);
+}
+
+#[cfg(feature = "fs_utf8")]
+#[test]
+fn atomic_write_utf8() {
+    let tmpdir = tmpdir();
+    let dir = cap_std::fs_utf8::Dir::from_cap_std(check!(tmpdir.try_clone()));
+
+    check!(dir.atomic_write("file", b"one"));
+    assert_eq!(check!(dir.read_to_string("file")), "one");
+
+    let mut file = check!(dir.create_atomic("file"));
+    check!(file.write_all(b"two"));
+    assert_eq!(check!(dir.read_to_string("file")), "one");
+    check!(file.commit());
+    assert_eq!(check!(dir.read_to_string("file")), "two");
+}
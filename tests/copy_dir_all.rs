@@ -0,0 +1,182 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{CopyDirOptions, Dir};
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use sys_common::{io::tmpdir, symlink_supported};
+
+fn make_tree(dir: &Dir) {
+    check!(dir.create_dir_all("src/a/b"));
+    check!(dir.create_dir("src/empty"));
+    check!(dir.write("src/a/b/c.txt", b"hello"));
+    check!(dir.write("src/d.txt", b"world!"));
+}
+
+#[test]
+fn copy_dir_all_basic() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    assert_eq!(check!(tmpdir.copy_dir_all("src", &tmpdir, "dst")), 11);
+    assert_eq!(check!(tmpdir.read_to_string("dst/a/b/c.txt")), "hello");
+    assert_eq!(check!(tmpdir.read_to_string("dst/d.txt")), "world!");
+    assert!(check!(tmpdir.metadata("dst/empty")).is_dir());
+
+    // The destination must not already exist.
+    assert!(tmpdir.copy_dir_all("src", &tmpdir, "dst").is_err());
+
+    // The source must be a directory.
+    assert!(tmpdir.copy_dir_all("src/d.txt", &tmpdir, "other").is_err());
+}
+
+#[test]
+fn copy_dir_all_between_dirs() {
+    let from = tmpdir();
+    let to = tmpdir();
+    make_tree(&from);
+
+    check!(from.copy_dir_all("src/a", &to, "copy"));
+    assert_eq!(check!(to.read_to_string("copy/b/c.txt")), "hello");
+    assert!(!to.exists("src"));
+
+    // Copying a directory into itself doesn't recurse forever.
+    check!(from.copy_dir_all("src", &from, "src/a/nested"));
+    assert_eq!(check!(from.read_to_string("src/a/nested/d.txt")), "world!");
+    assert!(!from.exists("src/a/nested/a/nested"));
+}
+
+#[test]
+fn copy_dir_all_progress() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let mut options = CopyDirOptions::new();
+    let progress = Arc::clone(&seen);
+    options.progress(move |p| {
+        progress.lock().unwrap().push((
+            p.path().to_path_buf(),
+            p.bytes(),
+            p.total_bytes(),
+            p.entries(),
+        ));
+    });
+    assert_eq!(
+        check!(tmpdir.copy_dir_all_with("src", &tmpdir, "dst", &options)),
+        11
+    );
+
+    let mut seen = seen.lock().unwrap().clone();
+    assert_eq!(seen.len(), 5);
+    assert_eq!(seen.last().unwrap().2, 11);
+    assert_eq!(seen.last().unwrap().3, 5);
+
+    // Directories are reported after their contents.
+    let position = |path: &str| {
+        seen.iter()
+            .position(|(p, ..)| p == &PathBuf::from(path))
+            .unwrap()
+    };
+    assert!(position("a/b/c.txt") < position("a/b"));
+    assert!(position("a/b") < position("a"));
+
+    seen.sort();
+    assert_eq!(
+        seen.iter()
+            .map(|(path, bytes, ..)| (path.clone(), *bytes))
+            .collect::<Vec<_>>(),
+        vec![
+            (PathBuf::from("a"), 0),
+            (PathBuf::from("a/b"), 0),
+            (PathBuf::from("a/b/c.txt"), 5),
+            (PathBuf::from("d.txt"), 6),
+            (PathBuf::from("empty"), 0),
+        ]
+    );
+}
+
+#[test]
+fn copy_dir_all_symlinks() {
+    if !symlink_supported() {
+        return;
+    }
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+    check!(tmpdir.symlink("d.txt", "src/link"));
+    check!(tmpdir.symlink("a", "src/dir_link"));
+    check!(tmpdir.symlink("missing", "src/dangling"));
+
+    check!(tmpdir.copy_dir_all("src", &tmpdir, "dst"));
+    for (link, target) in &[
+        ("link", "d.txt"),
+        ("dir_link", "a"),
+        ("dangling", "missing"),
+    ] {
+        let path = PathBuf::from("dst").join(link);
+        assert!(check!(tmpdir.symlink_metadata(&path))
+            .file_type()
+            .is_symlink());
+        assert_eq!(check!(tmpdir.read_link(&path)), PathBuf::from(target));
+    }
+}
+
+#[test]
+#[cfg(unix)]
+fn copy_dir_all_attributes() {
+    use cap_fs_ext::DirExt;
+    use cap_std::{fs::Permissions, time::SystemClock};
+    use std::{os::unix::fs::PermissionsExt, time::Duration};
+
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+    check!(tmpdir.set_permissions("src/d.txt", Permissions::from_mode(0o600)));
+    check!(tmpdir.set_permissions("src/a", Permissions::from_mode(0o750)));
+    let mtime = SystemClock::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    check!(tmpdir.set_times("src/d.txt", None, Some(mtime.into())));
+    check!(tmpdir.set_times("src/a", None, Some(mtime.into())));
+
+    let mode = |path: &str| check!(tmpdir.metadata(path)).permissions().mode() & 0o777;
+    let modified = |path: &str| check!(check!(tmpdir.metadata(path)).modified());
+
+    let mut options = CopyDirOptions::new();
+    options.preserve_times(true);
+    check!(tmpdir.copy_dir_all_with("src", &tmpdir, "preserved", &options));
+    assert_eq!(mode("preserved/d.txt"), 0o600);
+    assert_eq!(mode("preserved/a"), 0o750);
+    assert_eq!(modified("preserved/d.txt"), mtime);
+    assert_eq!(modified("preserved/a"), mtime);
+
+    options.preserve_permissions(false).preserve_times(false);
+    check!(tmpdir.copy_dir_all_with("src", &tmpdir, "fresh", &options));
+    check!(tmpdir.write("new.txt", b""));
+    check!(tmpdir.create_dir("new"));
+    assert_eq!(mode("fresh/d.txt"), mode("new.txt"));
+    assert_eq!(mode("fresh/a"), mode("new"));
+    assert_ne!(modified("fresh/d.txt"), mtime);
+}
+
+#[async_std::test]
+async fn async_copy_dir_all() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+    check!(dir.create_dir_all("src/a"));
+    check!(dir.write("src/a/b.txt", b"async").await);
+
+    assert_eq!(check!(dir.copy_dir_all("src", &dir, "dst").await), 5);
+    assert_eq!(check!(dir.read_to_string("dst/a/b.txt").await), "async");
+}
+
+#[cfg(feature = "fs_utf8")]
+#[test]
+fn copy_dir_all_utf8() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+    let dir = cap_std::fs_utf8::Dir::from_cap_std(check!(tmpdir.try_clone()));
+
+    assert_eq!(check!(dir.copy_dir_all("src", &dir, "dst")), 11);
+    assert_eq!(check!(dir.read_to_string("dst/a/b/c.txt")), "hello");
+    assert!(dir.copy_dir_all("src", &dir, "dst").is_err());
+}
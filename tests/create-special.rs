@@ -0,0 +1,69 @@
+// This file contains tests for `cap_fs_ext::DirExt::create_fifo` and
+// `cap_fs_ext::DirExt::create_device_node`.
+#![cfg(unix)]
+
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::{DirExt, FileTypeExt};
+use std::os::unix::fs::PermissionsExt;
+use sys_common::io::tmpdir;
+
+#[test]
+fn create_fifo() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+    check!(tmpdir.create_fifo("dir/fifo", 0o600));
+
+    let metadata = check!(tmpdir.symlink_metadata("dir/fifo"));
+    assert!(metadata.file_type().is_fifo());
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+
+    // Existing names aren't replaced.
+    error_contains!(tmpdir.create_fifo("dir/fifo", 0o600), "exists");
+
+    // Modes with bits other than permissions are rejected.
+    assert!(tmpdir.create_fifo("bad", 0o170_000).is_err());
+    assert!(!tmpdir.exists("bad"));
+}
+
+#[test]
+fn create_fifo_sandboxed() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("inner"));
+    check!(tmpdir.symlink("..", "inner/up"));
+    let inner = check!(tmpdir.open_dir("inner"));
+
+    assert!(inner.create_fifo("../fifo", 0o600).is_err());
+    assert!(inner.create_fifo("up/fifo", 0o600).is_err());
+    assert!(inner.create_fifo("/tmp/fifo", 0o600).is_err());
+    assert!(!tmpdir.exists("fifo"));
+}
+
+#[test]
+fn create_device_node() {
+    let tmpdir = tmpdir();
+
+    // Only block and character devices may be created.
+    assert!(unsafe { tmpdir.create_device_node("fifo", libc::S_IFIFO as u32 | 0o600, 0) }.is_err());
+    assert!(unsafe { tmpdir.create_device_node("file", libc::S_IFREG as u32 | 0o600, 0) }.is_err());
+
+    // Creating real devices needs privileges, so accept a permission error.
+    // Device 1:3 is /dev/null on Linux.
+    #[cfg(target_os = "linux")]
+    match unsafe { tmpdir.create_device_node("null", libc::S_IFCHR | 0o600, libc::makedev(1, 3)) } {
+        Ok(()) => {
+            let file_type = check!(tmpdir.symlink_metadata("null")).file_type();
+            assert!(file_type.is_char_device());
+        }
+        Err(e) => assert_eq!(e.raw_os_error(), Some(libc::EPERM)),
+    }
+}
+
+#[async_std::test]
+async fn async_create_fifo() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+    check!(dir.create_fifo("fifo", 0o644));
+    assert!(check!(dir.symlink_metadata("fifo")).file_type().is_fifo());
+}
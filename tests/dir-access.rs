@@ -0,0 +1,337 @@
+// This file contains tests for `Dir::read_only`, `Dir::append_only`, and
+// `Dir::create_only`, including their effect on the `cap_fs_ext` extension
+// traits.
+
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::{DirAccess, OpenOptions};
+use std::io::{self, Write};
+use sys_common::{io::tmpdir, symlink_supported};
+
+fn assert_denied<T: std::fmt::Debug>(result: io::Result<T>) {
+    assert_eq!(result.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+fn read_only() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+    check!(tmpdir.write("file", b"hello"));
+    check!(tmpdir.write("sub/file", b"world"));
+
+    let dir = check!(tmpdir.read_only());
+    assert_eq!(tmpdir.access(), DirAccess::ReadWrite);
+    assert_eq!(dir.access(), DirAccess::ReadOnly);
+
+    // Reading is fine.
+    assert_eq!(check!(dir.read_to_string("file")), "hello");
+    check!(dir.metadata("sub"));
+    check!(dir.open_with("file", OpenOptions::new().read(true)));
+
+    // Modifying isn't.
+    assert_denied(dir.create("new"));
+    assert_denied(dir.write("file", b"changed"));
+    assert_denied(dir.open_with("file", OpenOptions::new().write(true)));
+    assert_denied(dir.open_with("file", OpenOptions::new().append(true)));
+    assert_denied(dir.open_with("new", OpenOptions::new().read(true).create(true)));
+    assert_denied(dir.create_dir("new_dir"));
+    assert_denied(dir.remove_file("file"));
+    assert_denied(dir.remove_dir("sub"));
+    assert_denied(dir.remove_dir_all("sub"));
+    assert_denied(dir.rename("file", &dir, "renamed"));
+    assert_denied(dir.rename("file", &tmpdir, "renamed"));
+    assert_denied(tmpdir.rename("file", &dir, "renamed"));
+    assert_denied(dir.copy("file", &dir, "copied"));
+    assert_denied(dir.hard_link("file", &dir, "linked"));
+    assert_denied(dir.set_permissions("file", check!(tmpdir.metadata("file")).permissions()));
+    assert_denied(dir.create_anonymous_file());
+    assert_denied(dir.atomic_write("file", b"changed"));
+    if symlink_supported() {
+        assert_denied(dir.symlink("file", "link"));
+    }
+    assert_eq!(check!(tmpdir.read_to_string("file")), "hello");
+    assert!(!tmpdir.exists("new"));
+    assert!(!tmpdir.exists("new_dir"));
+
+    // Copying out of a read-only `Dir` is fine.
+    check!(dir.copy("file", &tmpdir, "copied"));
+
+    // Subdirectories, clones, and entries inherit the restriction.
+    let sub = check!(dir.open_dir("sub"));
+    assert_eq!(sub.access(), DirAccess::ReadOnly);
+    assert_denied(sub.write("file", b"changed"));
+    assert_denied(check!(dir.try_clone()).remove_file("file"));
+    for entry in check!(dir.entries()) {
+        let entry = check!(entry);
+        if entry.file_name() == "sub" {
+            assert_eq!(check!(entry.open_dir()).access(), DirAccess::ReadOnly);
+            assert_denied(entry.remove_dir());
+        } else {
+            check!(entry.open());
+            assert_denied(entry.open_with(OpenOptions::new().write(true)));
+            assert_denied(entry.remove_file());
+        }
+    }
+
+    // A view can't be widened again.
+    assert_eq!(check!(dir.append_only()).access(), DirAccess::ReadOnly);
+}
+
+#[test]
+fn append_only() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("log", b"one\n"));
+
+    let dir = check!(tmpdir.append_only());
+    assert_eq!(dir.access(), DirAccess::AppendOnly);
+
+    let mut file = check!(dir.open_with("log", OpenOptions::new().append(true)));
+    check!(file.write_all(b"two\n"));
+    let mut file = check!(dir.open_with("new_log", OpenOptions::new().append(true).create(true)));
+    check!(file.write_all(b"three\n"));
+    check!(dir.create_dir("sub"));
+    assert_eq!(check!(dir.open_dir("sub")).access(), DirAccess::AppendOnly);
+
+    assert_denied(dir.create("log"));
+    assert_denied(dir.open_with("log", OpenOptions::new().write(true)));
+    assert_denied(dir.open_with("log", OpenOptions::new().append(true).truncate(true)));
+    assert_denied(dir.remove_file("log"));
+    assert_denied(dir.rename("log", &dir, "renamed"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        assert_denied(dir.open_with(
+            "log",
+            OpenOptions::new().append(true).custom_flags(libc::O_TRUNC),
+        ));
+    }
+
+    assert_eq!(check!(tmpdir.read_to_string("log")), "one\ntwo\n");
+    assert_eq!(check!(tmpdir.read_to_string("new_log")), "three\n");
+}
+
+#[test]
+fn create_only() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("existing", b"hello"));
+
+    let dir = check!(tmpdir.create_only());
+    assert_eq!(dir.access(), DirAccess::CreateOnly);
+
+    let mut file = check!(dir.open_with("new", OpenOptions::new().write(true).create_new(true)));
+    check!(file.write_all(b"world"));
+    check!(dir.create_dir("sub"));
+
+    assert_denied(dir.open_with("existing", OpenOptions::new().append(true)));
+    assert_denied(dir.open_with("other", OpenOptions::new().write(true).create(true)));
+    assert_denied(dir.write("existing", b"changed"));
+    assert_denied(dir.remove_file("new"));
+    assert!(dir
+        .open_with("existing", OpenOptions::new().write(true).create_new(true))
+        .is_err());
+
+    assert_eq!(check!(tmpdir.read_to_string("existing")), "hello");
+    assert_eq!(check!(tmpdir.read_to_string("new")), "world");
+}
+
+/// Assert that the modifying `cap_fs_ext` methods are denied in `dir`.
+fn assert_ext_denied(dir: &cap_std::fs::Dir) {
+    use cap_fs_ext::{DirExt, SystemTimeSpec::SymbolicNow};
+
+    assert_denied(DirExt::set_atime(dir, "file", SymbolicNow));
+    assert_denied(DirExt::set_mtime(dir, "file", SymbolicNow));
+    assert_denied(DirExt::set_times(
+        dir,
+        "file",
+        Some(SymbolicNow),
+        Some(SymbolicNow),
+    ));
+    assert_denied(DirExt::set_symlink_times(
+        dir,
+        "file",
+        Some(SymbolicNow),
+        None,
+    ));
+    assert_denied(DirExt::symlink(dir, "file", "link"));
+    assert_denied(DirExt::symlink_file(dir, "file", "link"));
+    assert_denied(DirExt::symlink_dir(dir, "sub", "link"));
+    #[cfg(unix)]
+    {
+        use cap_fs_ext::FollowSymlinks;
+
+        assert_denied(DirExt::set_owner(
+            dir,
+            "file",
+            None,
+            None,
+            FollowSymlinks::Yes,
+        ));
+        assert_denied(unsafe { DirExt::create_device_node(dir, "dev", 0o20_600, 0) });
+    }
+    #[cfg(target_os = "linux")]
+    {
+        use cap_fs_ext::{DirXattrExt, FollowSymlinks};
+
+        assert_denied(dir.setxattr("file", "user.color", b"blue", FollowSymlinks::Yes));
+        assert_denied(dir.removexattr("file", "user.color", FollowSymlinks::Yes));
+    }
+    assert!(!dir.exists("link"));
+    assert!(!dir.exists("dev"));
+}
+
+#[test]
+fn read_only_ext() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+    check!(tmpdir.write("file", b"hello"));
+
+    let dir = check!(tmpdir.read_only());
+    assert_ext_denied(&dir);
+    #[cfg(unix)]
+    {
+        use cap_fs_ext::DirExt;
+
+        assert_denied(dir.create_fifo("fifo", 0o600));
+        assert!(!tmpdir.exists("fifo"));
+    }
+}
+
+#[test]
+fn append_only_ext() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+    check!(tmpdir.write("file", b"hello"));
+
+    let dir = check!(tmpdir.append_only());
+    assert_ext_denied(&dir);
+    assert_ext_denied(&check!(dir.create_only()));
+
+    // Creating new entries is still permitted.
+    #[cfg(unix)]
+    {
+        use cap_fs_ext::DirExt;
+
+        check!(dir.create_fifo("fifo", 0o600));
+    }
+}
+
+/// Assert that the metadata-changing methods of `file` are denied.
+fn assert_file_denied(file: &cap_std::fs::File) {
+    let permissions = check!(file.metadata()).permissions();
+    assert_denied(file.set_permissions(permissions));
+    #[cfg(unix)]
+    assert_denied(file.set_owner(None, None));
+    #[cfg(target_os = "linux")]
+    {
+        use cap_fs_ext::FileXattrExt;
+
+        assert_denied(file.setxattr("user.color", b"blue"));
+        assert_denied(file.removexattr("user.color"));
+    }
+}
+
+#[test]
+fn read_only_files() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+    check!(tmpdir.write("file", b"hello"));
+    check!(tmpdir.write("sub/file", b"world"));
+
+    let dir = check!(tmpdir.read_only());
+    let file = check!(dir.open("file"));
+    assert_eq!(file.access(), DirAccess::ReadOnly);
+    assert_file_denied(&file);
+    assert_file_denied(&check!(file.try_clone()));
+    assert_file_denied(&check!(check!(dir.open_dir("sub")).open("file")));
+    for entry in check!(dir.entries()) {
+        let entry = check!(entry);
+        if entry.file_name() == "file" {
+            assert_file_denied(&check!(entry.open()));
+        }
+    }
+
+    let create_only = check!(tmpdir.create_only());
+    assert_file_denied(&check!(create_only.open("file")));
+    let new = check!(create_only.open_with("new", OpenOptions::new().write(true).create_new(true)));
+    assert_file_denied(&new);
+
+    // Files opened from an unrestricted `Dir` aren't affected.
+    let file = check!(tmpdir.open("file"));
+    assert_eq!(file.access(), DirAccess::ReadWrite);
+    check!(file.set_permissions(check!(file.metadata()).permissions()));
+    #[cfg(unix)]
+    check!(file.set_owner(None, None));
+}
+
+#[test]
+#[cfg(feature = "fs_utf8")]
+fn read_only_files_utf8() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+
+    let dir = cap_std::fs_utf8::Dir::from_cap_std(check!(tmpdir.read_only()));
+    let file = check!(dir.open("file"));
+    assert_eq!(file.access(), DirAccess::ReadOnly);
+    assert_denied(file.set_permissions(check!(file.metadata()).permissions()));
+    #[cfg(unix)]
+    assert_denied(file.set_owner(None, None));
+}
+
+#[async_std::test]
+async fn async_read_only() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+    check!(dir.write("file", b"hello").await);
+    check!(dir.create_dir("sub"));
+
+    let read_only = check!(dir.read_only());
+    assert_eq!(check!(read_only.read_to_string("file").await), "hello");
+    assert_denied(read_only.write("file", b"changed").await);
+    assert_denied(read_only.remove_file("file"));
+    assert_denied(read_only.open_dir("sub").unwrap().create_dir("new"));
+}
+
+#[async_std::test]
+async fn async_read_only_ext() {
+    use cap_fs_ext::{DirExt, SystemTimeSpec::SymbolicNow};
+
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+    check!(dir.write("file", b"hello").await);
+
+    let read_only = check!(dir.read_only());
+    assert_denied(read_only.set_times("file", Some(SymbolicNow), Some(SymbolicNow)));
+    assert_denied(DirExt::symlink(&read_only, "file", "link"));
+    #[cfg(unix)]
+    assert_denied(read_only.create_fifo("fifo", 0o600));
+    #[cfg(target_os = "linux")]
+    {
+        use cap_fs_ext::{DirXattrExt, FollowSymlinks};
+
+        assert_denied(read_only.setxattr("file", "user.color", b"blue", FollowSymlinks::Yes));
+    }
+    check!(dir.set_times("file", Some(SymbolicNow), Some(SymbolicNow)));
+}
+
+#[async_std::test]
+async fn async_read_only_files() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+    check!(dir.write("file", b"hello").await);
+
+    let read_only = check!(dir.read_only());
+    let file = check!(read_only.open("file"));
+    assert_eq!(file.access(), DirAccess::ReadOnly);
+    let permissions = check!(file.metadata().await).permissions();
+    assert_denied(file.set_permissions(permissions).await);
+    #[cfg(unix)]
+    assert_denied(file.set_owner(None, None));
+    #[cfg(target_os = "linux")]
+    {
+        use cap_fs_ext::FileXattrExt;
+
+        assert_denied(file.setxattr("user.color", b"blue"));
+        assert_denied(file.removexattr("user.color"));
+    }
+}
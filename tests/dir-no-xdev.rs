@@ -0,0 +1,154 @@
+// This file contains tests for `Dir::no_xdev`, including its effect on
+// subdirectories, iteration, and the `cap_fs_ext` extension traits.
+#![cfg(unix)]
+
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::Dir;
+use std::{io, path::Path};
+use sys_common::{io::tmpdir, symlink_supported};
+
+fn assert_denied<T: std::fmt::Debug>(result: io::Result<T>) {
+    assert_eq!(result.unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+fn no_xdev_same_device() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a/b"));
+    check!(tmpdir.write("a/b/file", b"hello"));
+
+    let dir = check!(tmpdir.no_xdev());
+    assert!(!tmpdir.is_no_xdev());
+    assert!(dir.is_no_xdev());
+
+    // Everything on the same filesystem works as before.
+    assert_eq!(check!(dir.read_to_string("a/b/file")), "hello");
+    assert!(check!(dir.metadata("a/b")).is_dir());
+    check!(dir.create_dir_all("c/d"));
+    check!(dir.write("c/d/file", b"world"));
+    check!(dir.rename("c/d/file", &dir, "c/file"));
+    check!(dir.copy("c/file", &dir, "c/copy"));
+    check!(dir.copy_dir_all("a", &dir, "e"));
+    assert_eq!(check!(dir.read_to_string("e/b/file")), "hello");
+    assert_eq!(check!(dir.canonicalize("a/../c/copy")), Path::new("c/copy"));
+    assert_eq!(check!(dir.read_dir("c")).count(), 3);
+    assert_eq!(check!(dir.walk()).count(), 10);
+    assert_eq!(check!(dir.glob("*/b/*")).count(), 2);
+    check!(dir.remove_file("c/copy"));
+    check!(dir.remove_dir_all("c"));
+    assert!(!dir.exists("c"));
+
+    if symlink_supported() {
+        // Relative symlinks are resolved from the directory they're in, and
+        // may lead anywhere inside `dir`.
+        check!(dir.symlink("../a/b/file", "e/link"));
+        assert!(check!(dir.metadata("e/link")).is_file());
+        assert!(check!(dir.symlink_metadata("e/link"))
+            .file_type()
+            .is_symlink());
+        assert_eq!(check!(dir.read_to_string("e/link")), "hello");
+        assert_eq!(check!(dir.read_link("e/link")), Path::new("../a/b/file"));
+
+        // But escaping is still an escape attempt.
+        check!(dir.symlink("../../..", "a/escape"));
+        assert!(dir.metadata("a/escape").is_err());
+        assert!(dir.open("a/escape").is_err());
+    }
+
+    // Escaping `dir` is still an escape attempt.
+    let sub = check!(dir.open_dir("a"));
+    assert!(sub.open("../a/b/file").is_err());
+    assert!(sub.metadata("../a").is_err());
+}
+
+#[test]
+fn no_xdev_is_inherited() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a/b"));
+
+    let dir = check!(tmpdir.no_xdev());
+    assert!(check!(dir.open_dir("a")).is_no_xdev());
+    assert!(check!(dir.read_only()).is_no_xdev());
+    assert!(check!(dir.try_clone()).is_no_xdev());
+    for entry in check!(dir.entries()) {
+        assert!(check!(check!(entry).open_dir()).is_no_xdev());
+    }
+    for entry in check!(dir.walk()) {
+        let entry = check!(entry);
+        assert!(check!(entry.dir_entry().open_dir()).is_no_xdev());
+    }
+
+    assert!(!check!(tmpdir.open_dir("a")).is_no_xdev());
+}
+
+/// Mount points can't be created in an unprivileged test, so use "/proc",
+/// which is always its own mount on Linux.
+#[cfg(target_os = "linux")]
+#[test]
+fn no_xdev_mount_point() {
+    use cap_fs_ext::{DirExt, MetadataExt};
+
+    let ambient = check!(unsafe { Dir::open_ambient_dir("/") });
+    if check!(ambient.dir_metadata()).dev() == check!(ambient.metadata("proc")).dev() {
+        return;
+    }
+    let root = check!(ambient.no_xdev());
+
+    // Without `no_xdev`, crossing into "/proc" is fine.
+    check!(ambient.open("proc/version"));
+    check!(ambient.open_dir("proc"));
+
+    // With it, every way of resolving a path through "/proc" fails.
+    assert_denied(root.open("proc/version"));
+    assert_denied(root.open("proc/../proc/version"));
+    assert_denied(root.open_dir("proc"));
+    assert_denied(root.metadata("proc"));
+    assert_denied(root.metadata("proc/self"));
+    assert_denied(root.symlink_metadata("proc"));
+    assert_denied(root.read_dir("proc"));
+    assert_denied(root.read_link("proc/self"));
+    assert_denied(root.canonicalize("proc"));
+    assert_denied(root.remove_file("proc/version"));
+    assert_denied(root.remove_dir("proc/self"));
+    assert_denied(root.remove_dir_all("proc/self"));
+    assert_denied(root.rename("proc/version", &root, "proc/other"));
+    assert_denied(root.create_dir("proc/new"));
+    assert_denied(root.set_times("proc/version", None, None));
+    assert!(!root.exists("proc"));
+    assert!(!root.exists("proc/version"));
+
+    // Subdirectories inherit it.
+    let sub = check!(root.open_dir("."));
+    assert_denied(sub.open_dir("proc"));
+
+    // As do the entries of `read_dir`.
+    let entry = check!(root.entries())
+        .map(|entry| check!(entry))
+        .find(|entry| entry.file_name() == "proc")
+        .expect("\"/\" should contain \"proc\"");
+    assert_denied(entry.open_dir());
+    assert_denied(entry.metadata());
+
+    // `walk` yields "/proc" itself, but fails to descend into it.
+    let mut walk = check!(root.walk());
+    walk.max_depth(2);
+    let mut saw_proc = false;
+    while let Some(entry) = walk.next() {
+        match entry {
+            Ok(entry) if entry.path() == "proc" => {
+                saw_proc = true;
+                assert_denied(walk.next().unwrap());
+            }
+            Ok(entry) => assert!(!entry.path().starts_with("proc")),
+            Err(_) => {}
+        }
+    }
+    assert!(saw_proc);
+
+    // And `glob` can't match anything inside it.
+    for path in check!(root.glob("proc/*")) {
+        assert_denied(path);
+    }
+}
@@ -0,0 +1,43 @@
+// This file contains a test that dropping a `cap_fs_ext::AsyncDirWatcher`
+// while it's waiting for events closes its inotify instance. It's separate
+// from the other watcher tests so that nothing else in the process creates
+// inotify instances while it's counting them.
+#![cfg(target_os = "linux")]
+
+#[macro_use]
+mod sys_common;
+
+use async_std::stream::StreamExt;
+use cap_fs_ext::AsyncDirWatcher;
+use std::{fs, io, time::Duration};
+
+/// Counts the inotify instances open in this process.
+fn inotify_count() -> io::Result<usize> {
+    let mut count = 0;
+    for entry in fs::read_dir("/proc/self/fd")? {
+        // An entry may be closed before it's read; skip it.
+        if let Ok(target) = fs::read_link(entry?.path()) {
+            if target.as_os_str() == "anon_inode:inotify" {
+                count += 1;
+            }
+        }
+    }
+    Ok(count)
+}
+
+#[async_std::test]
+async fn async_drop_while_waiting() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+    let before = check!(inotify_count());
+
+    let mut watcher = check!(AsyncDirWatcher::new(&dir, "."));
+    let timeout = Duration::from_millis(10);
+    assert!(async_std::future::timeout(timeout, watcher.next())
+        .await
+        .is_err());
+    assert_eq!(check!(inotify_count()), before + 1);
+
+    drop(watcher);
+    assert_eq!(check!(inotify_count()), before);
+}
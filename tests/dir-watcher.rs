@@ -0,0 +1,149 @@
+// This file contains tests for `cap_fs_ext::DirWatcher` and
+// `cap_fs_ext::AsyncDirWatcher`.
+#![cfg(target_os = "linux")]
+
+#[macro_use]
+mod sys_common;
+
+use async_std::stream::StreamExt;
+use cap_fs_ext::{AsyncDirWatcher, DirEvent, DirEventKind, DirWatcher};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use sys_common::{io::tmpdir, symlink_supported};
+
+fn next(watcher: &mut DirWatcher) -> DirEvent {
+    check!(watcher.next().unwrap())
+}
+
+#[test]
+fn watch_dir() {
+    let tmpdir = tmpdir();
+    let mut watcher = check!(DirWatcher::new(&tmpdir, "."));
+
+    check!(tmpdir.write("file", b"hello"));
+    let event = next(&mut watcher);
+    assert_eq!(event.kind(), &DirEventKind::Created);
+    assert_eq!(event.path(), Path::new("file"));
+    assert!(!event.is_dir());
+    let event = next(&mut watcher);
+    assert_eq!(event.kind(), &DirEventKind::Modified);
+    assert_eq!(event.path(), Path::new("file"));
+
+    check!(tmpdir.create_dir("sub"));
+    let event = next(&mut watcher);
+    assert_eq!(event.kind(), &DirEventKind::Created);
+    assert_eq!(event.path(), Path::new("sub"));
+    assert!(event.is_dir());
+
+    check!(tmpdir.rename("file", &tmpdir, "renamed"));
+    let event = next(&mut watcher);
+    assert_eq!(
+        event.kind(),
+        &DirEventKind::Renamed {
+            from: PathBuf::from("file")
+        }
+    );
+    assert_eq!(event.path(), Path::new("renamed"));
+
+    // Moving out of the watched directory looks like a removal.
+    check!(tmpdir.rename("renamed", &tmpdir, "sub/moved"));
+    let event = next(&mut watcher);
+    assert_eq!(event.kind(), &DirEventKind::Removed);
+    assert_eq!(event.path(), Path::new("renamed"));
+
+    let mut perms = check!(tmpdir.metadata("sub")).permissions();
+    perms.set_readonly(true);
+    check!(tmpdir.set_permissions("sub", perms));
+    let event = next(&mut watcher);
+    assert_eq!(event.kind(), &DirEventKind::AttributesChanged);
+    assert_eq!(event.path(), Path::new("sub"));
+}
+
+#[test]
+fn watch_subdir() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a/b"));
+    let mut watcher = check!(DirWatcher::new(&tmpdir, "./a/b"));
+
+    check!(tmpdir.write("a/b/file", b""));
+    let event = next(&mut watcher);
+    assert_eq!(event.kind(), &DirEventKind::Created);
+    assert_eq!(event.path(), Path::new("a/b/file"));
+
+    check!(tmpdir.remove_file("a/b/file"));
+    let event = next(&mut watcher);
+    assert_eq!(event.kind(), &DirEventKind::Removed);
+    assert_eq!(event.path(), Path::new("a/b/file"));
+
+    // Removing the watched directory itself is reported with its own path.
+    check!(tmpdir.remove_dir("a/b"));
+    let event = next(&mut watcher);
+    assert_eq!(event.kind(), &DirEventKind::Removed);
+    assert_eq!(event.path(), Path::new("a/b"));
+}
+
+#[test]
+fn watch_escape() {
+    if !symlink_supported() {
+        return;
+    }
+
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+    let sub = check!(tmpdir.open_dir("sub"));
+
+    assert!(DirWatcher::new(&sub, "..").is_err());
+    assert!(DirWatcher::new(&sub, "/").is_err());
+
+    check!(sub.symlink("..", "parent"));
+    assert!(DirWatcher::new(&sub, "parent").is_err());
+
+    check!(sub.symlink(".", "self"));
+    let mut watcher = check!(DirWatcher::new(&sub, "self"));
+    check!(sub.write("file", b""));
+    let event = next(&mut watcher);
+    assert_eq!(event.kind(), &DirEventKind::Created);
+    assert_eq!(event.path(), Path::new("self/file"));
+}
+
+#[async_std::test]
+async fn async_watch_dir() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+    let mut watcher = check!(AsyncDirWatcher::new(&dir, "."));
+
+    check!(dir.create_dir("sub"));
+    let event = check!(watcher.next().await.unwrap());
+    assert_eq!(event.kind(), &DirEventKind::Created);
+    assert_eq!(event.path(), Path::new("sub"));
+    assert!(event.is_dir());
+
+    check!(dir.remove_dir("sub"));
+    let event = check!(watcher.next().await.unwrap());
+    assert_eq!(event.kind(), &DirEventKind::Removed);
+    assert_eq!(event.path(), Path::new("sub"));
+}
+
+#[async_std::test]
+async fn async_add_while_waiting() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+    check!(dir.create_dir("a"));
+    check!(dir.create_dir("b"));
+    let mut watcher = check!(AsyncDirWatcher::new(&dir, "a"));
+
+    // Nothing has happened yet, so this waits until it times out.
+    let timeout = Duration::from_millis(10);
+    assert!(async_std::future::timeout(timeout, watcher.next())
+        .await
+        .is_err());
+
+    // Watches can still be added after the stream has been waiting.
+    check!(watcher.add(&dir, "b"));
+    check!(dir.create_dir("b/sub"));
+    let event = check!(watcher.next().await.unwrap());
+    assert_eq!(event.kind(), &DirEventKind::Created);
+    assert_eq!(event.path(), Path::new("b/sub"));
+}
@@ -0,0 +1,90 @@
+// This file contains tests for `cap_fs_ext::FileIoExt`.
+
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::FileIoExt;
+use cap_std::fs::OpenOptions;
+use std::io::{IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write};
+use sys_common::io::tmpdir;
+
+#[test]
+fn read_write_at() {
+    let tmpdir = tmpdir();
+    let mut file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new().read(true).write(true).create(true)
+    ));
+    check!(file.write_all(b"0123456789"));
+    check!(file.seek(SeekFrom::Start(2)));
+
+    let mut buf = [0; 4];
+    assert_eq!(check!(file.read_at(&mut buf, 5)), 4);
+    assert_eq!(&buf, b"5678");
+    check!(file.write_all_at(b"ab", 8));
+    check!(file.read_exact_at(&mut buf, 6));
+    assert_eq!(&buf, b"67ab");
+
+    // Reads past the end are short, and `read_exact_at` reports them.
+    assert_eq!(check!(file.read_at(&mut buf, 8)), 2);
+    assert_eq!(
+        file.read_exact_at(&mut buf, 8).unwrap_err().kind(),
+        std::io::ErrorKind::UnexpectedEof
+    );
+
+    // None of this moved the cursor.
+    assert_eq!(check!(file.stream_position()), 2);
+    let mut rest = String::new();
+    check!(file.read_to_string(&mut rest));
+    assert_eq!(rest, "234567ab");
+}
+
+#[test]
+fn read_write_vectored_at() {
+    let tmpdir = tmpdir();
+    let file = check!(tmpdir.open_with(
+        "file",
+        OpenOptions::new().read(true).write(true).create(true)
+    ));
+
+    // Platforms without `pwritev` only write the first buffer.
+    let bufs = [IoSlice::new(b"abc"), IoSlice::new(b"def")];
+    match check!(file.write_vectored_at(&bufs, 4)) {
+        6 => (),
+        3 => check!(file.write_all_at(b"def", 7)),
+        n => panic!("unexpected write length {}", n),
+    }
+    assert_eq!(check!(file.metadata()).len(), 10);
+
+    let (mut a, mut b) = ([0; 2], [0; 2]);
+    let n =
+        check!(file.read_vectored_at(&mut [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)], 5));
+    assert!(n == 2 || n == 4);
+    assert_eq!(&a, b"bc");
+    if n == 4 {
+        assert_eq!(&b, b"de");
+    }
+}
+
+#[async_std::test]
+async fn async_read_write_at() {
+    use async_std::io::{prelude::SeekExt, ReadExt};
+
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+    check!(dir.write("file", b"0123456789").await);
+    let mut file = check!(dir.open("file"));
+    check!(file.seek(SeekFrom::Start(3)).await);
+
+    let mut buf = [0; 3];
+    check!(file.read_exact_at(&mut buf, 7));
+    assert_eq!(&buf, b"789");
+    let writer = check!(dir.open_with("file", OpenOptions::new().write(true)));
+    check!(writer.write_all_at(b"x", 0));
+
+    let mut rest = String::new();
+    check!(file.read_to_string(&mut rest).await);
+    assert_eq!(rest, "3456789");
+    check!(file.read_exact_at(&mut buf, 0));
+    assert_eq!(&buf, b"x12");
+}
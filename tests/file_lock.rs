@@ -0,0 +1,96 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::OpenOptions;
+use sys_common::io::tmpdir;
+
+#[test]
+fn lock_shared_and_exclusive() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("lock", b""));
+
+    // Separate opens of the same file have independent locks.
+    let a = check!(tmpdir.open("lock"));
+    let b = check!(tmpdir.open("lock"));
+
+    check!(a.lock_shared());
+    assert!(check!(b.try_lock_shared()));
+    assert!(!check!(b.try_lock_exclusive()));
+    check!(a.unlock());
+    check!(b.unlock());
+
+    check!(a.lock_exclusive());
+    assert!(!check!(b.try_lock_shared()));
+    assert!(!check!(b.try_lock_exclusive()));
+    check!(a.unlock());
+    assert!(check!(b.try_lock_exclusive()));
+    check!(b.unlock());
+}
+
+#[test]
+fn lock_file() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+
+    let lock = check!(tmpdir.lock_file("sub/lock"));
+    assert!(check!(tmpdir.metadata("sub/lock")).is_file());
+    assert!(check!(tmpdir.try_lock_file("sub/lock")).is_none());
+
+    let other = check!(tmpdir.open_with("sub/lock", OpenOptions::new().read(true)));
+    assert!(!check!(other.try_lock_shared()));
+
+    // Dropping the guard releases the lock, and leaves the file in place.
+    drop(lock);
+    assert!(check!(other.try_lock_shared()));
+    check!(other.unlock());
+
+    let lock = check!(tmpdir.try_lock_file("sub/lock")).unwrap();
+    check!(lock.unlock());
+    assert!(check!(tmpdir.try_lock_file("sub/lock")).is_some());
+}
+
+#[test]
+fn lock_file_keeps_contents() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("lock", b"pid 1234"));
+    let lock = check!(tmpdir.lock_file("lock"));
+    assert_eq!(check!(tmpdir.read_to_string("lock")), "pid 1234");
+    drop(lock);
+}
+
+#[async_std::test]
+async fn async_lock_file() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+
+    let lock = check!(dir.lock_file("lock").await);
+    assert!(check!(dir.try_lock_file("lock")).is_none());
+
+    let other = check!(dir.open("lock"));
+    assert!(!check!(other.try_lock_exclusive()));
+    drop(lock);
+    check!(other.lock_shared().await);
+    assert!(check!(dir.try_lock_file("lock")).is_none());
+    check!(other.unlock());
+    check!(other.lock_exclusive().await);
+    check!(other.unlock());
+}
+
+#[test]
+#[cfg(feature = "fs_utf8")]
+fn lock_file_utf8() {
+    let tmpdir = tmpdir();
+    let dir = cap_std::fs_utf8::Dir::from_cap_std(check!(tmpdir.try_clone()));
+
+    let lock = check!(dir.lock_file("lock"));
+    assert!(check!(dir.try_lock_file("lock")).is_none());
+
+    let other = check!(dir.open("lock"));
+    assert!(!check!(other.try_lock_shared()));
+    drop(lock);
+    assert!(check!(other.try_lock_exclusive()));
+    check!(other.unlock());
+
+    let lock = check!(dir.try_lock_file("lock")).unwrap();
+    check!(lock.unlock());
+}
@@ -0,0 +1,209 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::Dir;
+use std::path::PathBuf;
+use sys_common::{io::tmpdir, symlink_supported};
+
+fn glob(dir: &Dir, pattern: &str) -> Vec<PathBuf> {
+    check!(dir.glob(pattern)).map(|path| check!(path)).collect()
+}
+
+fn p(paths: &[&str]) -> Vec<PathBuf> {
+    paths.iter().map(PathBuf::from).collect()
+}
+
+/// Create this tree:
+///
+/// ```text
+/// README.md
+/// src/
+/// src/lib.rs
+/// src/fs/
+/// src/fs/dir.rs
+/// src/fs/mod.rs
+/// src/net/
+/// src/net/mod.rs
+/// tests/
+/// tests/a1.txt
+/// tests/b2.txt
+/// ```
+fn make_tree(dir: &Dir) {
+    check!(dir.create_dir_all("src/fs"));
+    check!(dir.create_dir_all("src/net"));
+    check!(dir.create_dir("tests"));
+    for file in &[
+        "README.md",
+        "src/lib.rs",
+        "src/fs/dir.rs",
+        "src/fs/mod.rs",
+        "src/net/mod.rs",
+        "tests/a1.txt",
+        "tests/b2.txt",
+    ] {
+        check!(dir.write(file, b""));
+    }
+}
+
+#[test]
+fn glob_wildcards() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    assert_eq!(glob(&tmpdir, "*"), p(&["README.md", "src", "tests"]));
+    assert_eq!(glob(&tmpdir, "src/*.rs"), p(&["src/lib.rs"]));
+    assert_eq!(
+        glob(&tmpdir, "src/*/mod.rs"),
+        p(&["src/fs/mod.rs", "src/net/mod.rs"])
+    );
+    assert_eq!(glob(&tmpdir, "tests/?1.txt"), p(&["tests/a1.txt"]));
+    assert_eq!(glob(&tmpdir, "tests/[b-z]*"), p(&["tests/b2.txt"]));
+    assert_eq!(glob(&tmpdir, "tests/*[!2].txt"), p(&["tests/a1.txt"]));
+    assert_eq!(glob(&tmpdir, "src/lib.rs"), p(&["src/lib.rs"]));
+    assert!(glob(&tmpdir, "src/missing.rs").is_empty());
+    assert!(glob(&tmpdir, "README.md/*").is_empty());
+}
+
+#[test]
+fn glob_recursive() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    // Each directory's matches are yielded before its subdirectories'.
+    assert_eq!(
+        glob(&tmpdir, "**/*.rs"),
+        p(&[
+            "src/lib.rs",
+            "src/fs/dir.rs",
+            "src/fs/mod.rs",
+            "src/net/mod.rs"
+        ])
+    );
+    assert_eq!(
+        glob(&tmpdir, "src/**/mod.rs"),
+        p(&["src/fs/mod.rs", "src/net/mod.rs"])
+    );
+    assert_eq!(
+        glob(&tmpdir, "src/**"),
+        p(&[
+            "src",
+            "src/fs",
+            "src/fs/dir.rs",
+            "src/fs/mod.rs",
+            "src/lib.rs",
+            "src/net",
+            "src/net/mod.rs"
+        ])
+    );
+
+    // Paths reachable in more than one way are yielded once.
+    assert_eq!(glob(&tmpdir, "**/**/lib.rs"), p(&["src/lib.rs"]));
+}
+
+#[test]
+fn glob_case_insensitive() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    assert!(glob(&tmpdir, "readme.*").is_empty());
+
+    let matches = check!(tmpdir.glob("readme.*"))
+        .case_insensitive(true)
+        .map(|path| check!(path))
+        .collect::<Vec<_>>();
+    assert_eq!(matches, p(&["README.md"]));
+
+    let matches = check!(tmpdir.glob("SRC/[K-M]*"))
+        .case_insensitive(true)
+        .map(|path| check!(path))
+        .collect::<Vec<_>>();
+    assert_eq!(matches, p(&["src/lib.rs"]));
+}
+
+#[test]
+fn glob_parent() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    assert_eq!(glob(&tmpdir, "src/fs/../lib.rs"), p(&["src/lib.rs"]));
+    assert_eq!(glob(&tmpdir, "*/../README.md"), p(&["README.md"]));
+
+    // `..` never refers to anything outside the directory.
+    check!(tmpdir.write("secret", b""));
+    let src = check!(tmpdir.open_dir("src"));
+    assert!(glob(&src, "../*").is_empty());
+    assert!(glob(&src, "fs/../../secret").is_empty());
+    assert!(glob(&src, "**/../../*").is_empty());
+}
+
+#[test]
+fn glob_invalid() {
+    let tmpdir = tmpdir();
+
+    error_contains!(tmpdir.glob("/etc/*"), "must be relative");
+    error_contains!(tmpdir.glob("[abc"), "unterminated character class");
+}
+
+#[test]
+fn glob_symlinks() {
+    if !symlink_supported() {
+        return;
+    }
+    let outer = tmpdir();
+    make_tree(&outer);
+    check!(outer.write("secret", b""));
+    check!(outer.symlink("fs", "src/link"));
+    check!(outer.symlink("..", "src/up"));
+    let src = check!(outer.open_dir("src"));
+
+    // Symlinks named by the pattern are followed, but `**` doesn't descend
+    // into them.
+    assert_eq!(glob(&src, "link/*.rs"), p(&["link/dir.rs", "link/mod.rs"]));
+    assert_eq!(glob(&src, "**/mod.rs"), p(&["fs/mod.rs", "net/mod.rs"]));
+
+    // Symlinks which lead outside the directory don't match.
+    assert_eq!(glob(&src, "up"), p(&["up"]));
+    assert!(glob(&src, "up/*").is_empty());
+}
+
+#[async_std::test]
+async fn async_glob() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+    check!(dir.create_dir_all("a/b"));
+    check!(dir.write("a/b/c.txt", b"c").await);
+    check!(dir.write("D.txt", b"d").await);
+
+    let paths = check!(dir.glob("**/*.txt"))
+        .map(|path| check!(path))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        ["D.txt", "a/b/c.txt"]
+            .iter()
+            .map(async_std::path::PathBuf::from)
+            .collect::<Vec<_>>()
+    );
+
+    let paths = check!(dir.glob("d.*"))
+        .case_insensitive(true)
+        .map(|path| check!(path))
+        .collect::<Vec<_>>();
+    assert_eq!(paths, [async_std::path::PathBuf::from("D.txt")]);
+}
+
+#[cfg(feature = "fs_utf8")]
+#[test]
+fn glob_utf8() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a/b"));
+    check!(tmpdir.write("a/one.txt", b""));
+    check!(tmpdir.write("a/b/two.txt", b""));
+    let dir = cap_std::fs_utf8::Dir::from_cap_std(check!(tmpdir.try_clone()));
+
+    let mut paths = check!(dir.glob("a/**/*.txt"))
+        .map(|path| check!(path))
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(paths, ["a/b/two.txt", "a/one.txt"]);
+}
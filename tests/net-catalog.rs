@@ -0,0 +1,1207 @@
+use cap_std::net::{
+    Catalog, HostsResolver, IpNet, Ipv4Addr, Ipv4Net, Ipv6Addr, Operations, PeerFilter, Resolver,
+    SocketAddr,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    io::{self, Read, Write},
+    sync::{Arc, Mutex},
+};
+
+fn assert_denied<T>(result: io::Result<T>) {
+    match result {
+        Ok(_) => panic!("expected PermissionDenied"),
+        Err(e) => assert_eq!(e.kind(), io::ErrorKind::PermissionDenied),
+    }
+}
+
+fn localhost_net() -> IpNet {
+    "127.0.0.0/8".parse().unwrap()
+}
+
+#[test]
+fn empty_catalog() {
+    let catalog = Catalog::new();
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 0));
+    assert_denied(catalog.check_addr(&addr, Operations::CONNECT));
+    assert_denied(catalog.bind_tcp_listener(addr));
+    assert_denied(catalog.bind_udp_socket(addr));
+}
+
+#[test]
+fn cidr_and_ports() {
+    let mut catalog = Catalog::new();
+    unsafe {
+        catalog
+            .insert_ip_net("10.0.0.0/8".parse().unwrap(), 443)
+            .insert_ip_net_port_range("192.168.1.0/24".parse().unwrap(), 8000, 8099)
+            .insert_ip_net_port_any("fd00::/8".parse().unwrap())
+            .insert_socket_addr("203.0.113.7:53".parse().unwrap());
+    }
+
+    for ok in &[
+        "10.0.0.1:443",
+        "10.255.255.255:443",
+        "192.168.1.1:8000",
+        "192.168.1.254:8099",
+        "[fd00::1]:0",
+        "[fdff::1]:65535",
+        "203.0.113.7:53",
+    ] {
+        catalog
+            .check_addr(&ok.parse().unwrap(), Operations::CONNECT)
+            .unwrap();
+    }
+
+    for bad in &[
+        "10.0.0.1:444",
+        "11.0.0.1:443",
+        "192.168.1.1:7999",
+        "192.168.1.1:8100",
+        "192.168.2.1:8000",
+        "[fe00::1]:80",
+        "203.0.113.8:53",
+        "203.0.113.7:54",
+    ] {
+        assert_eq!(
+            catalog
+                .check_addr(&bad.parse().unwrap(), Operations::CONNECT)
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::PermissionDenied,
+            "{} should be denied",
+            bad
+        );
+    }
+}
+
+#[test]
+fn restrict() {
+    let mut parent = Catalog::new();
+    unsafe {
+        parent
+            .insert_ip_net_port_range("10.0.0.0/8".parse().unwrap(), 400, 500)
+            .insert_ip_net_port_any("192.168.0.0/16".parse().unwrap());
+    }
+
+    let child = parent.restrict("10.1.0.0/16".parse().unwrap(), 443, 443);
+    child
+        .check_addr(&"10.1.2.3:443".parse().unwrap(), Operations::CONNECT)
+        .unwrap();
+    assert_denied(child.check_addr(&"10.2.2.3:443".parse().unwrap(), Operations::CONNECT));
+    assert_denied(child.check_addr(&"10.1.2.3:444".parse().unwrap(), Operations::CONNECT));
+    assert_denied(child.check_addr(&"192.168.0.1:443".parse().unwrap(), Operations::CONNECT));
+
+    // Asking for more than the parent has yields only what the parent has.
+    let wide = parent.restrict("0.0.0.0/0".parse().unwrap(), 0, u16::MAX);
+    wide.check_addr(&"10.1.2.3:400".parse().unwrap(), Operations::CONNECT)
+        .unwrap();
+    wide.check_addr(&"192.168.3.4:22".parse().unwrap(), Operations::CONNECT)
+        .unwrap();
+    assert_denied(wide.check_addr(&"10.1.2.3:22".parse().unwrap(), Operations::CONNECT));
+    assert_denied(wide.check_addr(&"172.16.0.1:443".parse().unwrap(), Operations::CONNECT));
+
+    // Attenuating by a catalog intersects the two.
+    let mut request = Catalog::new();
+    unsafe {
+        request
+            .insert_ip_net_port_any("10.0.0.0/24".parse().unwrap())
+            .insert_ip_net("192.168.7.0/24".parse().unwrap(), 22)
+            .insert_ip_net_port_any("172.16.0.0/12".parse().unwrap());
+    }
+    let child = parent.attenuate(&request);
+    child
+        .check_addr(&"10.0.0.9:450".parse().unwrap(), Operations::CONNECT)
+        .unwrap();
+    child
+        .check_addr(&"192.168.7.9:22".parse().unwrap(), Operations::CONNECT)
+        .unwrap();
+    assert_denied(child.check_addr(&"10.0.0.9:22".parse().unwrap(), Operations::CONNECT));
+    assert_denied(child.check_addr(&"192.168.7.9:23".parse().unwrap(), Operations::CONNECT));
+    assert_denied(child.check_addr(&"172.16.0.1:443".parse().unwrap(), Operations::CONNECT));
+}
+
+fn random_net(rng: &mut StdRng) -> IpNet {
+    // Keep addresses within a small space so that grants overlap often.
+    let addr = Ipv4Addr::new(10, 0, rng.gen_range(0, 4), rng.gen());
+    Ipv4Net::new(addr, rng.gen_range(20, 33)).unwrap().into()
+}
+
+fn random_ports(rng: &mut StdRng) -> (u16, u16) {
+    let a = rng.gen_range(0, 64);
+    let b = rng.gen_range(0, 64);
+    (a.min(b), a.max(b))
+}
+
+fn random_catalog(rng: &mut StdRng) -> Catalog {
+    let mut catalog = Catalog::new();
+    for _ in 0..rng.gen_range(0, 4) {
+        let (start, end) = random_ports(rng);
+        unsafe {
+            catalog.insert_ip_net_port_range(random_net(rng), start, end);
+        }
+    }
+    catalog
+}
+
+fn random_addr(rng: &mut StdRng) -> SocketAddr {
+    let ip = Ipv4Addr::new(10, 0, rng.gen_range(0, 4), rng.gen());
+    SocketAddr::from((ip, rng.gen_range(0, 64)))
+}
+
+#[test]
+fn restrict_never_widens() {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    for _ in 0..1000 {
+        let parent = random_catalog(&mut rng);
+        let net = random_net(&mut rng);
+        let (start, end) = random_ports(&mut rng);
+        let child = parent.restrict(net, start, end);
+        for _ in 0..100 {
+            let addr = random_addr(&mut rng);
+            if child.check_addr(&addr, Operations::CONNECT).is_ok() {
+                parent.check_addr(&addr, Operations::CONNECT).unwrap();
+                assert!(net.contains(&addr.ip()));
+                assert!(start <= addr.port() && addr.port() <= end);
+            } else if parent.check_addr(&addr, Operations::CONNECT).is_ok()
+                && net.contains(&addr.ip())
+            {
+                assert!(addr.port() < start || end < addr.port());
+            }
+        }
+    }
+}
+
+#[test]
+fn attenuate_never_widens() {
+    let mut rng = StdRng::seed_from_u64(0xca7a);
+    for _ in 0..1000 {
+        let parent = random_catalog(&mut rng);
+        let request = random_catalog(&mut rng);
+        let child = parent.attenuate(&request);
+        let grandchild = child.attenuate(&parent);
+        for _ in 0..100 {
+            let addr = random_addr(&mut rng);
+            let in_both = parent.check_addr(&addr, Operations::CONNECT).is_ok()
+                && request.check_addr(&addr, Operations::CONNECT).is_ok();
+            assert_eq!(
+                child.check_addr(&addr, Operations::CONNECT).is_ok(),
+                in_both
+            );
+            assert_eq!(
+                grandchild.check_addr(&addr, Operations::CONNECT).is_ok(),
+                in_both
+            );
+        }
+    }
+}
+
+#[test]
+fn operations() {
+    let mut catalog = Catalog::new();
+    unsafe {
+        catalog
+            .insert_ip_net_operations("10.0.0.0/8".parse().unwrap(), 443, 443, Operations::CONNECT)
+            .insert_ip_net_operations(localhost_net(), 0, u16::MAX, Operations::BIND)
+            .insert_ip_net_operations(
+                "192.168.0.0/16".parse().unwrap(),
+                53,
+                53,
+                Operations::UDP_SEND | Operations::UDP_RECEIVE,
+            );
+    }
+
+    let remote = "10.1.2.3:443".parse().unwrap();
+    catalog.check_addr(&remote, Operations::CONNECT).unwrap();
+    assert_denied(catalog.check_addr(&remote, Operations::BIND));
+    assert_denied(catalog.check_addr(&remote, Operations::UDP_SEND));
+    assert_denied(catalog.check_addr(&remote, Operations::CONNECT | Operations::BIND));
+    assert_denied(catalog.bind_tcp_listener(remote));
+    assert_denied(catalog.bind_udp_socket(remote));
+
+    // A bind-only grant can bind, but not connect or send.
+    let listener = catalog.bind_tcp_listener((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let addr = listener.local_addr().unwrap();
+    assert_denied(catalog.connect_tcp_stream(addr));
+    let udp_socket = catalog.bind_udp_socket((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let udp_addr = udp_socket.local_addr().unwrap();
+    assert_denied(catalog.send_to_udp_socket_addr(&udp_socket, b"ping", udp_addr));
+    assert_denied(catalog.connect_udp_socket(&udp_socket, udp_addr));
+
+    let dns = "192.168.0.1:53".parse().unwrap();
+    catalog
+        .check_addr(&dns, Operations::UDP_SEND | Operations::UDP_RECEIVE)
+        .unwrap();
+    assert_denied(catalog.check_addr(&dns, Operations::CONNECT));
+
+    // Restricting operations removes the others.
+    let connect_only = catalog.restrict_operations(Operations::CONNECT);
+    connect_only
+        .check_addr(&remote, Operations::CONNECT)
+        .unwrap();
+    assert_denied(connect_only.bind_tcp_listener((Ipv4Addr::LOCALHOST, 0)));
+    assert_denied(connect_only.check_addr(&dns, Operations::UDP_SEND));
+
+    // Attenuation intersects operations as well as addresses.
+    let mut request = Catalog::new();
+    unsafe {
+        request.insert_ip_net_operations(
+            "0.0.0.0/0".parse().unwrap(),
+            0,
+            u16::MAX,
+            Operations::UDP_SEND | Operations::BIND,
+        );
+    }
+    let child = catalog.attenuate(&request);
+    child.check_addr(&dns, Operations::UDP_SEND).unwrap();
+    assert_denied(child.check_addr(&dns, Operations::UDP_RECEIVE));
+    assert_denied(child.check_addr(&remote, Operations::CONNECT));
+}
+
+#[test]
+fn tcp_connect() {
+    let mut server_catalog = Catalog::new();
+    unsafe {
+        server_catalog.insert_ip_net_port_any(localhost_net());
+    }
+    let listener = server_catalog
+        .bind_tcp_listener((Ipv4Addr::LOCALHOST, 0))
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    // A catalog containing only the listener's address can connect to it.
+    let mut client_catalog = Catalog::new();
+    unsafe {
+        client_catalog.insert_socket_addr(addr);
+    }
+    let mut stream = client_catalog.connect_tcp_stream(addr).unwrap();
+    stream.write_all(b"hello").unwrap();
+    let (mut accepted, _) = listener.accept().unwrap();
+    let mut buf = [0_u8; 5];
+    accepted.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+
+    // Connections outside the catalog are rejected before anything is sent.
+    let other_port = SocketAddr::new(addr.ip(), addr.port().wrapping_add(1));
+    assert_denied(client_catalog.connect_tcp_stream(other_port));
+    let other_ip = SocketAddr::from((Ipv4Addr::new(127, 0, 0, 2), addr.port()));
+    assert_denied(client_catalog.connect_tcp_stream(other_ip));
+}
+
+#[test]
+fn udp_send() {
+    let mut catalog = Catalog::new();
+    unsafe {
+        catalog.insert_ip_net_port_any(localhost_net());
+    }
+    let receiver = catalog.bind_udp_socket((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let sender = catalog.bind_udp_socket((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let addr = receiver.local_addr().unwrap();
+
+    assert_eq!(
+        catalog
+            .send_to_udp_socket_addr(&sender, b"ping", addr)
+            .unwrap(),
+        4
+    );
+    let mut buf = [0_u8; 4];
+    let (n, from) = receiver.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"ping");
+    assert_eq!(from, sender.local_addr().unwrap());
+
+    let outside = "192.0.2.1:9".parse::<SocketAddr>().unwrap();
+    assert_denied(catalog.send_to_udp_socket_addr(&sender, b"ping", outside));
+    assert_denied(catalog.connect_udp_socket(&sender, outside));
+}
+
+#[async_std::test]
+async fn async_tcp_connect() {
+    use async_std::io::{ReadExt, WriteExt};
+
+    let mut catalog = cap_async_std::net::Catalog::new();
+    unsafe {
+        catalog.insert_ip_net_port_any(localhost_net());
+    }
+    let listener = catalog
+        .bind_tcp_listener((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let mut stream = catalog.connect_tcp_stream(addr).await.unwrap();
+    stream.write_all(b"hello").await.unwrap();
+    let (mut accepted, _) = listener.accept().await.unwrap();
+    let mut buf = [0_u8; 5];
+    accepted.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"hello");
+
+    let outside = "192.0.2.1:80".parse::<SocketAddr>().unwrap();
+    assert_denied(catalog.connect_tcp_stream(outside).await);
+}
+
+#[async_std::test]
+async fn async_operations() {
+    let mut catalog = cap_async_std::net::Catalog::new();
+    unsafe {
+        catalog.insert_ip_net_operations(localhost_net(), 0, u16::MAX, Operations::CONNECT);
+    }
+    assert_denied(catalog.bind_tcp_listener((Ipv4Addr::LOCALHOST, 0)).await);
+    assert_denied(catalog.bind_udp_socket((Ipv4Addr::LOCALHOST, 0)).await);
+
+    let binder = catalog.restrict_operations(Operations::BIND);
+    assert_denied(binder.bind_tcp_listener((Ipv4Addr::LOCALHOST, 0)).await);
+}
+
+fn stub_resolver() -> Arc<dyn Resolver> {
+    let mut hosts = HostsResolver::new();
+    hosts
+        .insert("db.internal.example", Ipv4Addr::LOCALHOST.into())
+        .insert("a.b.internal.example", Ipv4Addr::LOCALHOST.into())
+        .insert("internal.example", Ipv4Addr::LOCALHOST.into())
+        .insert("evil.example", Ipv4Addr::LOCALHOST.into())
+        .insert("multi.internal.example", Ipv4Addr::new(127, 0, 0, 2).into())
+        .insert("multi.internal.example", Ipv6Addr::LOCALHOST.into());
+    Arc::new(hosts)
+}
+
+#[test]
+fn names() {
+    let mut server_catalog = Catalog::new();
+    unsafe {
+        server_catalog.insert_ip_net_port_any(localhost_net());
+    }
+    let listener = server_catalog
+        .bind_tcp_listener((Ipv4Addr::LOCALHOST, 0))
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let mut catalog = Catalog::new();
+    unsafe {
+        catalog
+            .insert_name("*.internal.example", addr.port())
+            .set_resolver(stub_resolver());
+    }
+
+    catalog
+        .connect_tcp_stream_by_name("db.internal.example", addr.port())
+        .unwrap();
+    catalog
+        .connect_tcp_stream_by_name("A.B.Internal.Example.", addr.port())
+        .unwrap();
+    assert_denied(catalog.connect_tcp_stream_by_name("internal.example", addr.port()));
+    assert_denied(catalog.connect_tcp_stream_by_name("evil.example", addr.port()));
+    assert_denied(catalog.connect_tcp_stream_by_name("db.internal.example", addr.port() ^ 1));
+
+    // The address that a permitted name resolves to isn't itself permitted.
+    assert_denied(catalog.connect_tcp_stream(addr));
+    assert_denied(catalog.check_addr(&addr, Operations::CONNECT));
+
+    // Address grants don't permit names.
+    assert_denied(server_catalog.connect_tcp_stream_by_name("db.internal.example", addr.port()));
+
+    // Attenuation narrows names, and keeps the parent's resolver.
+    let mut request = Catalog::new();
+    unsafe {
+        request.insert_name("db.internal.example", addr.port());
+    }
+    let child = catalog.attenuate(&request);
+    child
+        .connect_tcp_stream_by_name("db.internal.example", addr.port())
+        .unwrap();
+    assert_denied(child.check_name("a.b.internal.example", addr.port(), Operations::CONNECT));
+    let child = request.attenuate(&catalog);
+    child
+        .check_name("db.internal.example", addr.port(), Operations::CONNECT)
+        .unwrap();
+
+    let mut request = Catalog::new();
+    unsafe {
+        request.insert_name("*.example", addr.port());
+    }
+    let child = catalog.attenuate(&request);
+    child
+        .check_name("db.internal.example", addr.port(), Operations::CONNECT)
+        .unwrap();
+    assert_denied(child.check_name("evil.example", addr.port(), Operations::CONNECT));
+
+    // Operations apply to names too.
+    let bind_only = catalog.restrict_operations(Operations::BIND);
+    assert_denied(bind_only.connect_tcp_stream_by_name("db.internal.example", addr.port()));
+}
+
+#[test]
+fn lookup_host() {
+    let mut catalog = Catalog::new();
+    unsafe {
+        catalog
+            .insert_name_operations("*.internal.example", 5000, 5999, Operations::CONNECT)
+            .set_resolver(stub_resolver());
+    }
+
+    let before = catalog.clone();
+    let addrs = catalog.lookup_host("multi.internal.example", 5432).unwrap();
+    assert_eq!(
+        addrs,
+        vec![
+            "127.0.0.2:5432".parse::<SocketAddr>().unwrap(),
+            "[::1]:5432".parse().unwrap()
+        ]
+    );
+    // Grants for the returned addresses are inserted into the catalog, for
+    // the operations the name is permitted for, and only on the looked-up
+    // port.
+    for addr in &addrs {
+        catalog.check_addr(addr, Operations::CONNECT).unwrap();
+        assert_denied(catalog.check_addr(addr, Operations::BIND));
+        assert_denied(catalog.check_addr(&SocketAddr::new(addr.ip(), 5433), Operations::CONNECT));
+    }
+
+    // Clones taken before the lookup don't gain the grants; catalogs derived
+    // afterward inherit them like any other grant.
+    assert_denied(before.check_addr(&addrs[0], Operations::CONNECT));
+    let child = catalog.restrict_operations(Operations::CONNECT);
+    child.check_addr(&addrs[0], Operations::CONNECT).unwrap();
+
+    // Names and ports outside the catalog aren't looked up.
+    assert_denied(catalog.lookup_host("evil.example", 5432));
+    assert_denied(catalog.lookup_host("db.internal.example", 6000));
+    assert_denied(catalog.check_addr(&"127.0.0.1:5432".parse().unwrap(), Operations::CONNECT));
+
+    // Permitted names that the resolver doesn't know fail normally.
+    assert_eq!(
+        catalog
+            .lookup_host("missing.internal.example", 5432)
+            .unwrap_err()
+            .kind(),
+        io::ErrorKind::NotFound
+    );
+}
+
+#[async_std::test]
+async fn async_lookup_host() {
+    let mut catalog = cap_async_std::net::Catalog::new();
+    unsafe {
+        catalog
+            .insert_name("db.internal.example", 5432)
+            .set_resolver(stub_resolver());
+    }
+    let addrs = catalog
+        .lookup_host("DB.internal.example.", 5432)
+        .await
+        .unwrap();
+    assert_eq!(addrs, vec!["127.0.0.1:5432".parse::<SocketAddr>().unwrap()]);
+    catalog.check_addr(&addrs[0], Operations::UDP_SEND).unwrap();
+    assert_denied(catalog.lookup_host("evil.example", 5432).await);
+    catalog.check_addr(&addrs[0], Operations::UDP_SEND).unwrap();
+}
+
+#[async_std::test]
+async fn async_names() {
+    let mut server_catalog = cap_async_std::net::Catalog::new();
+    unsafe {
+        server_catalog.insert_ip_net_port_any(localhost_net());
+    }
+    let listener = server_catalog
+        .bind_tcp_listener((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let mut catalog = cap_async_std::net::Catalog::new();
+    unsafe {
+        catalog
+            .insert_name("*.internal.example", addr.port())
+            .set_resolver(stub_resolver());
+    }
+    catalog
+        .connect_tcp_stream_by_name("db.internal.example", addr.port())
+        .await
+        .unwrap();
+    assert_denied(
+        catalog
+            .connect_tcp_stream_by_name("evil.example", addr.port())
+            .await,
+    );
+    assert_denied(catalog.connect_tcp_stream(addr).await);
+}
+
+#[test]
+fn udp_socket_send_to() {
+    let mut catalog = Catalog::new();
+    unsafe {
+        catalog.insert_ip_net_operations(localhost_net(), 0, u16::MAX, Operations::BIND);
+    }
+    let receiver = catalog.bind_udp_socket((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let addr = receiver.local_addr().unwrap();
+
+    let mut sender_catalog = catalog.clone();
+    unsafe {
+        sender_catalog.insert_ip_net_operations(
+            addr.ip().into(),
+            addr.port(),
+            addr.port(),
+            Operations::UDP_SEND,
+        );
+    }
+    let sender = sender_catalog
+        .bind_udp_socket((Ipv4Addr::LOCALHOST, 0))
+        .unwrap();
+    assert_eq!(sender.send_to(b"ping", addr).unwrap(), 4);
+    let mut buf = [0_u8; 4];
+    let (n, _) = receiver.recv_from(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"ping");
+
+    // The socket remembers its catalog, so other destinations are denied.
+    assert_denied(sender.send_to(b"ping", (Ipv4Addr::LOCALHOST, addr.port() ^ 1)));
+    assert_denied(sender.send_to(b"ping", "192.0.2.1:9"));
+    assert_denied(sender.try_clone().unwrap().send_to(b"ping", "192.0.2.1:9"));
+
+    // The receiver's catalog has no `UDP_SEND` grants.
+    assert_denied(receiver.send_to(b"pong", sender.local_addr().unwrap()));
+
+    // Sockets not bound from a catalog can't use `send_to` at all.
+    let ambient = unsafe {
+        cap_std::net::UdpSocket::from_std(std::net::UdpSocket::bind("127.0.0.1:0").unwrap())
+    };
+    assert_denied(ambient.send_to(b"ping", addr));
+    assert_denied(ambient.set_recv_filter(true));
+}
+
+#[test]
+fn udp_socket_recv_filter() {
+    let mut peers = Catalog::new();
+    unsafe {
+        peers.insert_ip_net_port_any(localhost_net());
+    }
+    let good = peers.bind_udp_socket((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let bad = peers.bind_udp_socket((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let good_addr = good.local_addr().unwrap();
+
+    let mut catalog = Catalog::new();
+    unsafe {
+        catalog
+            .insert_ip_net_operations(localhost_net(), 0, u16::MAX, Operations::BIND)
+            .insert_ip_net_operations(
+                good_addr.ip().into(),
+                good_addr.port(),
+                good_addr.port(),
+                Operations::UDP_RECEIVE,
+            );
+    }
+    let receiver = catalog.bind_udp_socket((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    receiver
+        .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+        .unwrap();
+    let addr = receiver.local_addr().unwrap();
+    assert!(!receiver.recv_filter());
+    receiver.set_recv_filter(true).unwrap();
+    assert!(receiver.recv_filter());
+
+    bad.send_to(b"bad", addr).unwrap();
+    bad.send_to(b"bad", addr).unwrap();
+    good.send_to(b"good", addr).unwrap();
+    good.send_to(b"good", addr).unwrap();
+
+    let mut buf = [0_u8; 8];
+    let (n, from) = receiver.peek_from(&mut buf).unwrap();
+    assert_eq!((&buf[..n], from), (&b"good"[..], good_addr));
+    let (n, from) = receiver.recv_from(&mut buf).unwrap();
+    assert_eq!((&buf[..n], from), (&b"good"[..], good_addr));
+
+    // With the filter off, datagrams from any peer are received.
+    receiver.set_recv_filter(false).unwrap();
+    bad.send_to(b"bad", addr).unwrap();
+    let (n, from) = receiver.recv_from(&mut buf).unwrap();
+    assert_eq!((&buf[..n], from), (&b"good"[..], good_addr));
+    let (n, from) = receiver.recv_from(&mut buf).unwrap();
+    assert_eq!((&buf[..n], from), (&b"bad"[..], bad.local_addr().unwrap()));
+}
+
+#[test]
+fn udp_socket_recv_filter_unconnected_recv() {
+    let mut peers = Catalog::new();
+    unsafe {
+        peers.insert_ip_net_port_any(localhost_net());
+    }
+    let good = peers.bind_udp_socket((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let bad = peers.bind_udp_socket((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let good_addr = good.local_addr().unwrap();
+
+    let mut catalog = Catalog::new();
+    unsafe {
+        catalog
+            .insert_ip_net_operations(localhost_net(), 0, u16::MAX, Operations::BIND)
+            .insert_ip_net_operations(
+                good_addr.ip().into(),
+                good_addr.port(),
+                good_addr.port(),
+                Operations::UDP_RECEIVE,
+            );
+    }
+    let receiver = catalog.bind_udp_socket((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    receiver
+        .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+        .unwrap();
+    let addr = receiver.local_addr().unwrap();
+    receiver.set_recv_filter(true).unwrap();
+
+    // `recv` and `peek` on an unconnected socket filter just like
+    // `recv_from` and `peek_from`.
+    bad.send_to(b"bad", addr).unwrap();
+    good.send_to(b"good", addr).unwrap();
+    bad.send_to(b"bad", addr).unwrap();
+    good.send_to(b"good2", addr).unwrap();
+
+    let mut buf = [0_u8; 8];
+    let n = receiver.peek(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"good");
+    let n = receiver.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"good");
+    let n = receiver.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"good2");
+}
+
+#[async_std::test]
+async fn async_udp_socket() {
+    let mut peers = cap_async_std::net::Catalog::new();
+    unsafe {
+        peers.insert_ip_net_port_any(localhost_net());
+    }
+    let good = peers
+        .bind_udp_socket((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let bad = peers
+        .bind_udp_socket((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let good_addr = good.local_addr().unwrap();
+
+    let mut catalog = cap_async_std::net::Catalog::new();
+    unsafe {
+        catalog
+            .insert_ip_net_operations(localhost_net(), 0, u16::MAX, Operations::BIND)
+            .insert_socket_addr(good_addr);
+    }
+    let receiver = catalog
+        .bind_udp_socket((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let addr = receiver.local_addr().unwrap();
+    receiver.set_recv_filter(true).unwrap();
+
+    bad.send_to(b"bad", addr).await.unwrap();
+    good.send_to(b"good", addr).await.unwrap();
+    let mut buf = [0_u8; 8];
+    let (n, from) = receiver.recv_from(&mut buf).await.unwrap();
+    assert_eq!((&buf[..n], from), (&b"good"[..], good_addr));
+
+    bad.send_to(b"bad", addr).await.unwrap();
+    good.send_to(b"good2", addr).await.unwrap();
+    let n = receiver.recv(&mut buf).await.unwrap();
+    assert_eq!(&buf[..n], b"good2");
+
+    assert_eq!(receiver.send_to(b"pong", good_addr).await.unwrap(), 4);
+    assert_denied(receiver.send_to(b"pong", bad.local_addr().unwrap()).await);
+}
+
+#[test]
+fn policy_round_trip() {
+    let policy = "\
+# A sample policy.
+10.0.0.0/8          443        connect
+192.168.1.0/24      8000-8099  bind,connect   # trailing comment
+fd00::/8            *          all
+
+203.0.113.7         53         udp-send,udp-receive
+*.Internal.Example. 8080       connect
+localhost           0-1023
+";
+    let catalog = unsafe { Catalog::from_policy(policy) }.unwrap();
+    let printed = catalog.to_policy();
+    assert_eq!(
+        printed,
+        "\
+10.0.0.0/8 443 connect
+192.168.1.0/24 8000-8099 bind,connect
+fd00::/8 * all
+203.0.113.7/32 53 udp-send,udp-receive
+*.internal.example 8080 connect
+localhost 0-1023 all
+"
+    );
+    let reparsed = unsafe { Catalog::from_policy(&printed) }.unwrap();
+    assert_eq!(reparsed.to_policy(), printed);
+
+    let ok = |addr: &str, ops| catalog.check_addr(&addr.parse().unwrap(), ops).unwrap();
+    ok("10.1.2.3:443", Operations::CONNECT);
+    ok("192.168.1.9:8050", Operations::BIND);
+    ok("[fd12::1]:9", Operations::all());
+    ok(
+        "203.0.113.7:53",
+        Operations::UDP_SEND | Operations::UDP_RECEIVE,
+    );
+    assert_denied(catalog.check_addr(&"10.1.2.3:443".parse().unwrap(), Operations::BIND));
+    assert_denied(catalog.check_addr(&"203.0.113.7:53".parse().unwrap(), Operations::CONNECT));
+    catalog
+        .check_name("db.internal.example", 8080, Operations::CONNECT)
+        .unwrap();
+    catalog
+        .check_name("localhost", 80, Operations::BIND)
+        .unwrap();
+    assert_denied(catalog.check_name("localhost", 8080, Operations::BIND));
+}
+
+#[test]
+fn policy_random_round_trip() {
+    let mut rng = StdRng::seed_from_u64(0x901c);
+    let all = [
+        Operations::BIND,
+        Operations::CONNECT,
+        Operations::UDP_SEND,
+        Operations::UDP_RECEIVE,
+    ];
+    for _ in 0..1000 {
+        let mut catalog = Catalog::new();
+        for _ in 0..rng.gen_range(0, 4) {
+            let (start, end) = random_ports(&mut rng);
+            let mut ops = Operations::empty();
+            for op in &all {
+                if rng.gen() {
+                    ops |= *op;
+                }
+            }
+            unsafe {
+                catalog.insert_ip_net_operations(random_net(&mut rng), start, end, ops);
+            }
+        }
+        let printed = catalog.to_policy();
+        let reparsed = unsafe { Catalog::from_policy(&printed) }.unwrap();
+        assert_eq!(reparsed.to_policy(), printed);
+        for _ in 0..100 {
+            let addr = random_addr(&mut rng);
+            let ops = all[rng.gen_range(0, all.len())];
+            assert_eq!(
+                catalog.check_addr(&addr, ops).is_ok(),
+                reparsed.check_addr(&addr, ops).is_ok()
+            );
+        }
+    }
+}
+
+#[test]
+fn policy_errors() {
+    for (policy, line, column, message) in &[
+        ("10.0.0.0/33 80", 1, 1, "invalid address"),
+        ("\n  not/a/name 80", 2, 3, "invalid address"),
+        ("10.0.0.0/8", 1, 11, "expected ports"),
+        ("10.0.0.0/8 # 80", 1, 16, "expected ports"),
+        ("10.0.0.0/8 70000", 1, 12, "invalid ports"),
+        ("10.0.0.0/8 90-80", 1, 12, "invalid ports"),
+        (
+            "# ok\n10.0.0.0/8 80 bind,conect",
+            2,
+            20,
+            "unknown operation",
+        ),
+        ("10.0.0.0/8 80 bind extra", 1, 20, "unexpected token"),
+        ("é 80", 1, 1, "invalid address"),
+        ("10.0.0.256 80", 1, 1, "invalid address"),
+        ("# typo\n  300.1.1.1 80", 2, 3, "invalid address"),
+        ("*.1.2.3 80", 1, 1, "invalid address"),
+        ("fe80::1::2 80", 1, 1, "invalid address"),
+        ("10.0.0.0/8 80 udp-send,,bind", 1, 24, "unknown operation"),
+    ] {
+        let err = match unsafe { Catalog::from_policy(policy) } {
+            Ok(_) => panic!("{:?} should fail to parse", policy),
+            Err(err) => err,
+        };
+        assert_eq!(
+            (err.line(), err.column(), err.message()),
+            (*line, *column, *message),
+            "{:?}",
+            policy
+        );
+        assert_eq!(err.to_string(), format!("{}:{}: {}", line, column, message));
+        assert_eq!(io::Error::from(err).kind(), io::ErrorKind::InvalidData);
+    }
+}
+
+/// Construct a peer filter which accepts only connections from `port` on
+/// localhost, and which records rejected peers in `rejected`.
+fn port_filter(port: u16, rejected: &Arc<Mutex<Vec<SocketAddr>>>) -> PeerFilter {
+    let mut catalog = Catalog::new();
+    unsafe {
+        catalog.insert_ip_net_operations(localhost_net(), port, port, Operations::ACCEPT);
+    }
+    let mut peer_filter = catalog.peer_filter();
+    let rejected = Arc::clone(rejected);
+    peer_filter.on_reject(move |addr| rejected.lock().unwrap().push(addr));
+    peer_filter
+}
+
+fn assert_closed(stream: &mut cap_std::net::TcpStream) {
+    let mut buf = [0_u8; 1];
+    match stream.read(&mut buf) {
+        Ok(n) => assert_eq!(n, 0),
+        Err(e) => assert_eq!(e.kind(), io::ErrorKind::ConnectionReset),
+    }
+}
+
+#[test]
+fn tcp_peer_filter() {
+    let mut catalog = Catalog::new();
+    unsafe {
+        catalog.insert_ip_net_port_any(localhost_net());
+    }
+    let mut listener = catalog.bind_tcp_listener((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let addr = listener.local_addr().unwrap();
+    let rejected = Arc::new(Mutex::new(Vec::new()));
+
+    // `accept` skips, closes, and reports disallowed peers.
+    let mut denied = catalog.connect_tcp_stream(addr).unwrap();
+    let allowed = catalog.connect_tcp_stream(addr).unwrap();
+    let allowed_addr = allowed.local_addr().unwrap();
+    listener.set_peer_filter(Some(port_filter(allowed_addr.port(), &rejected)));
+    let (_accepted, peer) = listener.accept().unwrap();
+    assert_eq!(peer, allowed_addr);
+    assert_eq!(*rejected.lock().unwrap(), [denied.local_addr().unwrap()]);
+    assert_closed(&mut denied);
+
+    // So does `incoming`, and clones of the listener.
+    rejected.lock().unwrap().clear();
+    let mut denied = catalog.connect_tcp_stream(addr).unwrap();
+    let mut allowed = catalog.connect_tcp_stream(addr).unwrap();
+    let allowed_addr = allowed.local_addr().unwrap();
+    listener.set_peer_filter(Some(port_filter(allowed_addr.port(), &rejected)));
+    let clone = listener.try_clone().unwrap();
+    let mut accepted = clone.incoming().next().unwrap().unwrap();
+    allowed.write_all(b"x").unwrap();
+    let mut buf = [0_u8; 1];
+    accepted.read_exact(&mut buf).unwrap();
+    assert_eq!(*rejected.lock().unwrap(), [denied.local_addr().unwrap()]);
+    assert_closed(&mut denied);
+
+    // Filters may also be sets of `IpNet`s.
+    let peer_filter = PeerFilter::from_ip_nets(vec!["10.0.0.0/8".parse().unwrap()]);
+    assert!(peer_filter.permits(&"10.1.2.3:4".parse().unwrap()));
+    assert!(!peer_filter.permits(&"127.0.0.1:4".parse().unwrap()));
+    listener.set_peer_filter(Some(peer_filter));
+    listener.set_nonblocking(true).unwrap();
+    let mut denied = catalog.connect_tcp_stream(addr).unwrap();
+    match listener.accept() {
+        Ok(_) => panic!("expected WouldBlock"),
+        Err(e) => assert_eq!(e.kind(), io::ErrorKind::WouldBlock),
+    }
+    assert_closed(&mut denied);
+
+    // Removing the filter accepts everyone again.
+    listener.set_peer_filter(None);
+    listener.set_nonblocking(false).unwrap();
+    let stream = catalog.connect_tcp_stream(addr).unwrap();
+    let (_accepted, peer) = listener.accept().unwrap();
+    assert_eq!(peer, stream.local_addr().unwrap());
+}
+
+#[async_std::test]
+async fn async_tcp_peer_filter() {
+    use async_std::{
+        io::{ReadExt, WriteExt},
+        stream::StreamExt,
+    };
+
+    let mut catalog = cap_async_std::net::Catalog::new();
+    unsafe {
+        catalog.insert_ip_net_port_any(localhost_net());
+    }
+    let mut listener = catalog
+        .bind_tcp_listener((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    let rejected = Arc::new(Mutex::new(Vec::new()));
+
+    let mut denied = catalog.connect_tcp_stream(addr).await.unwrap();
+    let allowed = catalog.connect_tcp_stream(addr).await.unwrap();
+    let allowed_addr = allowed.local_addr().unwrap();
+    let mut accept_catalog = cap_async_std::net::Catalog::new();
+    unsafe {
+        accept_catalog.insert_ip_net_operations(
+            localhost_net(),
+            allowed_addr.port(),
+            allowed_addr.port(),
+            Operations::ACCEPT,
+        );
+    }
+    let mut peer_filter = accept_catalog.peer_filter();
+    let hook_rejected = Arc::clone(&rejected);
+    peer_filter.on_reject(move |addr| hook_rejected.lock().unwrap().push(addr));
+    listener.set_peer_filter(Some(peer_filter));
+    let (_accepted, peer) = listener.accept().await.unwrap();
+    assert_eq!(peer, allowed_addr);
+    assert_eq!(*rejected.lock().unwrap(), [denied.local_addr().unwrap()]);
+    let mut buf = [0_u8; 1];
+    if let Ok(n) = denied.read(&mut buf).await {
+        assert_eq!(n, 0);
+    }
+
+    rejected.lock().unwrap().clear();
+    let denied = catalog.connect_tcp_stream(addr).await.unwrap();
+    let mut allowed = catalog.connect_tcp_stream(addr).await.unwrap();
+    let allowed_addr = allowed.local_addr().unwrap();
+    listener.set_peer_filter(Some(port_filter(allowed_addr.port(), &rejected)));
+    let mut accepted = listener.incoming().next().await.unwrap().unwrap();
+    allowed.write_all(b"x").await.unwrap();
+    accepted.read_exact(&mut buf).await.unwrap();
+    assert_eq!(*rejected.lock().unwrap(), [denied.local_addr().unwrap()]);
+}
+
+/// Return a loopback address on which nothing is listening.
+fn closed_addr(ip: std::net::IpAddr) -> SocketAddr {
+    let listener = std::net::TcpListener::bind((ip, 0)).unwrap();
+    listener.local_addr().unwrap()
+}
+
+#[test]
+fn happy_eyeballs() {
+    let v6_net: IpNet = "::1/128".parse().unwrap();
+    let mut catalog = Catalog::new();
+    unsafe {
+        catalog
+            .insert_ip_net_port_any(localhost_net())
+            .insert_ip_net_port_any(v6_net);
+    }
+    let timeout = std::time::Duration::from_secs(10);
+    let listener = catalog.bind_tcp_listener((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let addr = listener.local_addr().unwrap();
+    let closed_v4 = closed_addr(Ipv4Addr::LOCALHOST.into());
+    let closed_v6 = SocketAddr::from((Ipv6Addr::LOCALHOST, closed_v4.port()));
+
+    // Failed candidates are skipped, in either family.
+    let addrs = [closed_v6, closed_v4, addr];
+    let mut stream = catalog
+        .connect_happy_eyeballs_tcp_stream(&addrs[..], timeout)
+        .unwrap();
+    stream.write_all(b"x").unwrap();
+    let (mut accepted, _) = listener.accept().unwrap();
+    let mut buf = [0_u8; 1];
+    accepted.read_exact(&mut buf).unwrap();
+
+    // Addresses outside the catalog are never attempted.
+    let outside = SocketAddr::from((Ipv4Addr::new(192, 0, 2, 1), addr.port()));
+    let restricted = catalog.restrict(localhost_net(), addr.port(), addr.port());
+    restricted
+        .connect_happy_eyeballs_tcp_stream(&[outside, closed_v4, addr][..], timeout)
+        .unwrap();
+    listener.accept().unwrap();
+    assert_denied(restricted.connect_happy_eyeballs_tcp_stream(&[outside, closed_v4][..], timeout));
+
+    // When every candidate fails, the last error is reported.
+    match catalog.connect_happy_eyeballs_tcp_stream(&[closed_v4][..], timeout) {
+        Ok(_) => panic!("expected ConnectionRefused"),
+        Err(e) => assert_eq!(e.kind(), io::ErrorKind::ConnectionRefused),
+    }
+
+    // The timeout applies to the whole attempt. TEST-NET addresses are
+    // never routed, so they either hang or fail immediately.
+    let mut blackhole = catalog.clone();
+    unsafe {
+        blackhole.insert_ip_net_port_any("192.0.2.0/24".parse().unwrap());
+    }
+    let unreachable = [
+        SocketAddr::from((Ipv4Addr::new(192, 0, 2, 1), 80)),
+        SocketAddr::from((Ipv4Addr::new(192, 0, 2, 2), 80)),
+        SocketAddr::from((Ipv4Addr::new(192, 0, 2, 3), 80)),
+    ];
+    let start = std::time::Instant::now();
+    assert!(blackhole
+        .connect_happy_eyeballs_tcp_stream(&unreachable[..], std::time::Duration::from_millis(300))
+        .is_err());
+    assert!(start.elapsed() < std::time::Duration::from_secs(2));
+}
+
+#[async_std::test]
+async fn async_happy_eyeballs() {
+    use async_std::io::{ReadExt, WriteExt};
+
+    let mut catalog = cap_async_std::net::Catalog::new();
+    unsafe {
+        catalog.insert_ip_net_port_any(localhost_net());
+    }
+    let timeout = std::time::Duration::from_secs(10);
+    let listener = catalog
+        .bind_tcp_listener((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    let closed = closed_addr(Ipv4Addr::LOCALHOST.into());
+    let outside = SocketAddr::from((Ipv4Addr::new(192, 0, 2, 1), addr.port()));
+
+    let mut stream = catalog
+        .connect_happy_eyeballs_tcp_stream(&[outside, closed, addr][..], timeout)
+        .await
+        .unwrap();
+    stream.write_all(b"x").await.unwrap();
+    let (mut accepted, _) = listener.accept().await.unwrap();
+    let mut buf = [0_u8; 1];
+    accepted.read_exact(&mut buf).await.unwrap();
+
+    assert_denied(
+        catalog
+            .connect_happy_eyeballs_tcp_stream(outside, timeout)
+            .await,
+    );
+
+    // The timeout applies to the whole attempt, and abandons the attempts
+    // still pending.
+    unsafe {
+        catalog.insert_ip_net_port_any("192.0.2.0/24".parse().unwrap());
+    }
+    let unreachable = [
+        SocketAddr::from((Ipv4Addr::new(192, 0, 2, 1), 80)),
+        SocketAddr::from((Ipv4Addr::new(192, 0, 2, 2), 80)),
+    ];
+    let start = std::time::Instant::now();
+    assert!(catalog
+        .connect_happy_eyeballs_tcp_stream(&unreachable[..], std::time::Duration::from_millis(300))
+        .await
+        .is_err());
+    assert!(start.elapsed() < std::time::Duration::from_secs(2));
+}
+
+#[test]
+fn socket_options() {
+    use cap_std::net::{TcpListenerOptions, UdpSocketOptions};
+
+    let mut catalog = Catalog::new();
+    unsafe {
+        catalog
+            .insert_ip_net_port_any(localhost_net())
+            .insert_ip_net_port_any("::1/128".parse().unwrap());
+    }
+
+    let mut tcp_options = TcpListenerOptions::new();
+    tcp_options
+        .backlog(16)
+        .recv_buffer_size(64 * 1024)
+        .send_buffer_size(64 * 1024);
+    let listener = catalog
+        .bind_tcp_listener_with((Ipv4Addr::LOCALHOST, 0), &tcp_options)
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut stream = catalog.connect_tcp_stream(addr).unwrap();
+    stream.write_all(b"x").unwrap();
+    let (mut accepted, _) = listener.accept().unwrap();
+    let mut buf = [0_u8; 1];
+    accepted.read_exact(&mut buf).unwrap();
+
+    // Without `SO_REUSEPORT`, a second listener can't share the port.
+    assert_eq!(
+        catalog
+            .bind_tcp_listener_with(addr, &tcp_options)
+            .map(drop)
+            .unwrap_err()
+            .kind(),
+        io::ErrorKind::AddrInUse
+    );
+
+    let outside = SocketAddr::from((Ipv4Addr::new(192, 0, 2, 1), 0));
+    assert_denied(catalog.bind_tcp_listener_with(outside, &tcp_options));
+    assert_denied(catalog.bind_udp_socket_with(outside, &UdpSocketOptions::new()));
+
+    assert_denied(catalog.bind_tcp_listener_with(
+        (Ipv6Addr::UNSPECIFIED, 0),
+        TcpListenerOptions::new().only_v6(true),
+    ));
+
+    // An IPv6-only listener leaves the IPv4 port free.
+    if let Ok(v6) = catalog.bind_tcp_listener_with(
+        (Ipv6Addr::LOCALHOST, 0),
+        TcpListenerOptions::new().only_v6(true),
+    ) {
+        let port = v6.local_addr().unwrap().port();
+        catalog
+            .bind_tcp_listener_with((Ipv4Addr::LOCALHOST, port), &TcpListenerOptions::new())
+            .unwrap();
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn socket_options_reuse_port() {
+    use cap_std::net::{TcpListenerOptions, UdpSocketOptions};
+
+    let mut catalog = Catalog::new();
+    unsafe {
+        catalog.insert_ip_net_port_any(localhost_net());
+    }
+
+    let mut tcp_options = TcpListenerOptions::new();
+    tcp_options.reuse_port(true);
+    let first = catalog
+        .bind_tcp_listener_with((Ipv4Addr::LOCALHOST, 0), &tcp_options)
+        .unwrap();
+    let addr = first.local_addr().unwrap();
+    let second = catalog.bind_tcp_listener_with(addr, &tcp_options).unwrap();
+    assert_eq!(second.local_addr().unwrap(), addr);
+
+    let mut udp_options = UdpSocketOptions::new();
+    udp_options.reuse_port(true).recv_buffer_size(64 * 1024);
+    let first = catalog
+        .bind_udp_socket_with((Ipv4Addr::LOCALHOST, 0), &udp_options)
+        .unwrap();
+    let addr = first.local_addr().unwrap();
+    let second = catalog.bind_udp_socket_with(addr, &udp_options).unwrap();
+    assert_eq!(second.local_addr().unwrap(), addr);
+    assert_eq!(
+        catalog
+            .bind_udp_socket_with(addr, &UdpSocketOptions::new())
+            .map(drop)
+            .unwrap_err()
+            .kind(),
+        io::ErrorKind::AddrInUse
+    );
+}
+
+#[async_std::test]
+async fn async_socket_options() {
+    use async_std::io::{ReadExt, WriteExt};
+    use cap_async_std::net::{TcpListenerOptions, UdpSocketOptions};
+
+    let mut catalog = cap_async_std::net::Catalog::new();
+    unsafe {
+        catalog.insert_ip_net_port_any(localhost_net());
+    }
+    let listener = catalog
+        .bind_tcp_listener_with(
+            (Ipv4Addr::LOCALHOST, 0),
+            TcpListenerOptions::new().backlog(4),
+        )
+        .await
+        .unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut stream = catalog.connect_tcp_stream(addr).await.unwrap();
+    stream.write_all(b"x").await.unwrap();
+    let (mut accepted, _) = listener.accept().await.unwrap();
+    let mut buf = [0_u8; 1];
+    accepted.read_exact(&mut buf).await.unwrap();
+
+    let receiver = catalog
+        .bind_udp_socket_with(
+            (Ipv4Addr::LOCALHOST, 0),
+            UdpSocketOptions::new().recv_buffer_size(64 * 1024),
+        )
+        .await
+        .unwrap();
+    let sender = catalog
+        .bind_udp_socket_with((Ipv4Addr::LOCALHOST, 0), &UdpSocketOptions::new())
+        .await
+        .unwrap();
+    sender
+        .send_to(b"ping", receiver.local_addr().unwrap())
+        .await
+        .unwrap();
+    let mut buf = [0_u8; 4];
+    let (n, _) = receiver.recv_from(&mut buf).await.unwrap();
+    assert_eq!(&buf[..n], b"ping");
+}
@@ -0,0 +1,114 @@
+// This file contains tests for `cap_fs_ext::OpenOptionsResolveExt`.
+#![cfg(unix)]
+
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::{FollowSymlinks, MetadataExt, OpenOptionsFollowExt, OpenOptionsResolveExt};
+use cap_std::fs::{Dir, OpenOptions};
+use std::{io, os::unix::fs::OpenOptionsExt};
+use sys_common::{io::tmpdir, symlink_supported};
+
+fn no_xdev_options() -> OpenOptions {
+    let mut options = OpenOptions::new();
+    options.read(true).no_xdev(true);
+    options
+}
+
+fn no_symlinks_options() -> OpenOptions {
+    let mut options = OpenOptions::new();
+    options.read(true).no_symlinks(true);
+    options
+}
+
+#[test]
+fn no_xdev_same_device() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a/b"));
+    check!(tmpdir.write("a/b/file", b"hello"));
+
+    check!(tmpdir.open_with("a/b/file", &no_xdev_options()));
+    check!(tmpdir.open_with("a/../a/./b/file", &no_xdev_options()));
+    check!(tmpdir.open_with(".", &no_xdev_options()));
+
+    let mut options = no_xdev_options();
+    options.write(true).create(true);
+    check!(tmpdir.open_with("a/new", &options));
+
+    if symlink_supported() {
+        check!(tmpdir.symlink("a/b", "link"));
+        check!(tmpdir.open_with("link/file", &no_xdev_options()));
+    }
+
+    // Escaping is still an escape attempt.
+    let sub = check!(tmpdir.open_dir("a"));
+    assert!(sub.open_with("../a/b/file", &no_xdev_options()).is_err());
+}
+
+/// Mount points can't be created in an unprivileged test, so use "/proc",
+/// which is always its own mount on Linux.
+#[cfg(target_os = "linux")]
+#[test]
+fn no_xdev_mount_point() {
+    let root = check!(unsafe { Dir::open_ambient_dir("/") });
+    if check!(root.dir_metadata()).dev() == check!(root.metadata("proc")).dev() {
+        return;
+    }
+
+    // Without `no_xdev`, crossing into "/proc" is fine.
+    check!(root.open("proc/self/status"));
+
+    for path in &["proc", "proc/self/status", "proc/../proc/version"] {
+        let err = root.open_with(path, &no_xdev_options()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied, "{}", path);
+    }
+
+    // Opening "/proc" itself with `O_PATH` also crosses, since a mount point
+    // resolves to the root of the mounted filesystem.
+    let mut options = no_xdev_options();
+    options.custom_flags(libc::O_PATH);
+    assert!(root.open_with("proc", &options).is_err());
+}
+
+#[test]
+fn no_symlinks() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir_all("a/b"));
+    check!(tmpdir.write("a/b/file", b"hello"));
+
+    check!(tmpdir.open_with("a/b/file", &no_symlinks_options()));
+    check!(tmpdir.open_with("a/../a/b/file", &no_symlinks_options()));
+
+    if !symlink_supported() {
+        return;
+    }
+
+    check!(tmpdir.symlink("a/b", "dir_link"));
+    check!(tmpdir.symlink("b/file", "a/file_link"));
+
+    // Without `no_symlinks`, symlinks inside the tree are fine.
+    check!(tmpdir.open("dir_link/file"));
+    check!(tmpdir.open("a/file_link"));
+
+    for path in &["dir_link/file", "a/file_link", "dir_link/../b/file"] {
+        let err = tmpdir.open_with(path, &no_symlinks_options()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied, "{}", path);
+    }
+
+    // The same goes for `O_PATH`, which is emulated on some paths.
+    let mut options = no_symlinks_options();
+    options.custom_flags(libc::O_PATH);
+    assert!(tmpdir.open_with("dir_link/file", &options).is_err());
+    assert!(tmpdir.open_with("a/file_link", &options).is_err());
+
+    // A symlink in the last component that isn't being followed is still
+    // opened as before.
+    options.follow(FollowSymlinks::No);
+    #[cfg(target_os = "linux")]
+    check!(tmpdir.open_with("a/file_link", &options));
+    assert!(tmpdir.open_with("dir_link/file", &options).is_err());
+    let mut options = no_symlinks_options();
+    options.follow(FollowSymlinks::No);
+    let err = tmpdir.open_with("a/file_link", &options).unwrap_err();
+    assert_ne!(err.kind(), io::ErrorKind::PermissionDenied);
+}
@@ -0,0 +1,94 @@
+// This file contains tests for `cap_fs_ext::DirExt::set_owner` and
+// `File::set_owner`.
+#![cfg(unix)]
+
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::{DirExt, FollowSymlinks};
+use std::os::unix::fs::MetadataExt;
+use sys_common::io::tmpdir;
+
+fn is_root() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+#[test]
+fn set_owner_unchanged() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("dir"));
+    check!(tmpdir.write("dir/file", b""));
+    let (uid, gid) = (unsafe { libc::geteuid() }, unsafe { libc::getegid() });
+
+    // Setting the owner to the current owner, or leaving it unchanged, is
+    // always allowed.
+    check!(tmpdir.set_owner("dir/file", Some(uid), Some(gid), FollowSymlinks::Yes));
+    check!(tmpdir.set_owner("dir/file", None, None, FollowSymlinks::No));
+    check!(tmpdir.set_owner("dir", None, Some(gid), FollowSymlinks::Yes));
+    let file = check!(tmpdir.open("dir/file"));
+    check!(file.set_owner(Some(uid), None));
+
+    let metadata = check!(tmpdir.metadata("dir/file"));
+    assert_eq!((metadata.uid(), metadata.gid()), (uid, gid));
+
+    error_contains!(
+        tmpdir.set_owner("missing", None, None, FollowSymlinks::Yes),
+        "No such file"
+    );
+}
+
+#[test]
+fn set_owner_as_root() {
+    if !is_root() {
+        return;
+    }
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b""));
+    check!(tmpdir.symlink("file", "link"));
+
+    // Following the symlink changes the target.
+    check!(tmpdir.set_owner("link", Some(1234), Some(5678), FollowSymlinks::Yes));
+    let metadata = check!(tmpdir.metadata("file"));
+    assert_eq!((metadata.uid(), metadata.gid()), (1234, 5678));
+    assert_eq!(check!(tmpdir.symlink_metadata("link")).uid(), 0);
+
+    // Not following it changes the symlink itself.
+    check!(tmpdir.set_owner("link", Some(4321), None, FollowSymlinks::No));
+    assert_eq!(check!(tmpdir.symlink_metadata("link")).uid(), 4321);
+    assert_eq!(check!(tmpdir.metadata("file")).uid(), 1234);
+
+    // `None` leaves the existing id alone.
+    let file = check!(tmpdir.open("file"));
+    check!(file.set_owner(None, Some(42)));
+    let metadata = check!(file.metadata());
+    assert_eq!((metadata.uid(), metadata.gid()), (1234, 42));
+}
+
+#[test]
+fn set_owner_sandboxed() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("inner"));
+    check!(tmpdir.write("outside", b""));
+    check!(tmpdir.symlink("../outside", "inner/escape"));
+    let inner = check!(tmpdir.open_dir("inner"));
+
+    assert!(inner
+        .set_owner("../outside", None, None, FollowSymlinks::No)
+        .is_err());
+    assert!(inner
+        .set_owner("escape", None, None, FollowSymlinks::Yes)
+        .is_err());
+    assert!(inner
+        .set_owner("/tmp", None, None, FollowSymlinks::Yes)
+        .is_err());
+}
+
+#[async_std::test]
+async fn async_set_owner() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+    let file = check!(dir.create("file"));
+    let uid = unsafe { libc::geteuid() };
+    check!(file.set_owner(Some(uid), None));
+    check!(dir.set_owner("file", Some(uid), None, FollowSymlinks::No));
+}
@@ -0,0 +1,128 @@
+// This file contains tests for `cap_fs_ext::DirStatxExt`,
+// `cap_fs_ext::FileStatxExt`, and the `statx` fields of
+// `cap_fs_ext::MetadataExt`.
+#![cfg(target_os = "linux")]
+
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::{DirStatxExt, FileStatxExt, FollowSymlinks, MetadataExt, StatxFields, StatxSync};
+use sys_common::io::tmpdir;
+
+#[test]
+fn metadata_statx_fields() {
+    let tmpdir = tmpdir();
+    let a = check!(tmpdir.create("a"));
+    check!(tmpdir.write("b", b"contents"));
+
+    let a_metadata = check!(a.metadata());
+    let b_metadata = check!(tmpdir.metadata("b"));
+    let dir_metadata = check!(tmpdir.dir_metadata());
+
+    for metadata in &[&a_metadata, &b_metadata, &dir_metadata] {
+        assert!(metadata.statx_mask().contains(StatxFields::BASIC_STATS));
+        assert_eq!(
+            metadata.created().is_ok(),
+            metadata.statx_mask().contains(StatxFields::BTIME)
+        );
+        assert_eq!(
+            metadata.mnt_id().is_some(),
+            metadata.statx_mask().contains(StatxFields::MNT_ID)
+        );
+        assert_ne!(metadata.blksize(), 0);
+        assert!(!metadata.is_immutable());
+        assert!(!metadata.is_append_only());
+    }
+
+    // Everything is on the same mount.
+    assert_eq!(a_metadata.mnt_id(), b_metadata.mnt_id());
+    assert_eq!(a_metadata.mnt_id(), dir_metadata.mnt_id());
+    assert_eq!(b_metadata.len(), 8);
+}
+
+#[test]
+fn dir_statx() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+    check!(tmpdir.write("sub/file", b"contents"));
+
+    let metadata = check!(tmpdir.statx(
+        "sub/file",
+        FollowSymlinks::Yes,
+        StatxFields::SIZE | StatxFields::TYPE,
+        StatxSync::DontSync
+    ));
+    assert!(metadata
+        .statx_mask()
+        .contains(StatxFields::SIZE | StatxFields::TYPE));
+    assert!(metadata.is_file());
+    assert_eq!(metadata.len(), 8);
+
+    let metadata = check!(tmpdir.statx(
+        "sub",
+        FollowSymlinks::No,
+        StatxFields::ALL,
+        StatxSync::Force
+    ));
+    assert!(metadata.is_dir());
+
+    assert!(tmpdir
+        .statx(
+            "sub/missing",
+            FollowSymlinks::Yes,
+            StatxFields::ALL,
+            StatxSync::AsStat
+        )
+        .is_err());
+}
+
+#[test]
+fn dir_statx_escape() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.create_dir("sub"));
+    let sub = check!(tmpdir.open_dir("sub"));
+
+    for path in &["..", "/", "../sub"] {
+        assert!(sub
+            .statx(
+                path,
+                FollowSymlinks::Yes,
+                StatxFields::ALL,
+                StatxSync::AsStat
+            )
+            .is_err());
+    }
+
+    if sys_common::symlink_supported() {
+        check!(sub.symlink("..", "parent"));
+        assert!(sub
+            .statx(
+                "parent",
+                FollowSymlinks::Yes,
+                StatxFields::ALL,
+                StatxSync::AsStat
+            )
+            .is_err());
+        let metadata = check!(sub.statx(
+            "parent",
+            FollowSymlinks::No,
+            StatxFields::ALL,
+            StatxSync::AsStat
+        ));
+        assert!(metadata.file_type().is_symlink());
+    }
+}
+
+#[test]
+fn file_statx() {
+    let tmpdir = tmpdir();
+    check!(tmpdir.write("file", b"hello"));
+    let file = check!(tmpdir.open("file"));
+
+    let metadata = check!(file.statx(StatxFields::SIZE, StatxSync::DontSync));
+    assert!(metadata.statx_mask().contains(StatxFields::SIZE));
+    assert_eq!(metadata.len(), 5);
+
+    let full = check!(file.statx(StatxFields::ALL, StatxSync::AsStat));
+    assert_eq!(full.mnt_id(), check!(file.metadata()).mnt_id());
+}
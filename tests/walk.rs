@@ -0,0 +1,305 @@
+#[macro_use]
+mod sys_common;
+
+use cap_std::fs::Dir;
+use std::path::{Path, PathBuf};
+use sys_common::{io::tmpdir, symlink_supported};
+
+fn paths(walk: &mut cap_std::fs::Walk) -> Vec<PathBuf> {
+    walk.map(|entry| check!(entry).into_path()).collect()
+}
+
+fn sorted_paths(walk: &mut cap_std::fs::Walk) -> Vec<PathBuf> {
+    let mut paths = paths(walk);
+    paths.sort();
+    paths
+}
+
+fn p(paths: &[&str]) -> Vec<PathBuf> {
+    paths.iter().map(PathBuf::from).collect()
+}
+
+/// Create this tree:
+///
+/// ```text
+/// a/
+/// a/b/
+/// a/b/c.txt
+/// a/d.txt
+/// e/
+/// f.txt
+/// ```
+fn make_tree(dir: &Dir) {
+    check!(dir.create_dir_all("a/b"));
+    check!(dir.create_dir("e"));
+    check!(dir.write("a/b/c.txt", b"c"));
+    check!(dir.write("a/d.txt", b"d"));
+    check!(dir.write("f.txt", b"f"));
+}
+
+#[test]
+fn walk_basic() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    let all = p(&["a", "a/b", "a/b/c.txt", "a/d.txt", "e", "f.txt"]);
+    assert_eq!(sorted_paths(&mut check!(tmpdir.walk())), all);
+
+    // Sorted walks are in pre-order, with each directory sorted.
+    assert_eq!(paths(check!(tmpdir.walk()).sort_by_file_name()), all);
+    assert_eq!(
+        paths(
+            tmpdir
+                .walk()
+                .unwrap()
+                .sort_by(|a, b| b.file_name().cmp(&a.file_name()))
+        ),
+        p(&["f.txt", "e", "a", "a/d.txt", "a/b", "a/b/c.txt"])
+    );
+
+    // Depths and file types are reported.
+    for entry in check!(tmpdir.walk()) {
+        let entry = check!(entry);
+        assert_eq!(entry.depth(), entry.path().components().count());
+        assert_eq!(
+            entry.file_type().is_dir(),
+            entry.path().extension().is_none()
+        );
+        assert!(!entry.path_is_symlink());
+        assert_eq!(entry.file_name(), entry.path().file_name().unwrap());
+    }
+}
+
+#[test]
+fn walk_depth() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    assert_eq!(
+        sorted_paths(check!(tmpdir.walk()).max_depth(1)),
+        p(&["a", "e", "f.txt"])
+    );
+    assert_eq!(
+        sorted_paths(check!(tmpdir.walk()).min_depth(2)),
+        p(&["a/b", "a/b/c.txt", "a/d.txt"])
+    );
+    assert_eq!(
+        sorted_paths(check!(tmpdir.walk()).min_depth(2).max_depth(2)),
+        p(&["a/b", "a/d.txt"])
+    );
+    assert!(paths(check!(tmpdir.walk()).max_depth(0)).is_empty());
+}
+
+#[test]
+fn walk_skip_current_dir() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    // Skipping a directory right after it's yielded skips its contents.
+    let mut walk = check!(tmpdir.walk());
+    walk.sort_by_file_name();
+    let mut seen = Vec::new();
+    while let Some(entry) = walk.next() {
+        let entry = check!(entry);
+        if entry.path() == Path::new("a/b") {
+            walk.skip_current_dir();
+        }
+        seen.push(entry.into_path());
+    }
+    assert_eq!(seen, p(&["a", "a/b", "a/d.txt", "e", "f.txt"]));
+
+    // Skipping after a file skips the rest of its directory.
+    let mut walk = check!(tmpdir.walk());
+    walk.sort_by_file_name();
+    let mut seen = Vec::new();
+    while let Some(entry) = walk.next() {
+        let entry = check!(entry);
+        if entry.path() == Path::new("a/b/c.txt") {
+            walk.skip_current_dir();
+        }
+        seen.push(entry.into_path());
+    }
+    assert_eq!(seen, p(&["a", "a/b", "a/b/c.txt", "a/d.txt", "e", "f.txt"]));
+}
+
+#[test]
+fn walk_skip_current_dir_at_max_depth() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    // Directories at `max_depth` aren't descended into anyway, so skipping
+    // them mustn't skip their siblings.
+    let mut walk = check!(tmpdir.walk());
+    walk.sort_by_file_name().max_depth(2);
+    let mut seen = Vec::new();
+    while let Some(entry) = walk.next() {
+        let entry = check!(entry);
+        if entry.file_type().is_dir() {
+            walk.skip_current_dir();
+        }
+        seen.push(entry.into_path());
+    }
+    assert_eq!(seen, p(&["a", "e", "f.txt"]));
+
+    let mut walk = check!(tmpdir.walk());
+    walk.sort_by_file_name().max_depth(2);
+    let mut seen = Vec::new();
+    while let Some(entry) = walk.next() {
+        let entry = check!(entry);
+        if entry.path() == Path::new("a/b") {
+            walk.skip_current_dir();
+        }
+        seen.push(entry.into_path());
+    }
+    assert_eq!(seen, p(&["a", "a/b", "a/d.txt", "e", "f.txt"]));
+}
+
+#[test]
+fn walk_entries_open() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+
+    let mut contents = Vec::new();
+    for entry in check!(tmpdir.walk()).sort_by_file_name() {
+        let entry = check!(entry);
+        if entry.file_type().is_file() {
+            let mut file = check!(entry.dir_entry().open());
+            let mut s = String::new();
+            check!(std::io::Read::read_to_string(&mut file, &mut s));
+            contents.push(s);
+        }
+    }
+    assert_eq!(contents, ["c", "d", "f"]);
+}
+
+#[test]
+#[cfg_attr(windows, ignore)] // loops can't be detected on Windows yet
+fn walk_symlinks() {
+    if !symlink_supported() {
+        return;
+    }
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+    check!(tmpdir.symlink("../e", "a/to_e"));
+    check!(tmpdir.symlink(".", "a/b/loop"));
+    check!(tmpdir.symlink("missing", "dangling"));
+
+    // By default, symlinks are yielded but not followed.
+    let entries = tmpdir
+        .walk()
+        .unwrap()
+        .sort_by_file_name()
+        .map(|entry| check!(entry))
+        .collect::<Vec<_>>();
+    let symlinks = entries
+        .iter()
+        .filter(|entry| entry.path_is_symlink())
+        .map(|entry| entry.path().to_path_buf())
+        .collect::<Vec<_>>();
+    assert_eq!(symlinks, p(&["a/b/loop", "a/to_e", "dangling"]));
+    assert!(entries
+        .iter()
+        .filter(|entry| entry.path_is_symlink())
+        .all(|entry| entry.file_type().is_symlink()));
+
+    // When following, symlinks to directories are descended into, and loops
+    // are reported as errors rather than followed.
+    check!(tmpdir.create_dir("e/inner"));
+    let mut walk = check!(tmpdir.walk());
+    walk.follow_symlinks(true).sort_by_file_name();
+    let mut seen = Vec::new();
+    let mut errors = 0;
+    for entry in walk {
+        match entry {
+            Ok(entry) => {
+                if entry.path() == Path::new("a/to_e") {
+                    assert!(entry.path_is_symlink());
+                    assert!(entry.file_type().is_dir());
+                }
+                seen.push(entry.into_path());
+            }
+            Err(e) => {
+                assert!(e.to_string().contains("loop"), "{}", e);
+                errors += 1;
+            }
+        }
+    }
+    assert_eq!(errors, 1);
+    assert!(seen.contains(&PathBuf::from("a/to_e/inner")));
+    assert!(seen.contains(&PathBuf::from("a/b/loop")));
+    assert!(!seen
+        .iter()
+        .any(|path| path.starts_with("a/b/loop") && path != &PathBuf::from("a/b/loop")));
+    assert!(seen.contains(&PathBuf::from("dangling")));
+}
+
+#[test]
+fn walk_stays_inside() {
+    if !symlink_supported() {
+        return;
+    }
+    let outer = tmpdir();
+    check!(outer.create_dir("inner"));
+    check!(outer.write("secret.txt", b"secret"));
+    check!(outer.symlink("..", "inner/up"));
+    check!(outer.symlink("../secret.txt", "inner/secret"));
+    let inner = check!(outer.open_dir("inner"));
+
+    let mut walk = check!(inner.walk());
+    walk.follow_symlinks(true);
+    for entry in walk.flatten() {
+        assert!(!entry.file_type().is_dir() || !entry.path_is_symlink());
+        assert!(!entry.file_type().is_file());
+        assert!(entry.dir_entry().open().is_err());
+    }
+}
+
+#[async_std::test]
+async fn async_walk() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+    check!(dir.create_dir_all("a/b"));
+    check!(dir.write("a/b/c.txt", b"c").await);
+    check!(dir.write("d.txt", b"d").await);
+
+    let paths = dir
+        .walk()
+        .unwrap()
+        .sort_by_file_name()
+        .map(|entry| check!(entry).into_path())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        ["a", "a/b", "a/b/c.txt", "d.txt"]
+            .iter()
+            .map(async_std::path::PathBuf::from)
+            .collect::<Vec<_>>()
+    );
+
+    let mut walk = check!(dir.walk());
+    walk.min_depth(2).max_depth(2);
+    let entry = check!(walk.next().unwrap());
+    assert_eq!(entry.path(), async_std::path::Path::new("a/b"));
+    assert_eq!(entry.depth(), 2);
+    assert!(walk.next().is_none());
+}
+
+#[cfg(feature = "fs_utf8")]
+#[test]
+fn walk_utf8() {
+    let tmpdir = tmpdir();
+    make_tree(&tmpdir);
+    let dir = cap_std::fs_utf8::Dir::from_cap_std(check!(tmpdir.try_clone()));
+
+    let mut walk = check!(dir.walk());
+    walk.sort_by_file_name();
+    let mut seen = Vec::new();
+    while let Some(entry) = walk.next() {
+        let entry = check!(entry);
+        if entry.path() == "a/b" {
+            walk.skip_current_dir();
+        }
+        seen.push(entry.into_path());
+    }
+    assert_eq!(seen, ["a", "a/b", "a/d.txt", "e", "f.txt"]);
+}
@@ -0,0 +1,153 @@
+// This file contains tests for `cap_fs_ext::DirXattrExt` and
+// `cap_fs_ext::FileXattrExt`.
+#![cfg(target_os = "linux")]
+
+#[macro_use]
+mod sys_common;
+
+use cap_fs_ext::{DirXattrExt, FileXattrExt, FollowSymlinks};
+use std::ffi::OsString;
+use sys_common::io::tmpdir;
+
+/// Returns `false` if the filesystem holding `tmpdir` doesn't support user
+/// extended attributes, in which case the tests are skipped.
+fn xattrs_supported(tmpdir: &cap_std::fs::Dir) -> bool {
+    check!(tmpdir.write("probe", b""));
+    match tmpdir.setxattr("probe", "user.probe", b"", FollowSymlinks::Yes) {
+        Err(e) if e.raw_os_error() == Some(libc::ENOTSUP) => false,
+        result => {
+            check!(result);
+            check!(tmpdir.remove_file("probe"));
+            true
+        }
+    }
+}
+
+fn assert_no_data<T: std::fmt::Debug>(result: std::io::Result<T>) {
+    assert_eq!(result.unwrap_err().raw_os_error(), Some(libc::ENODATA));
+}
+
+#[test]
+fn dir_xattrs() {
+    let tmpdir = tmpdir();
+    if !xattrs_supported(&tmpdir) {
+        return;
+    }
+    check!(tmpdir.create_dir("sub"));
+    check!(tmpdir.write("sub/file", b"contents"));
+
+    check!(tmpdir.setxattr("sub/file", "user.color", b"blue", FollowSymlinks::Yes));
+    check!(tmpdir.setxattr("sub/file", "user.empty", b"", FollowSymlinks::No));
+    assert_eq!(
+        check!(tmpdir.getxattr("sub/file", "user.color", FollowSymlinks::Yes)),
+        b"blue"
+    );
+    assert!(check!(tmpdir.getxattr("sub/file", "user.empty", FollowSymlinks::Yes)).is_empty());
+
+    let mut names = check!(tmpdir.listxattr("sub/file", FollowSymlinks::Yes));
+    names.retain(|name| name.to_str().unwrap().starts_with("user."));
+    names.sort();
+    assert_eq!(
+        names,
+        [OsString::from("user.color"), OsString::from("user.empty")]
+    );
+
+    // Setting an existing attribute replaces it.
+    check!(tmpdir.setxattr("sub/file", "user.color", b"green", FollowSymlinks::Yes));
+    assert_eq!(
+        check!(tmpdir.getxattr("sub/file", "user.color", FollowSymlinks::Yes)),
+        b"green"
+    );
+
+    check!(tmpdir.removexattr("sub/file", "user.color", FollowSymlinks::Yes));
+    assert_no_data(tmpdir.getxattr("sub/file", "user.color", FollowSymlinks::Yes));
+    assert_no_data(tmpdir.removexattr("sub/file", "user.color", FollowSymlinks::Yes));
+
+    // Directories have attributes too.
+    check!(tmpdir.setxattr("sub", "user.dir", b"yes", FollowSymlinks::Yes));
+    assert_eq!(
+        check!(tmpdir.getxattr("sub", "user.dir", FollowSymlinks::Yes)),
+        b"yes"
+    );
+}
+
+#[test]
+fn dir_xattrs_symlinks() {
+    let tmpdir = tmpdir();
+    if !xattrs_supported(&tmpdir) {
+        return;
+    }
+    check!(tmpdir.write("file", b""));
+    check!(tmpdir.symlink("file", "link"));
+    check!(tmpdir.setxattr("file", "user.name", b"file", FollowSymlinks::Yes));
+
+    // Following the symlink reaches the target.
+    assert_eq!(
+        check!(tmpdir.getxattr("link", "user.name", FollowSymlinks::Yes)),
+        b"file"
+    );
+
+    // Not following it operates on the symlink itself, which can't have user
+    // attributes.
+    assert!(tmpdir
+        .getxattr("link", "user.name", FollowSymlinks::No)
+        .is_err());
+    assert!(tmpdir
+        .setxattr("link", "user.name", b"link", FollowSymlinks::No)
+        .is_err());
+}
+
+#[test]
+fn dir_xattrs_sandboxed() {
+    let tmpdir = tmpdir();
+    if !xattrs_supported(&tmpdir) {
+        return;
+    }
+    check!(tmpdir.create_dir("inner"));
+    check!(tmpdir.write("outside", b""));
+    check!(tmpdir.symlink("../outside", "inner/escape"));
+    let inner = check!(tmpdir.open_dir("inner"));
+
+    assert!(inner
+        .setxattr("../outside", "user.x", b"", FollowSymlinks::Yes)
+        .is_err());
+    assert!(inner
+        .setxattr("escape", "user.x", b"", FollowSymlinks::Yes)
+        .is_err());
+    assert!(inner.listxattr("/tmp", FollowSymlinks::Yes).is_err());
+    assert_no_data(tmpdir.getxattr("outside", "user.x", FollowSymlinks::Yes));
+}
+
+#[test]
+fn file_xattrs() {
+    let tmpdir = tmpdir();
+    if !xattrs_supported(&tmpdir) {
+        return;
+    }
+    let file = check!(tmpdir.create("file"));
+    check!(file.setxattr("user.a", b"1"));
+    assert_eq!(check!(file.getxattr("user.a")), b"1");
+    assert!(check!(file.listxattr()).contains(&OsString::from("user.a")));
+    assert_eq!(
+        check!(tmpdir.getxattr("file", "user.a", FollowSymlinks::Yes)),
+        b"1"
+    );
+    check!(file.removexattr("user.a"));
+    assert_no_data(file.getxattr("user.a"));
+}
+
+#[async_std::test]
+async fn async_xattrs() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let dir = unsafe { cap_async_std::fs::Dir::open_ambient_dir(tmpdir.path()) }.unwrap();
+    let file = check!(dir.create("file"));
+    match file.setxattr("user.a", b"1") {
+        Err(e) if e.raw_os_error() == Some(libc::ENOTSUP) => return,
+        result => check!(result),
+    }
+    assert_eq!(
+        check!(dir.getxattr("file", "user.a", FollowSymlinks::Yes)),
+        b"1"
+    );
+    assert!(check!(dir.listxattr("file", FollowSymlinks::No)).contains(&OsString::from("user.a")));
+}